@@ -0,0 +1,122 @@
+//! Not-done items with a due date, grouped by how soon they are due. "This
+//! week" runs from after today up to and including Sunday.
+
+use serde::{Deserialize, Serialize};
+
+use crate::due;
+
+/// Most items an agenda lists, the soonest due first.
+pub const MAX_ITEMS: usize = 500;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct AgendaItem {
+    pub root: String,
+    pub path: Vec<String>,
+    pub title: String,
+    pub due: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct Agenda {
+    pub overdue: Vec<AgendaItem>,
+    pub today: Vec<AgendaItem>,
+    pub this_week: Vec<AgendaItem>,
+    pub later: Vec<AgendaItem>,
+}
+
+impl Agenda {
+    /// File `item` relative to `today` (days since 1970-01-01). Items whose
+    /// due isn't a date are skipped.
+    pub fn push(&mut self, item: AgendaItem, today: i64) {
+        let Some(date) = due::due_date(&item.due) else {
+            return;
+        };
+        let end_of_week = today + 6 - due::weekday(today);
+        let bucket = if date < today {
+            &mut self.overdue
+        } else if date == today {
+            &mut self.today
+        } else if date <= end_of_week {
+            &mut self.this_week
+        } else {
+            &mut self.later
+        };
+        bucket.push(item);
+    }
+
+    /// Order every bucket by due, then root and path.
+    pub fn sort(&mut self) {
+        for bucket in [
+            &mut self.overdue,
+            &mut self.today,
+            &mut self.this_week,
+            &mut self.later,
+        ] {
+            bucket.sort_by(|a, b| (&a.due, &a.root, &a.path).cmp(&(&b.due, &b.root, &b.path)));
+        }
+    }
+
+    /// Keep only the `max` soonest due items. Expects sorted buckets.
+    pub fn truncate(&mut self, mut max: usize) {
+        for bucket in [
+            &mut self.overdue,
+            &mut self.today,
+            &mut self.this_week,
+            &mut self.later,
+        ] {
+            bucket.truncate(max);
+            max -= bucket.len();
+        }
+    }
+
+    pub fn buckets(&self) -> [(&'static str, &[AgendaItem]); 4] {
+        [
+            ("Overdue", &self.overdue),
+            ("Today", &self.today),
+            ("This week", &self.this_week),
+            ("Later", &self.later),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn buckets_by_due() {
+        // A Wednesday.
+        let today = due::parse_date("2026-10-14").unwrap();
+        let mut agenda = Agenda::default();
+        for (key, due) in [
+            ("later", "2026-10-19"),
+            ("week", "2026-10-18"),
+            ("today", "2026-10-14T18:00"),
+            ("overdue", "2026-10-13"),
+            ("garbage", "soon"),
+        ] {
+            agenda.push(
+                AgendaItem {
+                    root: "kjuulh".into(),
+                    path: vec![key.into()],
+                    title: key.into(),
+                    due: due.into(),
+                },
+                today,
+            );
+        }
+
+        let titles =
+            |items: &[AgendaItem]| items.iter().map(|i| i.title.clone()).collect::<Vec<_>>();
+        assert_eq!(vec!["overdue"], titles(&agenda.overdue));
+        assert_eq!(vec!["today"], titles(&agenda.today));
+        assert_eq!(vec!["week"], titles(&agenda.this_week));
+        assert_eq!(vec!["later"], titles(&agenda.later));
+
+        agenda.sort();
+        agenda.truncate(2);
+        assert_eq!(vec!["overdue"], titles(&agenda.overdue));
+        assert_eq!(vec!["today"], titles(&agenda.today));
+        assert!(agenda.this_week.is_empty() && agenda.later.is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Ordered steps inside an item, each with its own done flag. They don't
+//! change the item's state; an item with every step ticked still has to be
+//! marked done. Users number entries from 1, so do the toggle RPC and the TUI.
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
+pub struct ChecklistEntry {
+    pub title: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl ChecklistEntry {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            done: false,
+        }
+    }
+}
+
+/// `[x] title` or `[ ] title`, as written in the editor frontmatter.
+impl Display for ChecklistEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mark = if self.done { 'x' } else { ' ' };
+        write!(f, "[{mark}] {}", self.title)
+    }
+}
+
+/// Takes what [`Display`] writes, optionally as a `- ` list item; a bare
+/// title is an entry not done yet.
+impl FromStr for ChecklistEntry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("- ").unwrap_or(s).trim_start();
+        let (done, title) = if let Some(title) = s.strip_prefix("[ ]") {
+            (false, title)
+        } else if let Some(title) = s.strip_prefix("[x]").or_else(|| s.strip_prefix("[X]")) {
+            (true, title)
+        } else {
+            (false, s)
+        };
+        match title.trim() {
+            "" => Err("a checklist entry needs a title".into()),
+            title => Ok(Self {
+                title: title.into(),
+                done,
+            }),
+        }
+    }
+}
+
+/// Flip the entry at `index`, counted from 0. False when there is none.
+pub fn toggle(entries: &mut [ChecklistEntry], index: usize) -> bool {
+    match entries.get_mut(index) {
+        Some(entry) => {
+            entry.done = !entry.done;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Unticks every entry, for items reset by a copy or a template.
+pub fn reset(entries: &mut [ChecklistEntry]) {
+    for entry in entries {
+        entry.done = false;
+    }
+}
+
+/// How many entries are done, and how many there are.
+pub fn progress(entries: &[ChecklistEntry]) -> (usize, usize) {
+    (entries.iter().filter(|e| e.done).count(), entries.len())
+}
+
+/// `done/total` such as `3/5`, or `None` without a checklist.
+pub fn summary(entries: &[ChecklistEntry]) -> Option<String> {
+    let (done, total) = progress(entries);
+    (total > 0).then(|| format!("{done}/{total}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_what_it_displays() {
+        for raw in ["[x] ship it", "[ ] write docs"] {
+            assert_eq!(raw, raw.parse::<ChecklistEntry>().unwrap().to_string());
+        }
+        assert_eq!(
+            Ok(ChecklistEntry {
+                title: "review".into(),
+                done: true
+            }),
+            "- [X]  review ".parse()
+        );
+        assert_eq!(Ok(ChecklistEntry::new("plain")), "plain".parse());
+        assert!("[ ]".parse::<ChecklistEntry>().is_err());
+    }
+
+    #[test]
+    fn toggles_and_summarises() {
+        let mut entries = vec![ChecklistEntry::new("one"), ChecklistEntry::new("two")];
+        assert_eq!(Some("0/2".to_string()), summary(&entries));
+
+        assert!(toggle(&mut entries, 1));
+        assert!(!toggle(&mut entries, 2));
+        assert_eq!(Some("1/2".to_string()), summary(&entries));
+
+        reset(&mut entries);
+        assert_eq!((0, 2), progress(&entries));
+        assert_eq!(None, summary(&[]));
+    }
+}
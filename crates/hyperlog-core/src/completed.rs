@@ -0,0 +1,191 @@
+//! When items were finished. An item's `completed_at` is stamped when it
+//! becomes done and cleared when it is reopened; a recurring item keeps the
+//! dates it was done on in `completions` instead.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{due, log::ItemState, template};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct CompletedItem {
+    pub root: String,
+    pub path: Vec<String>,
+    pub title: String,
+    /// `YYYY-MM-DDTHH:MM:SSZ`, or only the date for recurring completions.
+    pub completed_at: String,
+}
+
+/// Items completed between `since` and `until`, both inclusive, as days since
+/// 1970-01-01.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct Completed {
+    pub since: i64,
+    pub until: i64,
+    pub items: Vec<CompletedItem>,
+}
+
+impl Completed {
+    pub fn new(since: i64, until: i64) -> Self {
+        Self {
+            since,
+            until,
+            items: Vec::new(),
+        }
+    }
+
+    /// Add the completions of the item at `path` that fall in the range: when
+    /// it was marked done, and each occurrence of a recurring item.
+    pub fn push(
+        &mut self,
+        root: &str,
+        path: &[String],
+        title: &str,
+        completed_at: Option<&str>,
+        completions: &[String],
+    ) {
+        let in_range = |at: &&str| {
+            due::due_date(at).is_some_and(|date| (self.since..=self.until).contains(&date))
+        };
+        let entries = completed_at
+            .into_iter()
+            .chain(completions.iter().map(String::as_str))
+            .filter(in_range)
+            .map(|at| CompletedItem {
+                root: root.to_string(),
+                path: path.to_vec(),
+                title: title.to_string(),
+                completed_at: at.to_string(),
+            })
+            .collect::<Vec<_>>();
+        self.items.extend(entries);
+    }
+
+    /// Most recent first, then by root and path.
+    pub fn sort(&mut self) {
+        self.items.sort_by(|a, b| {
+            b.completed_at
+                .cmp(&a.completed_at)
+                .then_with(|| (&a.root, &a.path).cmp(&(&b.root, &b.path)))
+        });
+    }
+}
+
+/// `YYYY-MM-DDTHH:MM:SSZ` for seconds since the epoch.
+pub fn timestamp(secs: i64) -> String {
+    let time = secs.rem_euclid(86_400);
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        due::format_date(secs.div_euclid(86_400)),
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// The current UTC time, see [`timestamp`].
+pub fn now() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    timestamp(secs)
+}
+
+/// Keep `completed_at` in step with `state`: stamped with `now` when the item
+/// is done and wasn't already, cleared when it is anything else.
+pub fn track(state: &ItemState, completed_at: &mut Option<String>, now: impl FnOnce() -> String) {
+    match state {
+        ItemState::Done => {
+            completed_at.get_or_insert_with(now);
+        }
+        _ => *completed_at = None,
+    }
+}
+
+/// The first day of a report: a `YYYY-MM-DD` date, `today`, `yesterday`, or
+/// days or weeks before `today` such as `-7d` or `-2w`.
+pub fn parse_since(raw: &str, today: i64) -> Option<i64> {
+    match raw.trim() {
+        "today" => Some(today),
+        "yesterday" => due::add_days(today, -1),
+        raw => match template::relative_due_days(raw) {
+            Some(days) if days <= 0 => due::add_days(today, days),
+            Some(_) => None,
+            None => due::parse_date(raw),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tracks_completion_with_the_state() {
+        let mut completed_at = None;
+        track(&ItemState::Done, &mut completed_at, || "first".into());
+        track(&ItemState::Done, &mut completed_at, || "second".into());
+        assert_eq!(Some("first".to_string()), completed_at);
+
+        track(&ItemState::Cancelled, &mut completed_at, || "third".into());
+        assert_eq!(None, completed_at);
+    }
+
+    #[test]
+    fn collects_completions_in_range() {
+        let day = |raw| due::parse_date(raw).unwrap();
+        let mut completed = Completed::new(day("2026-10-12"), day("2026-10-18"));
+        let path = |key: &str| vec!["section".to_string(), key.to_string()];
+        completed.push(
+            "work",
+            &path("old"),
+            "Old",
+            Some("2026-10-11T23:59:59Z"),
+            &[],
+        );
+        completed.push(
+            "work",
+            &path("one"),
+            "One",
+            Some("2026-10-13T09:00:00Z"),
+            &[],
+        );
+        completed.push(
+            "home",
+            &path("plants"),
+            "Water plants",
+            None,
+            &["2026-10-05".into(), "2026-10-15".into()],
+        );
+        completed.sort();
+
+        let titles = completed
+            .items
+            .iter()
+            .map(|i| (i.title.as_str(), i.completed_at.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("Water plants", "2026-10-15"),
+                ("One", "2026-10-13T09:00:00Z")
+            ],
+            titles
+        );
+    }
+
+    #[test]
+    fn formats_timestamps_and_parses_since() {
+        assert_eq!("1970-01-02T01:01:01Z", timestamp(86_400 + 3661));
+
+        let today = due::parse_date("2026-10-18").unwrap();
+        assert_eq!(Some(today - 7), parse_since("-1w", today));
+        assert_eq!(Some(today - 1), parse_since("yesterday", today));
+        assert_eq!(
+            due::parse_date("2026-10-01"),
+            parse_since("2026-10-01", today)
+        );
+        assert_eq!(None, parse_since("+1d", today));
+        assert_eq!(None, parse_since("last week", today));
+        assert_eq!(None, parse_since("-9223372036854775807w", today));
+    }
+}
@@ -0,0 +1,140 @@
+//! Turning an item that grew into a project into a section, and back. An item
+//! becomes an empty section keeping its title and description as
+//! [`SectionMeta`]; its state, dates, tags and checklist are dropped. Only an
+//! empty section becomes an item, open and titled after the section.
+//! Dependencies on a converted item are left dangling, which don't block.
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::log::{GraphItem, ItemState, SectionMeta};
+
+/// What a node is converted to, named as in a view's `kind`.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Item,
+    Section,
+}
+
+impl FromStr for Kind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "item" => Ok(Self::Item),
+            "section" => Ok(Self::Section),
+            _ => anyhow::bail!("expected item or section, got: {s}"),
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Item => f.write_str("item"),
+            Self::Section => f.write_str("section"),
+        }
+    }
+}
+
+/// The metadata of the section an item becomes.
+pub fn section_meta(title: &str, description: &str) -> SectionMeta {
+    SectionMeta {
+        title: title.to_string(),
+        description: description.to_string(),
+        ..Default::default()
+    }
+}
+
+/// The title and description of the item a section stored under `key`
+/// becomes; the key stands in for a missing title.
+pub fn item_fields(key: &str, meta: &SectionMeta) -> (String, String) {
+    let title = match meta.title.as_str() {
+        "" => key.to_string(),
+        title => title.to_string(),
+    };
+    (title, meta.description.clone())
+}
+
+/// Convert `node`, stored under `key`, to `kind`.
+pub fn convert(key: &str, node: &GraphItem, kind: Kind) -> anyhow::Result<GraphItem> {
+    match (node, kind) {
+        (
+            GraphItem::Item {
+                title, description, ..
+            },
+            Kind::Section,
+        ) => Ok(GraphItem::Section {
+            meta: section_meta(title, description),
+            items: Default::default(),
+        }),
+        (GraphItem::Section { items, meta }, Kind::Item) => {
+            if !items.is_empty() {
+                anyhow::bail!("section {key} is not empty");
+            }
+            let (title, description) = item_fields(key, meta);
+            Ok(GraphItem::item(title, description, ItemState::NotDone))
+        }
+        (GraphItem::User(_), _) => anyhow::bail!("a root can't be converted"),
+        (_, Kind::Item) => anyhow::bail!("{key} is already an item"),
+        (_, Kind::Section) => anyhow::bail!("{key} is already a section"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn converts_an_item_to_a_section_and_back() {
+        let mut item = GraphItem::item(
+            "Launch v1.0".into(),
+            "Everything for the launch".into(),
+            ItemState::InProgress,
+        );
+        if let GraphItem::Item { due, tags, .. } = &mut item {
+            *due = Some("2026-11-01".into());
+            *tags = vec!["work".into()];
+        }
+
+        let section = convert("launch", &item, Kind::Section).unwrap();
+        assert_eq!(
+            GraphItem::Section {
+                meta: section_meta("Launch v1.0", "Everything for the launch"),
+                items: BTreeMap::new(),
+            },
+            section
+        );
+        assert!(convert("launch", &section, Kind::Section).is_err());
+
+        match convert("launch", &section, Kind::Item).unwrap() {
+            GraphItem::Item {
+                title,
+                description,
+                state,
+                due,
+                ..
+            } => {
+                assert_eq!("Launch v1.0", title);
+                assert_eq!("Everything for the launch", description);
+                assert_eq!(ItemState::NotDone, state);
+                assert_eq!(None, due);
+            }
+            _ => panic!("expected an item"),
+        }
+
+        let full = GraphItem::Section {
+            meta: SectionMeta::default(),
+            items: BTreeMap::from([("child".to_string(), item)]),
+        };
+        assert!(convert("launch", &full, Kind::Item).is_err());
+        assert_eq!(
+            ("launch".to_string(), String::new()),
+            item_fields("launch", &SectionMeta::default())
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! "Blocked by" edges between items of the same root. An item lists the
+//! root-relative dotted paths of the items it depends on, and is blocked while
+//! any of them is still open. Dependencies that no longer exist (archived,
+//! deleted) don't block. Moving or copying a subtree to another root keeps the
+//! edges inside it and drops those crossing its boundary, see [`carry`] and
+//! [`detach`], so a path never resolves against the wrong root.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::log::GraphItem;
+
+#[derive(Default, Debug)]
+struct Node {
+    closed: bool,
+    depends_on: Vec<String>,
+}
+
+/// Every item of a root with its dependencies, keyed by dotted path.
+#[derive(Default, Debug)]
+pub struct Dependencies {
+    items: BTreeMap<String, Node>,
+}
+
+impl Dependencies {
+    pub fn insert(&mut self, path: impl Into<String>, closed: bool, depends_on: Vec<String>) {
+        self.items.insert(path.into(), Node { closed, depends_on });
+    }
+
+    /// Collect the items under `root`, a root's [`GraphItem::User`].
+    pub fn from_graph(root: &GraphItem) -> Self {
+        fn walk(deps: &mut Dependencies, prefix: &str, item: &GraphItem) {
+            match item {
+                GraphItem::User(children)
+                | GraphItem::Section {
+                    items: children, ..
+                } => {
+                    for (key, child) in children {
+                        let path = if prefix.is_empty() {
+                            key.clone()
+                        } else {
+                            format!("{prefix}.{key}")
+                        };
+                        walk(deps, &path, child);
+                    }
+                }
+                GraphItem::Item {
+                    state, depends_on, ..
+                } => deps.insert(prefix, state.is_closed(), depends_on.clone()),
+            }
+        }
+
+        let mut deps = Self::default();
+        walk(&mut deps, "", root);
+        deps
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.items.contains_key(path)
+    }
+
+    pub fn depends_on(&self, item: &str) -> &[String] {
+        self.items
+            .get(item)
+            .map(|node| node.depends_on.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Whether `item` depending on `dependency` would close a loop, including
+    /// an item depending on itself.
+    pub fn would_cycle(&self, item: &str, dependency: &str) -> bool {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![dependency];
+        while let Some(path) = stack.pop() {
+            if path == item {
+                return true;
+            }
+            if !seen.insert(path) {
+                continue;
+            }
+            if let Some(node) = self.items.get(path) {
+                stack.extend(node.depends_on.iter().map(String::as_str));
+            }
+        }
+
+        false
+    }
+
+    /// The dependencies of `item` that are still open.
+    pub fn open_dependencies(&self, item: &str) -> Vec<&str> {
+        self.items
+            .get(item)
+            .map(|node| {
+                node.depends_on
+                    .iter()
+                    .filter(|dep| self.items.get(dep.as_str()).is_some_and(|d| !d.closed))
+                    .map(String::as_str)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn is_blocked(&self, item: &str) -> bool {
+        !self.open_dependencies(item).is_empty()
+    }
+
+    /// Every blocked item, in path order.
+    pub fn blocked(&self) -> Vec<&str> {
+        self.items
+            .keys()
+            .map(String::as_str)
+            .filter(|item| self.is_blocked(item))
+            .collect()
+    }
+}
+
+/// The part of `path` below `subtree`, empty for `subtree` itself; `None`
+/// when `path` is outside it.
+fn below<'a>(path: &'a str, subtree: &str) -> Option<&'a str> {
+    path.strip_prefix(subtree)
+        .filter(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Point dependencies on `src`, or anything below it, at `dest` after a
+/// rename or move within the root. Returns whether anything changed.
+pub fn retarget(depends_on: &mut [String], src: &str, dest: &str) -> bool {
+    let mut changed = false;
+    for dep in depends_on.iter_mut() {
+        let Some(rest) = below(dep, src) else {
+            continue;
+        };
+        *dep = format!("{dest}{rest}");
+        changed = true;
+    }
+
+    changed
+}
+
+/// The dependencies of an item in the subtree at `src` that now lives at
+/// `dest` in another root: edges within the subtree follow it, the rest are
+/// dropped. Returns whether anything changed.
+pub fn carry(depends_on: &mut Vec<String>, src: &str, dest: &str) -> bool {
+    let before = depends_on.len();
+    depends_on.retain(|dep| below(dep, src).is_some());
+    retarget(depends_on, src, dest) || depends_on.len() != before
+}
+
+/// Drop dependencies on `subtree`, or anything below it, after it left the
+/// root. Returns whether anything changed.
+pub fn detach(depends_on: &mut Vec<String>, subtree: &str) -> bool {
+    let before = depends_on.len();
+    depends_on.retain(|dep| below(dep, subtree).is_none());
+    depends_on.len() != before
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn deps() -> Dependencies {
+        let mut deps = Dependencies::default();
+        deps.insert("a", false, vec!["b".into()]);
+        deps.insert("b", false, vec!["c".into(), "gone".into()]);
+        deps.insert("c", true, Vec::new());
+        deps
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let deps = deps();
+        assert!(deps.would_cycle("c", "a"));
+        assert!(deps.would_cycle("b", "a"));
+        assert!(deps.would_cycle("a", "a"));
+        assert!(!deps.would_cycle("a", "c"));
+        assert!(!deps.would_cycle("c", "gone"));
+    }
+
+    #[test]
+    fn blocked_while_dependencies_are_open() {
+        let deps = deps();
+        assert_eq!(vec!["b"], deps.open_dependencies("a"));
+        // "c" is done and "gone" no longer exists.
+        assert!(!deps.is_blocked("b"));
+        assert!(!deps.is_blocked("c"));
+    }
+
+    #[test]
+    fn retargets_renamed_paths() {
+        let mut depends_on = vec!["a.b".to_string(), "a.bc".to_string(), "a".to_string()];
+        assert!(retarget(&mut depends_on, "a.b", "x"));
+        assert_eq!(vec!["x", "a.bc", "a"], depends_on);
+
+        assert!(retarget(&mut depends_on, "a", "y"));
+        assert_eq!(vec!["x", "y.bc", "y"], depends_on);
+        assert!(!retarget(&mut depends_on, "z", "y"));
+    }
+
+    #[test]
+    fn moving_to_another_root_drops_edges_across_the_subtree() {
+        let mut moved = vec!["a.b.c".to_string(), "a.bc".to_string(), "a.b".to_string()];
+        assert!(carry(&mut moved, "a.b", "x"));
+        assert_eq!(vec!["x.c", "x"], moved);
+
+        let mut left = vec!["a.b.c".to_string(), "a.bc".to_string()];
+        assert!(detach(&mut left, "a.b"));
+        assert_eq!(vec!["a.bc"], left);
+        assert!(!detach(&mut left, "a.b"));
+    }
+}
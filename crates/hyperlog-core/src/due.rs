@@ -0,0 +1,173 @@
+//! Due dates. Dues are stored as ISO `YYYY-MM-DD` strings; here they are
+//! handled as days since 1970-01-01, so date math needs no calendar
+//! dependency.
+
+/// Days since 1970-01-01 for a `YYYY-MM-DD` date.
+pub fn parse_date(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    let bytes = raw.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let part = &raw[range];
+        part.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| part.parse().ok())
+            .flatten()
+    };
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+/// The calendar date of a due, ignoring any time of day after it.
+pub fn due_date(due: &str) -> Option<i64> {
+    let due = due.trim();
+    match due.get(10..) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['T', ' ']) => parse_date(&due[..10]),
+        _ => None,
+    }
+}
+
+/// `YYYY-MM-DD` for days since 1970-01-01.
+pub fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// `days` after (or before, when negative) `date`, or `None` when that falls
+/// outside the four-digit years [`parse_date`] reads.
+pub fn add_days(date: i64, days: i64) -> Option<i64> {
+    date.checked_add(days)
+        .filter(|&date| (days_from_civil(0, 1, 1)..=days_from_civil(9999, 12, 31)).contains(&date))
+}
+
+/// Today in UTC, as days since 1970-01-01.
+pub fn today() -> i64 {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    secs.div_euclid(86_400)
+}
+
+/// Whether an item starting on `start` is still deferred on `today`. Starts
+/// that aren't dates don't defer.
+pub fn is_deferred(start: Option<&str>, today: i64) -> bool {
+    start.and_then(due_date).is_some_and(|start| start > today)
+}
+
+/// The start date for snoozing an item: a `YYYY-MM-DD` date, `tomorrow`, or
+/// days or weeks from `today` such as `+3d` or `+1w`. Offsets past the last
+/// date are rejected.
+pub fn parse_snooze(raw: &str, today: i64) -> Option<String> {
+    let raw = raw.trim();
+    if raw == "tomorrow" {
+        return add_days(today, 1).map(format_date);
+    }
+    match crate::template::relative_due_days(raw) {
+        Some(days) if days > 0 => add_days(today, days).map(format_date),
+        Some(_) => None,
+        None => parse_date(raw).map(format_date),
+    }
+}
+
+/// Day of the week, 0 for Monday through 6 for Sunday.
+pub fn weekday(days: i64) -> i64 {
+    // 1970-01-01 was a Thursday.
+    (days + 3).rem_euclid(7)
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub(crate) fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Howard Hinnant's days_from_civil / civil_from_days.
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_dates() {
+        assert_eq!(Some(0), parse_date("1970-01-01"));
+        for date in ["1970-01-01", "2000-02-29", "2026-10-18", "1969-12-31"] {
+            assert_eq!(date, format_date(parse_date(date).unwrap()));
+        }
+        assert_eq!(None, parse_date("2026-02-29"));
+        assert_eq!(None, parse_date("2026-13-01"));
+        assert_eq!(None, parse_date("tomorrow"));
+        assert_eq!(None, parse_date("2026-1-01"));
+
+        let date = parse_date("2026-10-18").unwrap();
+        assert_eq!(parse_date("2026-11-01"), add_days(date, 14));
+        assert_eq!(
+            parse_date("9999-12-31"),
+            add_days(parse_date("9999-12-30").unwrap(), 1)
+        );
+        assert_eq!(None, add_days(parse_date("9999-12-31").unwrap(), 1));
+        assert_eq!(None, add_days(date, i64::MAX));
+        assert_eq!(None, add_days(date, i64::MIN));
+    }
+
+    #[test]
+    fn reads_the_date_of_a_due() {
+        assert_eq!(
+            parse_date("2026-10-18"),
+            due_date("2026-10-18T09:00:00+02:00")
+        );
+        assert_eq!(None, due_date("2026-10-18x"));
+        // 2026-10-18 is a Sunday.
+        assert_eq!(6, weekday(parse_date("2026-10-18").unwrap()));
+    }
+
+    #[test]
+    fn defers_until_the_start_date() {
+        let today = parse_date("2026-10-18").unwrap();
+        assert!(is_deferred(Some("2026-10-19"), today));
+        assert!(!is_deferred(Some("2026-10-18"), today));
+        assert!(!is_deferred(None, today));
+
+        assert_eq!(Some("2026-10-19".into()), parse_snooze("tomorrow", today));
+        assert_eq!(Some("2026-10-25".into()), parse_snooze("+1w", today));
+        assert_eq!(Some("2026-11-02".into()), parse_snooze("2026-11-02", today));
+        assert_eq!(None, parse_snooze("-1d", today));
+        assert_eq!(None, parse_snooze("soon", today));
+        assert_eq!(None, parse_snooze("+9223372036854775807w", today));
+        assert_eq!(None, parse_snooze("+9999999999999d", today));
+    }
+}
@@ -1 +1,15 @@
+pub mod agenda;
+pub mod checklist;
+pub mod completed;
+pub mod convert;
+pub mod deps;
+pub mod due;
 pub mod log;
+pub mod recur;
+pub mod search;
+pub mod standup;
+pub mod stats;
+pub mod tags;
+pub mod template;
+pub mod validate;
+pub mod wiki_links;
@@ -5,12 +5,26 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+use crate::checklist::ChecklistEntry;
+
+/// Stored as a plain string, except `blocked` which carries its reason:
+/// `{"blocked": {"reason": "..."}}`. Items written before the extra states
+/// existed are only ever `not-done` or `done`.
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
 pub enum ItemState {
     #[serde(rename = "not-done")]
     NotDone,
+    #[serde(rename = "in-progress")]
+    InProgress,
+    #[serde(rename = "blocked")]
+    Blocked {
+        #[serde(default)]
+        reason: String,
+    },
     #[serde(rename = "done")]
     Done,
+    #[serde(rename = "cancelled")]
+    Cancelled,
 }
 
 impl Default for ItemState {
@@ -19,6 +33,66 @@ impl Default for ItemState {
     }
 }
 
+impl ItemState {
+    /// Done or cancelled; nothing is left to do either way.
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Self::Done | Self::Cancelled)
+    }
+
+    /// The state after this one when cycling through them:
+    /// not-done, in-progress, blocked, done, cancelled and round again.
+    pub fn next(&self) -> Self {
+        match self {
+            Self::NotDone => Self::InProgress,
+            Self::InProgress => Self::Blocked {
+                reason: String::new(),
+            },
+            Self::Blocked { .. } => Self::Done,
+            Self::Done => Self::Cancelled,
+            Self::Cancelled => Self::NotDone,
+        }
+    }
+
+    /// What toggling does: open items are done, closed items are reopened.
+    pub fn toggled(&self) -> Self {
+        if self.is_closed() {
+            Self::NotDone
+        } else {
+            Self::Done
+        }
+    }
+}
+
+/// How urgent an item is, `P0` being the most. Orders most urgent first.
+#[derive(Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Priority {
+    P0,
+    P1,
+    P2,
+    P3,
+}
+
+impl std::str::FromStr for Priority {
+    type Err = anyhow::Error;
+
+    /// `P0` to `P3`, either case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "P0" => Ok(Self::P0),
+            "P1" => Ok(Self::P1),
+            "P2" => Ok(Self::P2),
+            "P3" => Ok(Self::P3),
+            _ => anyhow::bail!("expected a priority from P0 to P3, got: {s}"),
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
 /// An external reference link attached to an item (project-management metadata).
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug, Default)]
 pub struct Link {
@@ -28,26 +102,128 @@ pub struct Link {
     pub url: String,
 }
 
+/// What a section carries besides its children.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct SectionMeta {
+    /// Shown instead of the key when set, such as the title of the item the
+    /// section was converted from.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// Accent colour as `#rrggbb`, see [`SectionMeta::rgb`].
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub colour: String,
+    /// A short symbol shown before the name, such as an emoji.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub icon: String,
+    /// Shown folded, without its children, until selected.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub collapsed: bool,
+}
+
+impl SectionMeta {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The accent colour, `None` when unset or not `#rrggbb`.
+    pub fn rgb(&self) -> Option<(u8, u8, u8)> {
+        let hex = self.colour.strip_prefix('#')?;
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some((channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
 #[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)]
 pub enum GraphItem {
     #[serde(rename = "user")]
     User(BTreeMap<String, GraphItem>),
     #[serde(rename = "section")]
-    Section(BTreeMap<String, GraphItem>),
+    Section {
+        /// Kept next to the children under a key no child can have, as keys
+        /// never contain a `.`.
+        #[serde(
+            default,
+            rename = ".section",
+            skip_serializing_if = "SectionMeta::is_empty"
+        )]
+        meta: SectionMeta,
+        #[serde(flatten)]
+        items: BTreeMap<String, GraphItem>,
+    },
     #[serde(rename = "item")]
     Item {
         title: String,
         description: String,
         state: ItemState,
+        /// ISO `YYYY-MM-DD`, see [`crate::due`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        due: Option<String>,
+        /// `YYYY-MM-DD` the item is deferred until, see [`crate::due::is_deferred`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start: Option<String>,
+        /// Recurrence rule, see [`crate::recur`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        recur: Option<String>,
+        /// `YYYY-MM-DD` dates a recurring item was completed on, oldest first.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        completions: Vec<String>,
+        /// When the item was marked done, see [`crate::completed::track`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        completed_at: Option<String>,
+        /// Dotted root-relative paths of the items this one waits on, see
+        /// [`crate::deps`].
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        depends_on: Vec<String>,
+        /// Tags set on the item, without the inline ones, see [`crate::tags`].
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        priority: Option<Priority>,
+        /// Steps inside the item, see [`crate::checklist`].
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        checklist: Vec<ChecklistEntry>,
     },
 }
 
 impl GraphItem {
+    /// A section holding `items`, without metadata.
+    pub fn section(items: BTreeMap<String, GraphItem>) -> Self {
+        Self::Section {
+            items,
+            meta: SectionMeta::default(),
+        }
+    }
+
+    /// An item with just a title, description and state, everything else
+    /// unset.
+    pub fn item(title: String, description: String, state: ItemState) -> Self {
+        Self::Item {
+            title,
+            description,
+            state,
+            due: None,
+            start: None,
+            recur: None,
+            completions: Vec::new(),
+            completed_at: None,
+            depends_on: Vec::new(),
+            tags: Vec::new(),
+            priority: None,
+            checklist: Vec::new(),
+        }
+    }
+
     pub fn get(&self, path: &[&str]) -> Option<&GraphItem> {
         match path.split_first() {
             Some((first, rest)) => match self {
-                GraphItem::User(section) | GraphItem::Section(section) => {
+                GraphItem::User(section) | GraphItem::Section { items: section, .. } => {
                     section.get(*first)?.get(rest)
                 }
                 GraphItem::Item { .. } => None,
@@ -59,7 +235,7 @@ impl GraphItem {
     pub fn get_mut(&mut self, path: &[&str]) -> Option<&mut GraphItem> {
         match path.split_first() {
             Some((first, rest)) => match self {
-                GraphItem::User(section) | GraphItem::Section(section) => {
+                GraphItem::User(section) | GraphItem::Section { items: section, .. } => {
                     section.get_mut(*first)?.get_mut(rest)
                 }
                 GraphItem::Item { .. } => None,
@@ -71,7 +247,7 @@ impl GraphItem {
     pub fn take(&mut self, path: &[&str]) -> Option<GraphItem> {
         match path.split_first() {
             Some((first, rest)) => match self {
-                GraphItem::User(section) | GraphItem::Section(section) => {
+                GraphItem::User(section) | GraphItem::Section { items: section, .. } => {
                     if rest.is_empty() {
                         section.remove(*first)
                     } else {
@@ -106,7 +282,7 @@ impl DerefMut for Graph {
 mod test {
     use std::collections::BTreeMap;
 
-    use crate::log::{GraphItem, ItemState};
+    use crate::log::{GraphItem, ItemState, SectionMeta};
 
     use super::Graph;
 
@@ -145,7 +321,7 @@ mod test {
         let mut user = BTreeMap::new();
         user.insert(
             "some-project".into(),
-            GraphItem::Section(BTreeMap::default()),
+            GraphItem::section(BTreeMap::default()),
         );
 
         expected.insert("kjuulh".into(), GraphItem::User(user));
@@ -174,10 +350,10 @@ mod test {
         let mut some_project = BTreeMap::default();
         some_project.insert(
             "some-nested-project".into(),
-            GraphItem::Section(BTreeMap::default()),
+            GraphItem::section(BTreeMap::default()),
         );
         let mut user = BTreeMap::new();
-        user.insert("some-project".into(), GraphItem::Section(some_project));
+        user.insert("some-project".into(), GraphItem::section(some_project));
 
         expected.insert("kjuulh".into(), GraphItem::User(user));
 
@@ -211,20 +387,20 @@ mod test {
         let mut nested_project = BTreeMap::default();
         nested_project.insert(
             "some-todo".into(),
-            GraphItem::Item {
-                title: "some title".into(),
-                description: "some description".into(),
-                state: ItemState::NotDone,
-            },
+            GraphItem::item(
+                "some title".into(),
+                "some description".into(),
+                ItemState::NotDone,
+            ),
         );
 
         let mut some_project = BTreeMap::default();
         some_project.insert(
             "some-nested-project".into(),
-            GraphItem::Section(nested_project),
+            GraphItem::section(nested_project),
         );
         let mut user = BTreeMap::new();
-        user.insert("some-project".into(), GraphItem::Section(some_project));
+        user.insert("some-project".into(), GraphItem::section(some_project));
 
         expected.insert("kjuulh".into(), GraphItem::User(user));
 
@@ -233,4 +409,59 @@ mod test {
             serde_json::to_string_pretty(&graph).unwrap()
         );
     }
+
+    #[test]
+    fn section_meta_sits_next_to_the_children() {
+        let test_graph = r#"{
+  "type": "section",
+  ".section": {
+    "title": "v1.0 launch"
+  },
+  "description": {
+    "type": "section"
+  }
+}"#;
+
+        let section: GraphItem = serde_json::from_str(test_graph).unwrap();
+
+        let expected = GraphItem::Section {
+            items: BTreeMap::from([(
+                "description".to_string(),
+                GraphItem::section(BTreeMap::new()),
+            )]),
+            meta: SectionMeta {
+                title: "v1.0 launch".into(),
+                ..Default::default()
+            },
+        };
+        similar_asserts::assert_eq!(expected, section);
+        similar_asserts::assert_eq!(test_graph, serde_json::to_string_pretty(&section).unwrap());
+    }
+
+    #[test]
+    fn item_states_serialize_compatibly() {
+        for (state, json) in [
+            (ItemState::NotDone, r#""not-done""#),
+            (ItemState::InProgress, r#""in-progress""#),
+            (ItemState::Done, r#""done""#),
+            (ItemState::Cancelled, r#""cancelled""#),
+            (
+                ItemState::Blocked {
+                    reason: "waiting on review".into(),
+                },
+                r#"{"blocked":{"reason":"waiting on review"}}"#,
+            ),
+        ] {
+            assert_eq!(json, serde_json::to_string(&state).unwrap());
+            assert_eq!(state, serde_json::from_str::<ItemState>(json).unwrap());
+        }
+
+        let mut state = ItemState::NotDone;
+        for _ in 0..5 {
+            state = state.next();
+        }
+        assert_eq!(ItemState::NotDone, state);
+        assert_eq!(ItemState::NotDone, ItemState::Cancelled.toggled());
+        assert_eq!(ItemState::Done, ItemState::InProgress.toggled());
+    }
 }
@@ -0,0 +1,312 @@
+//! Recurring items. An item's `recur` rule is one of `daily`, `weekly`,
+//! `weekly:mon,thu` or `monthly`, or a subset of an RFC 5545 RRULE:
+//! `FREQ=DAILY|WEEKLY|MONTHLY` with optional `INTERVAL`, `BYDAY` (weekly) and
+//! `BYMONTHDAY` (monthly). Completing a recurring item doesn't mark it done;
+//! it records the completion and moves the due to the next occurrence.
+//!
+//! A plain `monthly` rule follows the day of the current due, so an item due
+//! on the 29th-31st is pinned to that day with `BYMONTHDAY` when first
+//! completed. Otherwise the first short month would clamp the due and every
+//! later occurrence would follow from there: the 31st would become the 28th
+//! from February onwards.
+
+use crate::due;
+
+const WEEKDAYS: [&str; 7] = ["mo", "tu", "we", "th", "fr", "sa", "su"];
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Rule {
+    pub freq: Freq,
+    /// Every `interval` days, weeks or months.
+    pub interval: i64,
+    /// Weekdays for weekly rules, 0 for Monday; the due's own weekday when
+    /// empty.
+    pub weekdays: Vec<i64>,
+    /// Day of the month for monthly rules; the due's own day when `None`.
+    /// Clamped to the length of shorter months.
+    pub month_day: Option<i64>,
+}
+
+impl Rule {
+    pub fn parse(raw: &str) -> anyhow::Result<Self> {
+        let raw = raw.trim();
+        let lower = raw.to_ascii_lowercase();
+        let rule = match lower.as_str() {
+            "daily" => Self::every(Freq::Daily),
+            "weekly" => Self::every(Freq::Weekly),
+            "monthly" => Self::every(Freq::Monthly),
+            _ => match lower.strip_prefix("weekly:") {
+                Some(days) => Self {
+                    weekdays: parse_weekdays(days)?,
+                    ..Self::every(Freq::Weekly)
+                },
+                None => Self::parse_rrule(lower.strip_prefix("rrule:").unwrap_or(&lower))
+                    .map_err(|e| anyhow::anyhow!("invalid recurrence {raw:?}: {e}"))?,
+            },
+        };
+        Ok(rule)
+    }
+
+    fn every(freq: Freq) -> Self {
+        Self {
+            freq,
+            interval: 1,
+            weekdays: Vec::new(),
+            month_day: None,
+        }
+    }
+
+    fn parse_rrule(raw: &str) -> anyhow::Result<Self> {
+        let mut freq = None;
+        let mut rule = Self::every(Freq::Daily);
+        for part in raw.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("expected KEY=VALUE, got {part:?}"))?;
+            match key {
+                "freq" => {
+                    freq = Some(match value {
+                        "daily" => Freq::Daily,
+                        "weekly" => Freq::Weekly,
+                        "monthly" => Freq::Monthly,
+                        _ => anyhow::bail!("unsupported FREQ {value:?}"),
+                    })
+                }
+                "interval" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|i| *i >= 1)
+                        .ok_or_else(|| anyhow::anyhow!("INTERVAL must be at least 1"))?
+                }
+                "byday" => rule.weekdays = parse_weekdays(value)?,
+                "bymonthday" => {
+                    rule.month_day = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|d| (1..=31).contains(d))
+                            .ok_or_else(|| anyhow::anyhow!("BYMONTHDAY must be 1-31"))?,
+                    )
+                }
+                _ => anyhow::bail!("unsupported RRULE part {key:?}"),
+            }
+        }
+        rule.freq = freq.ok_or_else(|| anyhow::anyhow!("FREQ is required"))?;
+        if !rule.weekdays.is_empty() && rule.freq != Freq::Weekly {
+            anyhow::bail!("BYDAY is only supported for FREQ=WEEKLY");
+        }
+        if rule.month_day.is_some() && rule.freq != Freq::Monthly {
+            anyhow::bail!("BYMONTHDAY is only supported for FREQ=MONTHLY");
+        }
+        Ok(rule)
+    }
+
+    /// The first occurrence strictly after `after` (days since 1970-01-01).
+    pub fn next(&self, after: i64) -> i64 {
+        match self.freq {
+            Freq::Daily => after + self.interval,
+            Freq::Weekly if self.weekdays.is_empty() => after + 7 * self.interval,
+            Freq::Weekly => {
+                let week_start = after - due::weekday(after);
+                let later_this_week = (after + 1..week_start + 7)
+                    .find(|day| self.weekdays.contains(&due::weekday(*day)));
+                later_this_week.unwrap_or_else(|| {
+                    let first = self.weekdays.iter().min().copied().unwrap_or_default();
+                    week_start + 7 * self.interval + first
+                })
+            }
+            Freq::Monthly => {
+                let (year, month, day) = due::civil_from_days(after);
+                let target = self.month_day.unwrap_or(day);
+                if target > day {
+                    let this_month = target.min(due::days_in_month(year, month));
+                    if this_month > day {
+                        return due::days_from_civil(year, month, this_month);
+                    }
+                }
+                let months = year * 12 + month - 1 + self.interval;
+                let (year, month) = (months.div_euclid(12), months.rem_euclid(12) + 1);
+                due::days_from_civil(year, month, target.min(due::days_in_month(year, month)))
+            }
+        }
+    }
+}
+
+/// `rule` with its month day pinned to the day of `due`, when it is a monthly
+/// rule without one and that day doesn't exist in every month.
+pub fn pin_month_day(rule: &str, due: Option<&str>) -> anyhow::Result<Option<String>> {
+    let parsed = Rule::parse(rule)?;
+    let day = match due.and_then(due::due_date) {
+        Some(date) => due::civil_from_days(date).2,
+        None => return Ok(None),
+    };
+    if parsed.freq != Freq::Monthly || parsed.month_day.is_some() || day <= 28 {
+        return Ok(None);
+    }
+    Ok(Some(match parsed.interval {
+        1 => format!("FREQ=MONTHLY;BYMONTHDAY={day}"),
+        interval => format!("FREQ=MONTHLY;INTERVAL={interval};BYMONTHDAY={day}"),
+    }))
+}
+
+fn parse_weekdays(raw: &str) -> anyhow::Result<Vec<i64>> {
+    let mut days = raw
+        .split(',')
+        .map(|day| {
+            let day = day.trim();
+            WEEKDAYS
+                .iter()
+                .position(|d| day.is_ascii() && day.len() >= 2 && d.starts_with(&day[..2]))
+                .map(|d| d as i64)
+                .ok_or_else(|| anyhow::anyhow!("unknown weekday {day:?}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    days.sort();
+    days.dedup();
+    Ok(days)
+}
+
+/// The due after completing an occurrence: the first occurrence after both
+/// the current due and `today`, keeping any time of day. Items without a due
+/// recur from today.
+pub fn next_due(rule: &str, current: Option<&str>, today: i64) -> anyhow::Result<String> {
+    let rule = Rule::parse(rule)?;
+    let current = current.map(str::trim);
+    let date = current.and_then(due::due_date);
+    let mut next = rule.next(date.unwrap_or(today));
+    while next <= today {
+        next = rule.next(next);
+    }
+    let time = match (current, date) {
+        (Some(current), Some(_)) => &current[10..],
+        _ => "",
+    };
+    Ok(format!("{}{time}", due::format_date(next)))
+}
+
+/// Complete the current occurrence of a recurring item: remember `today` in
+/// `completions` and move `due` to the next occurrence. `rule` may get its
+/// month day pinned, see [`pin_month_day`].
+pub fn complete(
+    rule: &mut String,
+    due: &mut Option<String>,
+    completions: &mut Vec<String>,
+    today: i64,
+) -> anyhow::Result<()> {
+    if let Some(pinned) = pin_month_day(rule, due.as_deref())? {
+        *rule = pinned;
+    }
+    *due = Some(next_due(rule, due.as_deref(), today)?);
+    completions.push(due::format_date(today));
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(raw: &str) -> i64 {
+        due::parse_date(raw).unwrap()
+    }
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!(Rule::every(Freq::Daily), Rule::parse("Daily").unwrap());
+        assert_eq!(vec![0, 3], Rule::parse("weekly:thu,mon").unwrap().weekdays);
+        let rrule = Rule::parse("RRULE:FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=31").unwrap();
+        assert_eq!(
+            (Freq::Monthly, 2, Some(31)),
+            (rrule.freq, rrule.interval, rrule.month_day)
+        );
+        for bad in [
+            "yearly",
+            "weekly:funday",
+            "FREQ=DAILY;BYDAY=MO",
+            "FREQ=WEEKLY;INTERVAL=0",
+            "INTERVAL=2",
+            "FREQ=DAILY;COUNT=3",
+        ] {
+            assert!(Rule::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn finds_next_occurrence() {
+        // 2026-10-14 is a Wednesday.
+        let wednesday = date("2026-10-14");
+        let weekly = |raw| Rule::parse(raw).unwrap().next(wednesday);
+        assert_eq!(date("2026-10-21"), weekly("weekly"));
+        assert_eq!(date("2026-10-15"), weekly("weekly:mon,thu"));
+        assert_eq!(date("2026-10-19"), weekly("weekly:mon,tue"));
+        assert_eq!(
+            date("2026-10-26"),
+            weekly("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO")
+        );
+
+        let monthly = |raw, from| Rule::parse(raw).unwrap().next(date(from));
+        assert_eq!(date("2026-02-28"), monthly("monthly", "2026-01-31"));
+        assert_eq!(
+            date("2026-01-31"),
+            monthly("FREQ=MONTHLY;BYMONTHDAY=31", "2026-01-15")
+        );
+        assert_eq!(
+            date("2026-02-28"),
+            monthly("FREQ=MONTHLY;BYMONTHDAY=31", "2026-01-31")
+        );
+        assert_eq!(date("2027-01-15"), monthly("monthly", "2026-12-15"));
+    }
+
+    #[test]
+    fn completes_occurrences() {
+        let today = date("2026-10-18");
+        let mut due = Some("2026-10-01T09:00".to_string());
+        let mut completions = Vec::new();
+        complete(&mut "weekly".into(), &mut due, &mut completions, today).unwrap();
+        // Overdue occurrences are skipped rather than queued up.
+        assert_eq!(Some("2026-10-22T09:00".to_string()), due);
+        assert_eq!(vec!["2026-10-18".to_string()], completions);
+
+        assert_eq!("2026-10-19", next_due("daily", None, today).unwrap());
+        assert_eq!(
+            "2026-10-21",
+            next_due("daily", Some("2026-10-20"), today).unwrap()
+        );
+    }
+
+    #[test]
+    fn monthly_keeps_the_end_of_month_through_february() {
+        let mut rule = "monthly".to_string();
+        let mut due = Some("2026-01-31".to_string());
+        let mut completions = Vec::new();
+        for (today, next) in [
+            ("2026-01-31", "2026-02-28"),
+            ("2026-02-28", "2026-03-31"),
+            ("2026-03-31", "2026-04-30"),
+        ] {
+            complete(&mut rule, &mut due, &mut completions, date(today)).unwrap();
+            assert_eq!(Some(next.to_string()), due);
+        }
+        assert_eq!("FREQ=MONTHLY;BYMONTHDAY=31", rule);
+
+        // Days every month has are left alone.
+        let mut rule = "monthly".to_string();
+        let mut due = Some("2026-01-15".to_string());
+        complete(&mut rule, &mut due, &mut completions, date("2026-01-15")).unwrap();
+        assert_eq!(
+            ("monthly", Some("2026-02-15")),
+            (rule.as_str(), due.as_deref())
+        );
+        assert_eq!(
+            Some("FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=30".to_string()),
+            pin_month_day("FREQ=MONTHLY;INTERVAL=2", Some("2026-01-30")).unwrap()
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Finding items by text and tag across a root, or all of them. Inline
+//! `#tag`s and case folding are only settled by [`Search::matches`]; the
+//! server narrows candidates down in SQL first.
+
+use serde::{Deserialize, Serialize};
+
+use crate::tags;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct SearchHit {
+    pub root: String,
+    /// Root-relative.
+    pub path: Vec<String>,
+    pub title: String,
+    pub done: bool,
+    /// Every tag of the item, inline ones included.
+    pub tags: Vec<String>,
+}
+
+/// Most hits a search returns, the first in root and path order.
+pub const MAX_HITS: usize = 200;
+
+#[derive(Clone, Debug, Default)]
+pub struct Search {
+    /// Case-insensitive text in the title or description; empty matches all.
+    pub text: String,
+    pub tag: Option<String>,
+}
+
+impl Search {
+    pub fn matches(&self, title: &str, description: &str, tags: &[String]) -> bool {
+        let text = self.text.trim().to_lowercase();
+        let has_text = text.is_empty()
+            || title.to_lowercase().contains(&text)
+            || description.to_lowercase().contains(&text);
+
+        has_text
+            && self
+                .tag
+                .as_deref()
+                .is_none_or(|tag| tags::matches(tags, description, tag))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matches_text_and_tag() {
+        let search = Search {
+            text: "Login".into(),
+            tag: Some("#bug".into()),
+        };
+        assert!(search.matches("Fix login", "#bug on submit", &[]));
+        assert!(search.matches("Fix it", "the login page", &["bug".into()]));
+        assert!(!search.matches("Fix login", "", &[]));
+        assert!(!search.matches("Fix signup", "#bug", &[]));
+        assert!(Search::default().matches("", "", &[]));
+    }
+}
@@ -0,0 +1,281 @@
+//! A daily standup: what was finished since the last working day, what is
+//! still open, what is overdue and what is blocked.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{due, log::ItemState};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct StandupItem {
+    pub root: String,
+    pub path: Vec<String>,
+    pub title: String,
+    /// Why it is listed, such as the due date or what it is waiting on.
+    pub detail: Option<String>,
+}
+
+impl StandupItem {
+    /// The dotted path of the section the item is in, empty at the root.
+    pub fn section(&self) -> String {
+        self.path[..self.path.len().saturating_sub(1)].join(".")
+    }
+}
+
+/// An item as seen by [`Standup::push`].
+pub struct StandupEntry<'a> {
+    pub root: &'a str,
+    pub path: &'a [String],
+    pub title: &'a str,
+    pub state: &'a ItemState,
+    pub due: Option<&'a str>,
+    /// Dependencies that are still open.
+    pub waiting_on: Vec<&'a str>,
+    /// Seconds since the epoch, when the backend knows it.
+    pub created_unix: Option<i64>,
+    pub completed_at: Option<&'a str>,
+    pub completions: &'a [String],
+    /// Archived items can only be listed as done.
+    pub archived: bool,
+}
+
+/// `today` and `since` are days since 1970-01-01, `since` being the last
+/// working day before `today`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct Standup {
+    pub today: i64,
+    pub since: i64,
+    pub done: Vec<StandupItem>,
+    /// In progress, or added since the last working day.
+    pub open: Vec<StandupItem>,
+    pub overdue: Vec<StandupItem>,
+    pub blocked: Vec<StandupItem>,
+}
+
+impl Standup {
+    pub fn new(today: i64) -> Self {
+        Self {
+            today,
+            since: last_working_day(today),
+            ..Default::default()
+        }
+    }
+
+    /// File `entry`. An item done since the last working day is listed as
+    /// done; an open one goes to the first of blocked, overdue and still open
+    /// that applies, if any.
+    pub fn push(&mut self, entry: StandupEntry) {
+        let item = |detail: Option<String>| StandupItem {
+            root: entry.root.to_string(),
+            path: entry.path.to_vec(),
+            title: entry.title.to_string(),
+            detail,
+        };
+
+        let in_range = |at: &str| {
+            due::due_date(at).is_some_and(|date| (self.since..=self.today).contains(&date))
+        };
+        if entry
+            .completed_at
+            .into_iter()
+            .chain(entry.completions.iter().map(String::as_str))
+            .any(in_range)
+        {
+            self.done.push(item(None));
+        }
+        if entry.state.is_closed() || entry.archived {
+            return;
+        }
+
+        let overdue = entry
+            .due
+            .filter(|due| due::due_date(due).is_some_and(|date| date < self.today));
+        let created_since = entry
+            .created_unix
+            .is_some_and(|created| created.div_euclid(86_400) >= self.since);
+
+        match entry.state {
+            ItemState::Blocked { reason } => self
+                .blocked
+                .push(item(Some(reason.clone()).filter(|r| !r.is_empty()))),
+            _ if !entry.waiting_on.is_empty() => self.blocked.push(item(Some(format!(
+                "waiting on {}",
+                entry.waiting_on.join(", ")
+            )))),
+            _ if overdue.is_some() => self
+                .overdue
+                .push(item(overdue.map(|due| format!("due {due}")))),
+            ItemState::InProgress => self.open.push(item(Some("in progress".into()))),
+            _ if created_since => self.open.push(item(Some("new".into()))),
+            _ => {}
+        }
+    }
+
+    /// Order every list by root and path.
+    pub fn sort(&mut self) {
+        for items in [
+            &mut self.done,
+            &mut self.open,
+            &mut self.overdue,
+            &mut self.blocked,
+        ] {
+            items.sort_by(|a, b| (&a.root, &a.path).cmp(&(&b.root, &b.path)));
+        }
+    }
+
+    pub fn sections(&self) -> [(String, &[StandupItem]); 4] {
+        [
+            (
+                format!("Done since {}", due::format_date(self.since)),
+                &self.done,
+            ),
+            ("Still open".into(), &self.open),
+            ("Overdue".into(), &self.overdue),
+            ("Blocked".into(), &self.blocked),
+        ]
+    }
+
+    /// The report as markdown, each list grouped by root and section.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Standup {}\n", due::format_date(self.today));
+        for (heading, items) in self.sections() {
+            let _ = write!(out, "\n## {heading}\n");
+            if items.is_empty() {
+                out.push_str("\n_Nothing_\n");
+                continue;
+            }
+
+            let mut group = None;
+            for item in items {
+                let section = item.section();
+                if group != Some((&item.root, section.clone())) {
+                    if section.is_empty() {
+                        let _ = write!(out, "\n### {}\n\n", item.root);
+                    } else {
+                        let _ = write!(out, "\n### {}: {}\n\n", item.root, section);
+                    }
+                    group = Some((&item.root, section));
+                }
+                match &item.detail {
+                    Some(detail) => {
+                        let _ = writeln!(out, "- {} ({})", item.title, detail);
+                    }
+                    None => {
+                        let _ = writeln!(out, "- {}", item.title);
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// The weekday before `today`: Friday when `today` is a Saturday, Sunday or
+/// Monday.
+pub fn last_working_day(today: i64) -> i64 {
+    match due::weekday(today) {
+        0 => today - 3,
+        6 => today - 2,
+        _ => today - 1,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn last_working_day_skips_the_weekend() {
+        let day = |raw| due::parse_date(raw).unwrap();
+        assert_eq!(day("2026-10-16"), last_working_day(day("2026-10-19")));
+        assert_eq!(day("2026-10-16"), last_working_day(day("2026-10-18")));
+        assert_eq!(day("2026-10-20"), last_working_day(day("2026-10-21")));
+    }
+
+    #[test]
+    fn files_items_and_renders_markdown() {
+        // A Monday.
+        let today = due::parse_date("2026-10-19").unwrap();
+        let mut standup = Standup::new(today);
+        fn entry<'a>(path: &'a [String], title: &'a str, state: &'a ItemState) -> StandupEntry<'a> {
+            StandupEntry {
+                root: "work",
+                path,
+                title,
+                state,
+                due: None,
+                waiting_on: Vec::new(),
+                created_unix: None,
+                completed_at: None,
+                completions: &[],
+                archived: false,
+            }
+        }
+        let path = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        let shipped = path(&["api", "shipped"]);
+        let old = path(&["api", "old"]);
+        let review = path(&["review"]);
+        let deploy = path(&["api", "deploy"]);
+        let late = path(&["api", "late"]);
+        let fresh = path(&["api", "fresh"]);
+        let idle = path(&["api", "idle"]);
+        let retired = path(&["api", "retired"]);
+        let shelved = path(&["api", "shelved"]);
+
+        standup.push(StandupEntry {
+            completed_at: Some("2026-10-16T17:00:00Z"),
+            ..entry(&shipped, "Shipped", &ItemState::Done)
+        });
+        standup.push(StandupEntry {
+            completed_at: Some("2026-10-15T17:00:00Z"),
+            ..entry(&old, "Old", &ItemState::Done)
+        });
+        standup.push(entry(&review, "Review", &ItemState::InProgress));
+        standup.push(StandupEntry {
+            waiting_on: vec!["api.late"],
+            due: Some("2026-10-01"),
+            ..entry(&deploy, "Deploy", &ItemState::NotDone)
+        });
+        standup.push(StandupEntry {
+            due: Some("2026-10-18"),
+            ..entry(&late, "Late", &ItemState::InProgress)
+        });
+        standup.push(StandupEntry {
+            created_unix: Some(today * 86_400 - 3600),
+            ..entry(&fresh, "Fresh", &ItemState::NotDone)
+        });
+        standup.push(entry(&idle, "Idle", &ItemState::NotDone));
+        standup.push(StandupEntry {
+            completed_at: Some("2026-10-17T09:00:00Z"),
+            archived: true,
+            ..entry(&retired, "Retired", &ItemState::Done)
+        });
+        standup.push(StandupEntry {
+            archived: true,
+            ..entry(&shelved, "Shelved", &ItemState::InProgress)
+        });
+        standup.sort();
+
+        assert_eq!(
+            "# Standup 2026-10-19\n\
+             \n## Done since 2026-10-16\n\
+             \n### work: api\n\n\
+             - Retired\n\
+             - Shipped\n\
+             \n## Still open\n\
+             \n### work: api\n\n\
+             - Fresh (new)\n\
+             \n### work\n\n\
+             - Review (in progress)\n\
+             \n## Overdue\n\
+             \n### work: api\n\n\
+             - Late (due 2026-10-18)\n\
+             \n## Blocked\n\
+             \n### work: api\n\n\
+             - Deploy (waiting on api.late)\n",
+            standup.to_markdown()
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Progress of a root or one of its sections: how many items each section
+//! holds, has done and has overdue, and how many items were created and
+//! completed each day. Only the server knows when items were created.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{due, log::ItemState};
+
+/// Longest series either backend serves, in days.
+pub const MAX_DAYS: i64 = 366;
+
+/// Check that a series from `since` through `today` may be asked for: in
+/// order and no longer than [`MAX_DAYS`].
+pub fn check_range(since: i64, today: i64) -> anyhow::Result<()> {
+    if since > today {
+        anyhow::bail!(
+            "since ({}) is after today ({})",
+            due::format_date(since),
+            due::format_date(today)
+        );
+    }
+    if today - since >= MAX_DAYS {
+        anyhow::bail!("stats cover at most {} days", MAX_DAYS);
+    }
+    Ok(())
+}
+
+/// Item counts of a subtree. Cancelled items aren't counted.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Counts {
+    pub total: i64,
+    pub done: i64,
+    /// Open with a due before today.
+    pub overdue: i64,
+}
+
+impl Counts {
+    pub fn add(&mut self, state: &ItemState, due: Option<&str>, today: i64) {
+        match state {
+            ItemState::Cancelled => return,
+            ItemState::Done => self.done += 1,
+            _ if due.and_then(due::due_date).is_some_and(|date| date < today) => self.overdue += 1,
+            _ => {}
+        }
+        self.total += 1;
+    }
+
+    pub fn open(&self) -> i64 {
+        self.total - self.done
+    }
+
+    /// Share of the items that are done, rounded down; `None` without items.
+    pub fn percent_done(&self) -> Option<i64> {
+        (self.total > 0).then(|| self.done * 100 / self.total)
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct SectionStats {
+    /// Root-relative, empty for the root itself.
+    pub path: Vec<String>,
+    pub counts: Counts,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct DayStats {
+    /// Days since 1970-01-01.
+    pub date: i64,
+    pub created: i64,
+    pub completed: i64,
+    /// Items left open at the end of the day.
+    pub remaining: i64,
+}
+
+/// An item as seen by [`Stats::push`].
+pub struct StatsEntry<'a> {
+    /// Root-relative.
+    pub path: &'a [String],
+    pub state: &'a ItemState,
+    pub due: Option<&'a str>,
+    /// Seconds since the epoch, when the backend knows it.
+    pub created_unix: Option<i64>,
+    pub completed_at: Option<&'a str>,
+}
+
+/// The first section is the subtree asked for, followed by every section
+/// below it in path order. `days` runs from `since` through `today`.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct Stats {
+    pub since: i64,
+    pub today: i64,
+    pub sections: Vec<SectionStats>,
+    pub days: Vec<DayStats>,
+}
+
+impl Stats {
+    /// Stats of the subtree at `path` for the days `since` through `today`.
+    pub fn new(path: Vec<String>, since: i64, today: i64) -> Self {
+        Self {
+            since,
+            today,
+            sections: vec![SectionStats {
+                path,
+                counts: Counts::default(),
+            }],
+            days: (since..=today)
+                .map(|date| DayStats {
+                    date,
+                    created: 0,
+                    completed: 0,
+                    remaining: 0,
+                })
+                .collect(),
+        }
+    }
+
+    /// Report on the section at `path` as well; it must be below the subtree.
+    pub fn add_section(&mut self, path: Vec<String>) {
+        self.sections.push(SectionStats {
+            path,
+            counts: Counts::default(),
+        });
+    }
+
+    /// Count an item into every section it is below, and into the days it
+    /// was created and completed on. Cancelled items aren't counted, and
+    /// occurrences of a recurring item aren't completions as it stays open.
+    pub fn push(&mut self, entry: StatsEntry) {
+        if matches!(entry.state, ItemState::Cancelled) {
+            return;
+        }
+        for section in &mut self.sections {
+            if entry.path.len() > section.path.len() && entry.path.starts_with(&section.path) {
+                section.counts.add(entry.state, entry.due, self.today);
+            }
+        }
+
+        let (since, days) = (self.since, self.days.len());
+        let index = |date: i64| usize::try_from(date - since).ok().filter(|i| *i < days);
+        let created = entry
+            .created_unix
+            .and_then(|created| index(created.div_euclid(86_400)));
+        let completed = entry
+            .completed_at
+            .filter(|_| matches!(entry.state, ItemState::Done))
+            .and_then(due::due_date)
+            .and_then(index);
+        if let Some(i) = created {
+            self.days[i].created += 1;
+        }
+        if let Some(i) = completed {
+            self.days[i].completed += 1;
+        }
+    }
+
+    /// Sort the sections and work out what remained open each day, going back
+    /// from what is open today.
+    pub fn finish(&mut self) {
+        self.sections[1..].sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut remaining = self.sections[0].counts.open();
+        for day in self.days.iter_mut().rev() {
+            day.remaining = remaining;
+            remaining = remaining - day.created + day.completed;
+        }
+    }
+
+    /// One line per day with a bar of what remained open, scaled to `width`.
+    pub fn burndown(&self, width: usize) -> String {
+        let most = self.days.iter().map(|d| d.remaining).max().unwrap_or(0);
+        self.days
+            .iter()
+            .map(|day| {
+                let bar = match most {
+                    0 => 0,
+                    most => (day.remaining.max(0) as usize * width).div_ceil(most as usize),
+                };
+                format!(
+                    "{}  {:<width$}  {:>4} open  +{} -{}\n",
+                    due::format_date(day.date),
+                    "#".repeat(bar),
+                    day.remaining,
+                    day.created,
+                    day.completed,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ranges_are_capped() {
+        let today = due::parse_date("2026-10-18").unwrap();
+        assert!(check_range(today, today).is_ok());
+        assert!(check_range(today - (MAX_DAYS - 1), today).is_ok());
+        assert!(check_range(today - MAX_DAYS, today).is_err());
+        assert!(check_range(today + 1, today).is_err());
+    }
+
+    #[test]
+    fn counts_sections_and_days() {
+        let day = |raw| due::parse_date(raw).unwrap();
+        let mut stats = Stats::new(Vec::new(), day("2026-10-16"), day("2026-10-18"));
+        stats.add_section(vec!["web".into()]);
+        stats.add_section(vec!["api".into()]);
+
+        let path = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        let items = [
+            (
+                path(&["api", "a"]),
+                ItemState::Done,
+                None,
+                Some("2026-10-17T10:00:00Z"),
+            ),
+            (
+                path(&["api", "b"]),
+                ItemState::NotDone,
+                Some("2026-10-01"),
+                None,
+            ),
+            (path(&["api", "c"]), ItemState::Cancelled, None, None),
+            (path(&["web", "d"]), ItemState::InProgress, None, None),
+            (
+                path(&["e"]),
+                ItemState::Done,
+                None,
+                Some("2026-10-18T08:00:00Z"),
+            ),
+        ];
+        for (path, state, due, completed_at) in &items {
+            stats.push(StatsEntry {
+                path,
+                state,
+                due: *due,
+                created_unix: Some(day("2026-10-16") * 86_400 + 60),
+                completed_at: *completed_at,
+            });
+        }
+        stats.finish();
+
+        let sections = stats
+            .sections
+            .iter()
+            .map(|s| {
+                (
+                    s.path.join("."),
+                    s.counts.total,
+                    s.counts.done,
+                    s.counts.overdue,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (String::new(), 4, 2, 1),
+                ("api".to_string(), 2, 1, 1),
+                ("web".to_string(), 1, 0, 0),
+            ],
+            sections
+        );
+        assert_eq!(Some(50), stats.sections[0].counts.percent_done());
+
+        let days = stats
+            .days
+            .iter()
+            .map(|d| (d.created, d.completed, d.remaining))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(4, 0, 4), (0, 1, 3), (0, 1, 2)], days);
+    }
+
+    #[test]
+    fn draws_a_burndown() {
+        let day = due::parse_date("2026-10-17").unwrap();
+        let mut stats = Stats::new(Vec::new(), day, day + 1);
+        stats.days[0].remaining = 4;
+        stats.days[1].remaining = 2;
+        stats.days[1].completed = 2;
+
+        assert_eq!(
+            "2026-10-17  ####     4 open  +0 -0\n2026-10-18  ##       2 open  +0 -2\n",
+            stats.burndown(4)
+        );
+    }
+}
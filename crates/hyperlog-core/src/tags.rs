@@ -0,0 +1,149 @@
+//! Labels on items such as `bug` or `@waiting`. An item's tags are the ones set
+//! on it plus every inline `#tag` in its description, see [`item_tags`]. Tags
+//! are kept lowercase and without the `#`.
+
+use std::collections::BTreeMap;
+
+/// Clean up a tag as typed: the `#` is dropped, whitespace becomes `-` and
+/// case is folded. `None` when nothing is left.
+pub fn normalize(raw: &str) -> Option<String> {
+    let tag = raw
+        .trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+
+    (!tag.is_empty() && tag != "@").then_some(tag)
+}
+
+/// [`normalize`] each tag, dropping empty ones and duplicates.
+pub fn normalize_all(raw: &[String]) -> Vec<String> {
+    let mut tags = raw.iter().filter_map(|t| normalize(t)).collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '/')
+}
+
+/// Inline `#tag`s in `body`. The `#` has to start a word and be followed by a
+/// letter, so markdown headings, `#123` issue numbers and URL fragments are
+/// left alone.
+pub fn parse_inline(body: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut prev = None;
+    for (i, c) in body.char_indices() {
+        if c == '#' && prev.is_none_or(|p: char| p.is_whitespace() || p == '(') {
+            let rest = &body[i + 1..];
+            if rest.chars().next().is_some_and(char::is_alphabetic) {
+                let end = rest.find(|c| !is_tag_char(c)).unwrap_or(rest.len());
+                let tag = rest[..end].trim_end_matches(['-', '_', '/']);
+                tags.push(tag.to_lowercase());
+            }
+        }
+        prev = Some(c);
+    }
+
+    tags
+}
+
+/// Everything an item is tagged with: `tags` and the inline ones in
+/// `description`, sorted and without duplicates.
+pub fn item_tags(tags: &[String], description: &str) -> Vec<String> {
+    let mut all = normalize_all(tags);
+    all.extend(parse_inline(description));
+    all.sort();
+    all.dedup();
+    all
+}
+
+/// Whether an item with `tags` and `description` carries `filter`, which is
+/// normalized first so `#Bug` and `bug` are the same filter.
+pub fn matches(tags: &[String], description: &str, filter: &str) -> bool {
+    match normalize(filter) {
+        Some(filter) => item_tags(tags, description).contains(&filter),
+        None => true,
+    }
+}
+
+/// How a tag is shown: contexts keep their `@`, everything else gets a `#`.
+pub fn display(tag: &str) -> String {
+    if tag.starts_with('@') {
+        tag.to_string()
+    } else {
+        format!("#{tag}")
+    }
+}
+
+/// Tally how many items carry each tag, given every item's [`item_tags`].
+/// Most used first, then by name.
+pub fn count(items: impl IntoIterator<Item = Vec<String>>) -> Vec<(String, i64)> {
+    let mut tally = Tally::default();
+    for tags in items {
+        tally.add(tags);
+    }
+    tally.into_counts()
+}
+
+/// [`count`] for items that arrive one at a time.
+#[derive(Default, Debug)]
+pub struct Tally(BTreeMap<String, i64>);
+
+impl Tally {
+    /// Count one item's [`item_tags`].
+    pub fn add(&mut self, tags: Vec<String>) {
+        for tag in tags {
+            *self.0.entry(tag).or_default() += 1;
+        }
+    }
+
+    /// Most used first, then by name.
+    pub fn into_counts(self) -> Vec<(String, i64)> {
+        let mut counts = self.0.into_iter().collect::<Vec<_>>();
+        counts.sort_by(|(a_tag, a), (b_tag, b)| b.cmp(a).then_with(|| a_tag.cmp(b_tag)));
+        counts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalizes_typed_tags() {
+        assert_eq!(Some("bug".into()), normalize("#Bug"));
+        assert_eq!(Some("@waiting".into()), normalize(" @waiting "));
+        assert_eq!(Some("needs-review".into()), normalize("needs review"));
+        assert_eq!(None, normalize("#"));
+        assert_eq!(
+            vec!["@home".to_string(), "bug".into()],
+            normalize_all(&["bug".into(), "#BUG".into(), "@home".into(), "".into()])
+        );
+    }
+
+    #[test]
+    fn parses_inline_tags() {
+        assert_eq!(
+            vec!["bug", "ui/layout", "later"],
+            parse_inline("a #bug in the (#ui/layout) code, #later.")
+        );
+        assert!(parse_inline("# Heading\nsee #123 and https://x.dev/a#frag").is_empty());
+    }
+
+    #[test]
+    fn filters_and_counts() {
+        let tags = vec!["@home".to_string()];
+        assert!(matches(&tags, "fix the #Bug", "#bug"));
+        assert!(matches(&tags, "", "@home"));
+        assert!(!matches(&tags, "", "bug"));
+
+        assert_eq!(
+            vec![("bug".to_string(), 2), ("@home".into(), 1)],
+            count([item_tags(&tags, "#bug"), vec!["bug".into()]])
+        );
+    }
+}
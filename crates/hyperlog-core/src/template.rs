@@ -0,0 +1,189 @@
+//! Section blueprints. A template is an ordinary section kept under the
+//! root's [`TEMPLATES_SECTION`]; applying it copies its children to a new
+//! section, replacing `{{variable}}` in keys, titles, descriptions and section
+//! metadata. Item dues may be relative to an anchor date (`+3d`, `-1w`).
+
+use std::collections::BTreeMap;
+
+use crate::{due, log::SectionMeta, validate};
+
+/// Top-level section holding a root's templates.
+pub const TEMPLATES_SECTION: &str = "templates";
+
+/// Path of the template called `name`.
+pub fn template_path(name: &str) -> Vec<String> {
+    vec![TEMPLATES_SECTION.to_string(), name.to_string()]
+}
+
+/// Replace every `{{name}}` in `text` with its value from `vars`. Unknown
+/// variables are an error rather than being left in place.
+pub fn substitute(text: &str, vars: &BTreeMap<String, String>) -> anyhow::Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        let after = &rest[open + 2..];
+        let Some(close) = after.find("}}") else {
+            break;
+        };
+        let name = after[..close].trim();
+        let value = vars
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("template variable is not set: {name}"))?;
+        out.push_str(&rest[..open]);
+        out.push_str(value);
+        rest = &after[close + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Substitute a node key, which must stay a single non-empty path segment.
+pub fn substitute_key(key: &str, vars: &BTreeMap<String, String>) -> anyhow::Result<String> {
+    let key = substitute(key, vars)?;
+    if key.is_empty() || key.contains('.') {
+        anyhow::bail!("template key must be non-empty and cannot contain '.': {key:?}");
+    }
+    Ok(key)
+}
+
+/// Parse a `name=value` variable assignment.
+pub fn parse_var(raw: &str) -> anyhow::Result<(String, String)> {
+    match raw.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.to_string()))
+        }
+        _ => anyhow::bail!("expected a variable as name=value, got: {raw}"),
+    }
+}
+
+/// Days from the anchor date for a relative due such as `+3d`, `-1w` or
+/// `+0d`; `None` when `due` is not relative or too far off to count.
+pub fn relative_due_days(due: &str) -> Option<i64> {
+    let due = due.trim();
+    let sign = match due.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let body = &due[1..];
+    let (count, per) = match body.strip_suffix('d') {
+        Some(count) => (count, 1),
+        None => (body.strip_suffix('w')?, 7),
+    };
+    if count.is_empty() || !count.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let count: i64 = count.parse().ok()?;
+    count.checked_mul(per).map(|days| sign * days)
+}
+
+/// The anchor date as days since 1970-01-01; today when `None`.
+pub fn anchor(raw: Option<&str>) -> anyhow::Result<i64> {
+    match raw {
+        Some(raw) => due::parse_date(raw)
+            .ok_or_else(|| anyhow::anyhow!("expected an anchor date as YYYY-MM-DD, got: {raw}")),
+        None => Ok(due::today()),
+    }
+}
+
+/// Substitute a template item's due and resolve it against `anchor` when it
+/// is relative.
+pub fn render_due(
+    raw: &str,
+    vars: &BTreeMap<String, String>,
+    anchor: i64,
+) -> anyhow::Result<String> {
+    let rendered = substitute(raw, vars)?;
+    Ok(match relative_due_days(&rendered) {
+        Some(days) => due::format_date(
+            due::add_days(anchor, days)
+                .ok_or_else(|| anyhow::anyhow!("due out of range: {rendered}"))?,
+        ),
+        None => rendered,
+    })
+}
+
+/// Substitute the text of a template section's metadata, which has to be
+/// valid metadata once rendered.
+pub fn render_meta(
+    meta: &SectionMeta,
+    vars: &BTreeMap<String, String>,
+) -> anyhow::Result<SectionMeta> {
+    let meta = SectionMeta {
+        title: substitute(&meta.title, vars)?,
+        description: substitute(&meta.description, vars)?,
+        colour: substitute(&meta.colour, vars)?,
+        icon: substitute(&meta.icon, vars)?,
+        collapsed: meta.collapsed,
+    };
+    validate::section(&meta)?;
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vars() -> BTreeMap<String, String> {
+        [("version", "1.2"), ("owner", "kjuulh")]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .into()
+    }
+
+    #[test]
+    fn substitutes_variables() {
+        assert_eq!(
+            "release 1.2 by kjuulh, {{ unclosed",
+            substitute("release {{version}} by {{ owner }}, {{ unclosed", &vars()).unwrap()
+        );
+        assert!(substitute("{{missing}}", &vars()).is_err());
+        assert!(substitute_key("v{{version}}", &vars()).is_err());
+        assert_eq!(
+            "by-kjuulh",
+            substitute_key("by-{{owner}}", &vars()).unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_relative_dues() {
+        assert_eq!(Some(3), relative_due_days("+3d"));
+        assert_eq!(Some(-14), relative_due_days("-2w"));
+        assert_eq!(Some(0), relative_due_days("+0d"));
+        assert_eq!(None, relative_due_days("2026-10-18"));
+        assert_eq!(None, relative_due_days("+d"));
+        assert_eq!(None, relative_due_days("+3m"));
+        assert_eq!(None, relative_due_days("+9223372036854775807w"));
+        assert_eq!(None, relative_due_days("+99999999999999999999d"));
+
+        let anchor = anchor(Some("2026-10-30")).unwrap();
+        assert_eq!("2026-11-02", render_due("+3d", &vars(), anchor).unwrap());
+        assert_eq!("2026-10-16", render_due("-2w", &vars(), anchor).unwrap());
+        assert_eq!("v1.2", render_due("v{{version}}", &vars(), anchor).unwrap());
+        assert!(render_due("+9999999999999999d", &vars(), anchor).is_err());
+    }
+
+    #[test]
+    fn renders_section_meta() {
+        let meta = SectionMeta {
+            title: "Release {{version}}".into(),
+            description: "Owned by {{owner}}".into(),
+            collapsed: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            SectionMeta {
+                title: "Release 1.2".into(),
+                description: "Owned by kjuulh".into(),
+                collapsed: true,
+                ..Default::default()
+            },
+            render_meta(&meta, &vars()).unwrap()
+        );
+
+        let bad_colour = SectionMeta {
+            colour: "{{owner}}".into(),
+            ..Default::default()
+        };
+        assert!(render_meta(&bad_colour, &vars()).is_err());
+    }
+}
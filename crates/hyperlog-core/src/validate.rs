@@ -0,0 +1,353 @@
+//! Checks for the free-form fields clients send: dues, start dates,
+//! recurrence rules, link URLs and checklist entries of items, and the
+//! metadata of sections. Anything that later has to be parsed (agenda
+//! bucketing, opening a link) is checked before it is written. Failures name
+//! the offending field so clients can point at it.
+
+use crate::{
+    checklist::ChecklistEntry,
+    due,
+    log::{Link, SectionMeta},
+    recur,
+    template::{relative_due_days, TEMPLATES_SECTION},
+};
+
+/// Longest icon a section may have, in characters; enough for an emoji
+/// sequence.
+pub const MAX_ICON_LEN: usize = 8;
+
+/// Link schemes an item may point at.
+pub const LINK_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FieldError {
+    /// Field path within the item, e.g. `due` or `links[1].url`.
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// One or more invalid fields on an item.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Invalid(pub Vec<FieldError>);
+
+impl std::fmt::Display for Invalid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid item: ")?;
+        for (i, err) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Invalid {}
+
+/// Check the due, start, recurrence and links of the item at `path`. Items
+/// under the templates section may also hold relative (`+3d`) or
+/// `{{templated}}` dates; those are checked again once the template is
+/// applied.
+pub fn item(
+    path: &[String],
+    due: Option<&str>,
+    start: Option<&str>,
+    recur: Option<&str>,
+    links: &[Link],
+) -> Result<(), Invalid> {
+    let mut errors = Vec::new();
+    let is_template = path.first().map(String::as_str) == Some(TEMPLATES_SECTION);
+    let rendered_later =
+        |raw: &str| is_template && (raw.contains("{{") || relative_due_days(raw).is_some());
+    for (field, raw) in [("due", due), ("start", start)] {
+        if let Some(raw) = raw.filter(|raw| !rendered_later(raw)) {
+            if let Err(message) = self::due(raw) {
+                errors.push(FieldError {
+                    field: field.into(),
+                    message,
+                });
+            }
+        }
+    }
+    if let Some(Err(e)) = recur.map(recur::Rule::parse) {
+        errors.push(FieldError {
+            field: "recur".into(),
+            message: e.to_string(),
+        });
+    }
+    for (i, link) in links.iter().enumerate() {
+        if let Err(message) = link_url(&link.url) {
+            errors.push(FieldError {
+                field: format!("links[{i}].url"),
+                message,
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Invalid(errors))
+    }
+}
+
+/// Checklist entries need a title on a single line, the way the editor
+/// frontmatter writes them.
+pub fn checklist(entries: &[ChecklistEntry]) -> Result<(), Invalid> {
+    let errors = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            let message = if entry.title.trim().is_empty() {
+                "a checklist entry needs a title"
+            } else if entry.title.contains('\n') {
+                "a checklist entry title must be a single line"
+            } else {
+                return None;
+            };
+            Some(FieldError {
+                field: format!("checklist[{i}].title"),
+                message: message.into(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Invalid(errors))
+    }
+}
+
+/// A section's title and icon are shown on a single line, and its colour has
+/// to be `#rrggbb` for clients to draw it.
+pub fn section(meta: &SectionMeta) -> Result<(), Invalid> {
+    let mut errors = Vec::new();
+    if meta.title.contains('\n') {
+        errors.push(FieldError {
+            field: "title".into(),
+            message: "a section title must be a single line".into(),
+        });
+    }
+    if !meta.colour.is_empty() && meta.rgb().is_none() {
+        errors.push(FieldError {
+            field: "colour".into(),
+            message: format!("expected a colour as #rrggbb, got: {}", meta.colour),
+        });
+    }
+    if meta.icon.contains('\n') || meta.icon.chars().count() > MAX_ICON_LEN {
+        errors.push(FieldError {
+            field: "icon".into(),
+            message: format!("an icon must be a single line of at most {MAX_ICON_LEN} characters"),
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Invalid(errors))
+    }
+}
+
+/// An ISO date, `YYYY-MM-DD`, optionally followed by a time of day
+/// (`THH:MM` or `THH:MM:SS`) and a timezone (`Z` or `+HH:MM`).
+pub fn due(raw: &str) -> Result<(), String> {
+    let invalid = || {
+        Err(format!(
+            "expected YYYY-MM-DD with an optional THH:MM[:SS] and timezone, got: {raw:?}"
+        ))
+    };
+    if raw.get(..10).and_then(due::parse_date).is_none() {
+        return invalid();
+    }
+    let rest = &raw[10..];
+    if rest.is_empty() {
+        return Ok(());
+    }
+    let Some(time) = rest.strip_prefix(['T', ' ']) else {
+        return invalid();
+    };
+
+    let (clock, zone) = match time.find(['Z', '+', '-']) {
+        Some(at) => time.split_at(at),
+        None => (time, ""),
+    };
+    let fields = clock.split(':').collect::<Vec<_>>();
+    let in_range = |field: &str, max: u32| {
+        field.len() == 2
+            && field.bytes().all(|b| b.is_ascii_digit())
+            && field.parse::<u32>().is_ok_and(|v| v <= max)
+    };
+    let clock_ok = match fields.as_slice() {
+        [hour, minute] => in_range(hour, 23) && in_range(minute, 59),
+        [hour, minute, second] => {
+            in_range(hour, 23) && in_range(minute, 59) && in_range(second, 59)
+        }
+        _ => false,
+    };
+    let zone_ok = match zone.as_bytes().first() {
+        None => true,
+        Some(b'Z') => zone.len() == 1,
+        Some(_) => match zone[1..].split_once(':') {
+            Some((hour, minute)) => in_range(hour, 23) && in_range(minute, 59),
+            None => false,
+        },
+    };
+    if clock_ok && zone_ok {
+        Ok(())
+    } else {
+        invalid()
+    }
+}
+
+/// An absolute URL with one of the [`LINK_SCHEMES`]. `http(s)` links need a
+/// host and `mailto` links an address.
+pub fn link_url(raw: &str) -> Result<(), String> {
+    if raw.is_empty() {
+        return Err("url cannot be empty".into());
+    }
+    if raw.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("url cannot contain whitespace: {raw:?}"));
+    }
+    let Some((scheme, rest)) = raw.split_once(':') else {
+        return Err(format!("url has no scheme: {raw:?}"));
+    };
+    let scheme = scheme.to_ascii_lowercase();
+    match scheme.as_str() {
+        "http" | "https" => {
+            let host = rest
+                .strip_prefix("//")
+                .map(|rest| rest.split(['/', '?', '#']).next().unwrap_or_default())
+                .unwrap_or_default();
+            let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+            if host.is_empty() || host.starts_with(':') {
+                return Err(format!("url has no host: {raw:?}"));
+            }
+            Ok(())
+        }
+        "mailto" => match rest.split('?').next().unwrap_or_default().split_once('@') {
+            Some((user, domain)) if !user.is_empty() && !domain.is_empty() => Ok(()),
+            _ => Err(format!("mailto url has no address: {raw:?}")),
+        },
+        _ => Err(format!(
+            "unsupported url scheme {scheme:?}, expected one of: {}",
+            LINK_SCHEMES.join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validates_dues() {
+        for ok in [
+            "2026-10-18",
+            "2026-10-18T09:30",
+            "2026-10-18T09:30:15",
+            "2026-10-18T09:30Z",
+            "2026-10-18T09:30:15+02:00",
+            "2026-10-18 09:30-05:00",
+        ] {
+            assert_eq!(Ok(()), due(ok), "{ok}");
+        }
+        for bad in [
+            "",
+            "tomorrow",
+            "2026-02-30",
+            "2026-10-18T",
+            "2026-10-18T9:30",
+            "2026-10-18T24:00",
+            "2026-10-18T09:30+2",
+            "2026-10-18T09:30ZZ",
+            "2026-10-18x",
+            "+3d",
+        ] {
+            assert!(due(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn validates_link_urls() {
+        for ok in [
+            "https://example.com",
+            "http://user@example.com:8080/a?b#c",
+            "HTTPS://example.com",
+            "mailto:someone@example.com",
+        ] {
+            assert_eq!(Ok(()), link_url(ok), "{ok}");
+        }
+        for bad in [
+            "",
+            "example.com",
+            "https://",
+            "https:///path",
+            "https://exa mple.com",
+            "javascript:alert(1)",
+            "file:///etc/passwd",
+            "mailto:",
+        ] {
+            assert!(link_url(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn reports_every_invalid_field() {
+        let links = vec![
+            Link {
+                title: "ok".into(),
+                url: "https://example.com".into(),
+            },
+            Link {
+                title: "bad".into(),
+                url: "ftp://example.com".into(),
+            },
+        ];
+        let path = vec!["some-section".to_string(), "item".to_string()];
+        let err = item(&path, Some("soon"), Some("later"), Some("hourly"), &links).unwrap_err();
+        let fields = err.0.iter().map(|e| e.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["due", "start", "recur", "links[1].url"], fields);
+
+        let template = vec![TEMPLATES_SECTION.to_string(), "release".to_string()];
+        assert_eq!(
+            Ok(()),
+            item(&template, Some("+3d"), Some("{{start}}"), None, &links[..1])
+        );
+        let err = item(&template, Some("soon"), None, None, &[]).unwrap_err();
+        assert_eq!("due", err.0[0].field);
+    }
+
+    #[test]
+    fn validates_checklist_titles() {
+        let entries = ["step", " ", "two\nlines"].map(ChecklistEntry::new);
+        let err = checklist(&entries).unwrap_err();
+        let fields = err.0.iter().map(|e| e.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["checklist[1].title", "checklist[2].title"], fields);
+        assert_eq!(Ok(()), checklist(&entries[..1]));
+    }
+
+    #[test]
+    fn validates_section_metadata() {
+        let mut meta = SectionMeta {
+            colour: "#FF8800".into(),
+            icon: "🚀".into(),
+            ..Default::default()
+        };
+        assert_eq!(Ok(()), section(&meta));
+        assert_eq!(Some((255, 136, 0)), meta.rgb());
+
+        meta.colour = "orange".into();
+        meta.icon = "a very long icon".into();
+        let err = section(&meta).unwrap_err();
+        let fields = err.0.iter().map(|e| e.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["colour", "icon"], fields);
+    }
+}
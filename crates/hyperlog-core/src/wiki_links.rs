@@ -0,0 +1,187 @@
+//! `[[wiki-links]]` in item descriptions. A target resolves by root-relative
+//! slash-path first, then by item title / section key (first match in path
+//! order), case-insensitive. Backlinks are found with [`parse_targets`], and
+//! renames update links with [`rewrite_targets`].
+
+use std::collections::HashMap;
+
+pub fn norm(s: &str) -> String {
+    s.trim().to_lowercase()
+}
+
+/// Calls `f` with the trimmed target of every well-formed link in `body`
+/// (`[[target]]` / `[[target|alias]]`, no brackets or newlines inside), and
+/// replaces the target with whatever it returns.
+pub fn rewrite_targets(body: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(open) = rest.find("[[") {
+        let after = &rest[open + 2..];
+        let Some(close) = after.find("]]") else {
+            break;
+        };
+        out.push_str(&rest[..open + 2]);
+        let inner = &after[..close];
+        let well_formed = !inner.is_empty()
+            && !inner.contains('[')
+            && !inner.contains(']')
+            && !inner.contains('\n');
+        let (target, alias) = match inner.split_once('|') {
+            Some((target, alias)) => (target, Some(alias)),
+            None => (inner, None),
+        };
+        let new_target = if well_formed && !target.trim().is_empty() {
+            f(target.trim())
+        } else {
+            None
+        };
+        match new_target {
+            Some(new_target) => {
+                out.push_str(&new_target);
+                if let Some(alias) = alias {
+                    out.push('|');
+                    out.push_str(alias);
+                }
+            }
+            None => out.push_str(inner),
+        }
+        out.push_str("]]");
+        rest = &after[close + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The link targets written in `body`.
+pub fn parse_targets(body: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    rewrite_targets(body, |target| {
+        out.push(target.to_string());
+        None
+    });
+    out
+}
+
+/// Resolves link targets against the nodes of one root.
+pub struct LinkResolver {
+    paths: Vec<String>,
+    by_path: HashMap<String, usize>,
+    by_name: HashMap<String, usize>,
+}
+
+impl LinkResolver {
+    /// `nodes` are (dotted root-relative path, display name) in path order;
+    /// the display name is an item's title, or the key for sections.
+    pub fn new(nodes: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut resolver = Self {
+            paths: Vec::new(),
+            by_path: HashMap::new(),
+            by_name: HashMap::new(),
+        };
+        for (i, (path, name)) in nodes.into_iter().enumerate() {
+            let key = path.rsplit('.').next().unwrap_or(&path);
+            resolver
+                .by_path
+                .entry(norm(&path.replace('.', "/")))
+                .or_insert(i);
+            resolver.by_name.entry(norm(&name)).or_insert(i);
+            resolver.by_name.entry(norm(key)).or_insert(i);
+            resolver.paths.push(path);
+        }
+        resolver
+    }
+
+    /// Index (into the nodes given to [`LinkResolver::new`]) of the node `target` points at.
+    pub fn resolve(&self, target: &str) -> Option<usize> {
+        let t = target.trim();
+        if t.is_empty() {
+            return None;
+        }
+        let n = norm(t);
+        if t.contains('/') {
+            if let Some(&i) = self.by_path.get(&n) {
+                return Some(i);
+            }
+        }
+        self.by_name
+            .get(&n)
+            .or_else(|| self.by_path.get(&n))
+            .copied()
+    }
+
+    pub fn path(&self, index: usize) -> &str {
+        &self.paths[index]
+    }
+
+    /// Rewrite the links in `body` that point at `src` or below it so they
+    /// still do once `src` (a dotted path) has become `dest`. Links that
+    /// resolve by title are left alone, a rename doesn't change titles.
+    pub fn retarget(&self, body: &str, src: &str, dest: &str) -> String {
+        let src_key = src.rsplit('.').next().unwrap_or(src);
+        let dest_key = dest.rsplit('.').next().unwrap_or(dest);
+        rewrite_targets(body, |target| {
+            let i = self.resolve(target)?;
+            let path = self.path(i);
+            let below = path.strip_prefix(src)?;
+            if !below.is_empty() && !below.starts_with('.') {
+                return None;
+            }
+            if target.contains('/') && self.by_path.get(&norm(target)) == Some(&i) {
+                return Some(format!("{dest}{below}").replace('.', "/"));
+            }
+            if below.is_empty() && norm(target) == norm(src_key) {
+                return Some(dest_key.to_string());
+            }
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn resolver() -> LinkResolver {
+        LinkResolver::new(
+            [
+                ("projects", "projects"),
+                ("projects.hyperlog", "hyperlog"),
+                ("projects.hyperlog.release", "Cut a release"),
+                ("inbox", "inbox"),
+            ]
+            .map(|(path, name)| (path.to_string(), name.to_string())),
+        )
+    }
+
+    #[test]
+    fn parses_targets_and_aliases() {
+        assert_eq!(
+            vec!["a".to_string(), "b/c".to_string()],
+            parse_targets("see [[ a ]] and [[b/c|the c]], not [[]] or [[x\ny]]")
+        );
+    }
+
+    #[test]
+    fn resolves_by_path_then_name() {
+        let resolver = resolver();
+
+        assert_eq!(Some(2), resolver.resolve("Projects/Hyperlog/Release"));
+        assert_eq!(Some(2), resolver.resolve("cut a release"));
+        assert_eq!(Some(1), resolver.resolve("hyperlog"));
+        assert_eq!(None, resolver.resolve("missing"));
+    }
+
+    #[test]
+    fn retargets_links_into_a_renamed_subtree() {
+        let resolver = resolver();
+
+        assert_eq!(
+            "[[log]], [[projects/log/release|ship]], [[Cut a release]], [[inbox]] [[nope",
+            resolver.retarget(
+                "[[hyperlog]], [[projects/hyperlog/release|ship]], [[Cut a release]], [[inbox]] [[nope",
+                "projects.hyperlog",
+                "projects.log",
+            )
+        );
+    }
+}
@@ -0,0 +1,23 @@
+-- Login throttling. One counter row per throttled key: scope is 'identifier'
+-- (the lowercased email/username typed in) or 'peer' (the client address).
+CREATE TABLE IF NOT EXISTS login_throttle (
+    scope TEXT NOT NULL,
+    throttle_key TEXT NOT NULL,
+    failures BIGINT NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (scope, throttle_key)
+);
+
+-- Every login attempt, kept for auditing. user_id is set when the identifier
+-- matched an account.
+CREATE TABLE IF NOT EXISTS login_attempts (
+    id UUID NOT NULL PRIMARY KEY,
+    identifier TEXT NOT NULL,
+    peer_addr TEXT,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    succeeded BOOLEAN NOT NULL,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_attempts_identifier ON login_attempts(identifier, attempted_at);
+CREATE INDEX IF NOT EXISTS idx_login_attempts_user ON login_attempts(user_id, attempted_at);
@@ -0,0 +1,35 @@
+-- TOTP second factor. A row exists from enrolment on; it only protects the
+-- account once confirmed with a first valid code (enabled = true).
+-- last_used_step blocks replaying a code within its validity window.
+CREATE TABLE IF NOT EXISTS user_totp (
+    user_id UUID NOT NULL PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT false,
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- One-time recovery codes, stored hashed (sha256).
+CREATE TABLE IF NOT EXISTS totp_recovery_codes (
+    id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_totp_recovery_codes_user ON totp_recovery_codes(user_id);
+
+-- Password-verified logins waiting for their second factor. The challenge
+-- token is stored hashed and carries the client info the session will get.
+CREATE TABLE IF NOT EXISTS login_challenges (
+    id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL,
+    user_agent TEXT NOT NULL DEFAULT '',
+    client_name TEXT NOT NULL DEFAULT '',
+    failed_attempts BIGINT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_login_challenges_hash ON login_challenges(token_hash);
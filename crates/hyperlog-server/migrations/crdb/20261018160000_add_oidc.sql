@@ -0,0 +1,28 @@
+-- External (OIDC) identities linked to users. The provider is the name from
+-- the server's OIDC config, the subject its stable `sub` claim.
+CREATE TABLE IF NOT EXISTS user_identities (
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_login_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (provider, subject)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_identities_user ON user_identities(user_id);
+
+-- Logins sent off to a provider and not back yet. Keyed by the hashed state
+-- parameter; nonce and PKCE verifier are needed to redeem the code.
+-- initiated_by is who started the login, if anyone was signed in. Only they
+-- may finish it into a link; anyone else finishing it just logs in.
+CREATE TABLE IF NOT EXISTS oidc_login_states (
+    state_hash TEXT NOT NULL PRIMARY KEY,
+    provider TEXT NOT NULL,
+    nonce TEXT NOT NULL,
+    code_verifier TEXT NOT NULL,
+    user_agent TEXT NOT NULL DEFAULT '',
+    client_name TEXT NOT NULL DEFAULT '',
+    initiated_by UUID NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Deleting a workspace only stamps deleted_at; the root and its nodes are
+-- purged once the grace period has passed. A deleted root frees its name.
+ALTER TABLE roots ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+DROP INDEX IF EXISTS idx_roots_user_name;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_roots_user_name_live ON roots(user_id, root_name) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_roots_deleted_at ON roots(deleted_at) WHERE deleted_at IS NOT NULL;
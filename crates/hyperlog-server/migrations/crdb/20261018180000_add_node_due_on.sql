@@ -0,0 +1,7 @@
+-- The date part of item_content.due, kept by the database so agenda queries
+-- can use an index instead of walking every item. Values that aren't dates
+-- end up here too; readers parse and skip them.
+ALTER TABLE nodes ADD COLUMN IF NOT EXISTS due_on TEXT
+    GENERATED ALWAYS AS (substring(item_content->>'due' FROM 1 FOR 10)) STORED;
+CREATE INDEX IF NOT EXISTS idx_nodes_root_due_on ON nodes (root_id, due_on)
+    WHERE item_type = 'ITEM' AND status = 'active' AND due_on IS NOT NULL;
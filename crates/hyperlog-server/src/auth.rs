@@ -1,5 +1,21 @@
-//! Users & authentication: argon2id passwords, HS256 access JWTs, and opaque
-//! rotating refresh tokens (stored hashed) with reuse detection.
+//! Users & authentication: argon2id passwords, access JWTs signed by the
+//! current key of a [`keys::KeySet`], and opaque rotating refresh tokens
+//! (stored hashed) with reuse detection. Every login starts a session;
+//! refresh rotation stays within it, so sessions can be listed and revoked
+//! individually. Failed logins are throttled, see
+//! [`throttle`]. Forgotten passwords are reset through a single-use token
+//! delivered by a [`mail::MailTransport`]. Accounts can add a [`totp`] second
+//! factor, in which case login hands out a short-lived challenge that has to
+//! be completed with a code before any tokens are issued. Users can also sign
+//! in through an external [`oidc`] provider.
+
+mod keys;
+mod mail;
+mod oidc;
+mod throttle;
+mod totp;
+
+pub use keys::KeySet;
 
 use std::sync::Arc;
 
@@ -7,7 +23,6 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -17,29 +32,88 @@ use tonic::{Request, Response, Status};
 use uuid::Uuid;
 
 use hyperlog_protos::hyperlog::{
-    auth_server::Auth, AuthResponse, LoginRequest, LogoutRequest, LogoutResponse, MeRequest,
-    RefreshRequest, RegisterRequest, User as PbUser,
+    auth_server::Auth, AuthResponse, ChangePasswordRequest, ChangePasswordResponse,
+    ConfirmTotpRequest, ConfirmTotpResponse, DisableTotpRequest, DisableTotpResponse,
+    EnrollTotpRequest, EnrollTotpResponse, FinishOidcLoginRequest, ListOidcProvidersRequest,
+    ListOidcProvidersResponse, ListSessionsRequest, ListSessionsResponse, LoginRequest,
+    LogoutRequest, LogoutResponse, MeRequest, OidcProvider, RefreshRequest, RegisterRequest,
+    RequestPasswordResetRequest, RequestPasswordResetResponse, ResetPasswordRequest,
+    ResetPasswordResponse, RevokeAllOtherSessionsRequest, RevokeAllOtherSessionsResponse,
+    RevokeSessionRequest, RevokeSessionResponse, SecondFactorChallenge, Session as PbSession,
+    StartOidcLoginRequest, StartOidcLoginResponse, User as PbUser, VerifyTotpRequest,
+};
+
+use self::{
+    mail::{Mail, MailTransport, OutboxTransport},
+    oidc::{link_target, Identity, Oidc, OidcConfig, ProviderConfig},
+    throttle::{Attempt, LoginThrottle, ThrottleConfig},
 };
 
 const ACCESS_TTL_SECS: i64 = 15 * 60;
 const REFRESH_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+const RESET_TTL_SECS: i64 = 60 * 60;
+/// At most one reset mail per account in this window.
+const RESET_RESEND_SECS: i64 = 60;
+const MIN_PASSWORD_LEN: usize = 8;
+const CHALLENGE_TTL_SECS: i64 = 5 * 60;
+/// Codes a single login challenge will check before it is spent.
+const CHALLENGE_MAX_FAILURES: i64 = 5;
+const RECOVERY_CODE_COUNT: usize = 10;
+const OIDC_STATE_TTL_SECS: i64 = 10 * 60;
 
 /// Authenticated user id, injected into request extensions by the auth
 /// interceptor (see external_grpc) for downstream services.
 #[derive(Clone, Copy, Debug)]
 pub struct AuthedUser(pub Uuid);
 
+/// Session the presented access token was issued for. Absent for tokens minted
+/// before sessions existed.
+#[derive(Clone, Copy, Debug)]
+pub struct AuthedSession(pub Uuid);
+
+/// The verified contents of an access JWT.
+pub struct Access {
+    pub user_id: Uuid,
+    pub session_id: Option<Uuid>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct Claims {
     sub: String,
     exp: usize,
     iat: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sid: Option<String>,
+}
+
+/// Client metadata recorded on a session when it starts.
+struct ClientInfo {
+    user_agent: String,
+    client_name: String,
+}
+
+impl ClientInfo {
+    fn from_request<T>(request: &Request<T>, client_name: &str) -> Self {
+        let user_agent = request
+            .metadata()
+            .get("user-agent")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        Self {
+            user_agent: truncate(user_agent, 512),
+            client_name: truncate(client_name.trim(), 128),
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct AuthService {
     db: PgPool,
-    jwt_secret: Arc<Vec<u8>>,
+    keys: Arc<KeySet>,
+    throttle: LoginThrottle,
+    mailer: Arc<dyn MailTransport>,
+    totp_issuer: Arc<String>,
+    oidc: Arc<Oidc>,
 }
 
 #[derive(sqlx::FromRow)]
@@ -50,71 +124,117 @@ struct UserRow {
     password_hash: String,
 }
 
+#[derive(sqlx::FromRow)]
+struct SessionRow {
+    id: Uuid,
+    user_agent: String,
+    client_name: String,
+    created_unix: i64,
+    last_used_unix: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct TotpRow {
+    secret: String,
+    enabled: bool,
+    last_used_step: Option<i64>,
+}
+
 impl AuthService {
-    pub fn new(db: PgPool) -> Self {
-        let secret = std::env::var("HYPERLOG_JWT_SECRET")
-            .unwrap_or_else(|_| "dev-insecure-secret-change-me".to_string());
-        if secret == "dev-insecure-secret-change-me" {
-            tracing::warn!("HYPERLOG_JWT_SECRET not set — using an insecure dev secret");
-        }
-        Self {
+    pub fn new(db: PgPool, keys: Arc<KeySet>) -> anyhow::Result<Self> {
+        Ok(Self::with_mailer(
             db,
-            jwt_secret: Arc::new(secret.into_bytes()),
-        }
+            keys,
+            Arc::new(OutboxTransport::from_env()),
+            OidcConfig::from_env()?,
+        ))
     }
 
-    pub fn jwt_secret(&self) -> Arc<Vec<u8>> {
-        self.jwt_secret.clone()
+    pub fn with_mailer(
+        db: PgPool,
+        keys: Arc<KeySet>,
+        mailer: Arc<dyn MailTransport>,
+        oidc: OidcConfig,
+    ) -> Self {
+        Self {
+            throttle: LoginThrottle::new(db.clone(), ThrottleConfig::from_env()),
+            db,
+            keys,
+            mailer,
+            totp_issuer: Arc::new(
+                std::env::var("HYPERLOG_TOTP_ISSUER").unwrap_or_else(|_| "hyperlog".to_string()),
+            ),
+            oidc: Arc::new(Oidc::new(oidc)),
+        }
     }
 
-    /// Validate an access JWT and return the user id. Used by the interceptor.
-    pub fn verify_access(secret: &[u8], token: &str) -> anyhow::Result<Uuid> {
-        let data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(secret),
-            &Validation::new(Algorithm::HS256),
-        )?;
-        Ok(Uuid::parse_str(&data.claims.sub)?)
+    /// Validate an access JWT and return who it was issued to. Used by the
+    /// interceptor.
+    pub fn verify_access(keys: &KeySet, token: &str) -> anyhow::Result<Access> {
+        let claims: Claims = keys.verify(token)?;
+        Ok(Access {
+            user_id: Uuid::parse_str(&claims.sub)?,
+            session_id: claims.sid.as_deref().map(Uuid::parse_str).transpose()?,
+        })
     }
 
-    fn make_access(&self, uid: Uuid) -> anyhow::Result<(String, i64)> {
+    fn make_access(&self, uid: Uuid, session_id: Uuid) -> anyhow::Result<(String, i64)> {
         let now = OffsetDateTime::now_utc();
         let exp = now + Duration::seconds(ACCESS_TTL_SECS);
         let claims = Claims {
             sub: uid.to_string(),
             exp: exp.unix_timestamp() as usize,
             iat: now.unix_timestamp() as usize,
+            sid: Some(session_id.to_string()),
         };
-        let token = encode(
-            &Header::new(Algorithm::HS256),
-            &claims,
-            &EncodingKey::from_secret(&self.jwt_secret),
-        )?;
-        Ok((token, ACCESS_TTL_SECS))
+        Ok((self.keys.sign(&claims)?, ACCESS_TTL_SECS))
     }
 
-    async fn issue_refresh(&self, uid: Uuid) -> anyhow::Result<(String, i64)> {
+    async fn start_session(&self, uid: Uuid, client: &ClientInfo) -> anyhow::Result<Uuid> {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO sessions (id, user_id, user_agent, client_name) VALUES ($1,$2,$3,$4)",
+        )
+        .bind(id)
+        .bind(uid)
+        .bind(&client.user_agent)
+        .bind(&client.client_name)
+        .execute(&self.db)
+        .await?;
+        Ok(id)
+    }
+
+    /// Returns the raw token and the id of its row (for `replaced_by`).
+    async fn issue_refresh(&self, uid: Uuid, session_id: Uuid) -> anyhow::Result<(String, Uuid)> {
         let mut bytes = [0u8; 32];
         rand::thread_rng().fill_bytes(&mut bytes);
         let raw = hex::encode(bytes);
         let hash = sha256_hex(&raw);
         let expires = OffsetDateTime::now_utc() + Duration::seconds(REFRESH_TTL_SECS);
+        let id = Uuid::new_v4();
         sqlx::query(
-            "INSERT INTO refresh_tokens (id, user_id, token_hash, expires_at) VALUES ($1,$2,$3,$4)",
+            "INSERT INTO refresh_tokens (id, user_id, token_hash, expires_at, session_id) VALUES ($1,$2,$3,$4,$5)",
         )
-        .bind(Uuid::new_v4())
+        .bind(id)
         .bind(uid)
         .bind(&hash)
         .bind(expires)
+        .bind(session_id)
         .execute(&self.db)
         .await?;
-        Ok((raw, REFRESH_TTL_SECS))
+        Ok((raw, id))
     }
 
-    async fn auth_response(&self, user: UserRow) -> anyhow::Result<AuthResponse> {
-        let (access_token, access_expires_in) = self.make_access(user.id)?;
-        let (refresh_token, refresh_expires_in) = self.issue_refresh(user.id).await?;
-        Ok(AuthResponse {
+    /// Issue an access + refresh pair for `session_id`. Also returns the new
+    /// refresh token's row id.
+    async fn auth_response(
+        &self,
+        user: UserRow,
+        session_id: Uuid,
+    ) -> anyhow::Result<(AuthResponse, Uuid)> {
+        let (access_token, access_expires_in) = self.make_access(user.id, session_id)?;
+        let (refresh_token, refresh_id) = self.issue_refresh(user.id, session_id).await?;
+        let resp = AuthResponse {
             user: Some(PbUser {
                 id: user.id.to_string(),
                 email: user.email,
@@ -123,9 +243,410 @@ impl AuthService {
             access_token,
             access_expires_in,
             refresh_token,
-            refresh_expires_in,
+            refresh_expires_in: REFRESH_TTL_SECS,
+            second_factor: None,
+        };
+        Ok((resp, refresh_id))
+    }
+
+    async fn login_response(
+        &self,
+        user: UserRow,
+        client: &ClientInfo,
+    ) -> anyhow::Result<AuthResponse> {
+        let session_id = self.start_session(user.id, client).await?;
+        Ok(self.auth_response(user, session_id).await?.0)
+    }
+
+    /// Revoke sessions of `uid` (and their refresh tokens): only `only` when
+    /// given, every session except `except` when given, otherwise all of them.
+    /// Returns the number of sessions revoked.
+    async fn revoke_sessions(
+        &self,
+        uid: Uuid,
+        only: Option<Uuid>,
+        except: Option<Uuid>,
+    ) -> anyhow::Result<u64> {
+        let mut tx = self.db.begin().await?;
+        let revoked = revoke_sessions_in(&mut tx, uid, only, except).await?;
+        tx.commit().await?;
+        Ok(revoked)
+    }
+
+    async fn totp(&self, uid: Uuid) -> anyhow::Result<Option<TotpRow>> {
+        Ok(sqlx::query_as(
+            "SELECT secret, enabled, last_used_step FROM user_totp WHERE user_id = $1",
+        )
+        .bind(uid)
+        .fetch_optional(&self.db)
+        .await?)
+    }
+
+    /// Check a TOTP code or recovery code for a user with TOTP enabled,
+    /// consuming it on success so it can't be used again.
+    async fn check_second_factor(&self, uid: Uuid, code: &str) -> anyhow::Result<bool> {
+        let Some(totp) = self.totp(uid).await?.filter(|t| t.enabled) else {
+            return Ok(false);
+        };
+        let secret = totp::base32_decode(&totp.secret)
+            .ok_or_else(|| anyhow::anyhow!("stored TOTP secret for {uid} is not base32"))?;
+        let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+        if let Some(step) = totp::verify(&secret, code, now, totp.last_used_step.map(|s| s as u64))
+        {
+            // Conditional, so two concurrent requests can't both spend the step.
+            let res = sqlx::query(
+                "UPDATE user_totp SET last_used_step = $2 WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)",
+            )
+            .bind(uid)
+            .bind(step as i64)
+            .execute(&self.db)
+            .await?;
+            return Ok(res.rows_affected() == 1);
+        }
+
+        let res = sqlx::query(
+            "UPDATE totp_recovery_codes SET used_at = now() WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+        )
+        .bind(uid)
+        .bind(sha256_hex(&normalize_recovery_code(code)))
+        .execute(&self.db)
+        .await?;
+        Ok(res.rows_affected() == 1)
+    }
+
+    /// Password was right but a second factor is required: park the login
+    /// in a challenge for VerifyTotp to complete.
+    async fn challenge_response(
+        &self,
+        uid: Uuid,
+        client: &ClientInfo,
+    ) -> anyhow::Result<AuthResponse> {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let raw = hex::encode(bytes);
+        sqlx::query(
+            "INSERT INTO login_challenges (id, user_id, token_hash, user_agent, client_name, expires_at) VALUES ($1,$2,$3,$4,$5,$6)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(uid)
+        .bind(sha256_hex(&raw))
+        .bind(&client.user_agent)
+        .bind(&client.client_name)
+        .bind(OffsetDateTime::now_utc() + Duration::seconds(CHALLENGE_TTL_SECS))
+        .execute(&self.db)
+        .await?;
+        Ok(AuthResponse {
+            second_factor: Some(SecondFactorChallenge {
+                challenge_token: raw,
+                expires_in: CHALLENGE_TTL_SECS,
+            }),
+            ..Default::default()
         })
     }
+
+    /// Issue tokens, or a TOTP challenge if the user has a second factor.
+    async fn sign_in(&self, user: UserRow, client: &ClientInfo) -> anyhow::Result<AuthResponse> {
+        if self.totp(user.id).await?.is_some_and(|t| t.enabled) {
+            return self.challenge_response(user.id, client).await;
+        }
+        self.login_response(user, client).await
+    }
+
+    async fn user_by_id(&self, uid: Uuid) -> anyhow::Result<UserRow> {
+        Ok(
+            sqlx::query_as("SELECT id, email, username, password_hash FROM users WHERE id = $1")
+                .bind(uid)
+                .fetch_one(&self.db)
+                .await?,
+        )
+    }
+
+    /// The user an external identity signs in as: the linked one, otherwise
+    /// (as the provider config allows) `link_to`, the user with the same
+    /// verified email, or a freshly provisioned one.
+    #[allow(clippy::result_large_err)]
+    async fn oidc_user(
+        &self,
+        provider: &ProviderConfig,
+        identity: &Identity,
+        link_to: Option<Uuid>,
+    ) -> Result<UserRow, Status> {
+        let linked: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT user_id FROM user_identities WHERE provider = $1 AND subject = $2",
+        )
+        .bind(&provider.name)
+        .bind(&identity.subject)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(internal)?;
+        if let Some((uid,)) = linked {
+            if link_to.is_some_and(|other| other != uid) {
+                return Err(Status::already_exists(
+                    "this identity is linked to another account",
+                ));
+            }
+            sqlx::query(
+                "UPDATE user_identities SET last_login_at = now(), email = $3 WHERE provider = $1 AND subject = $2",
+            )
+            .bind(&provider.name)
+            .bind(&identity.subject)
+            .bind(&identity.email)
+            .execute(&self.db)
+            .await
+            .map_err(internal)?;
+            return self.user_by_id(uid).await.map_err(internal);
+        }
+
+        let email = identity.usable_email(provider);
+        let user = match (link_to, email) {
+            (Some(uid), _) => self.user_by_id(uid).await.map_err(internal)?,
+            (None, Some(email)) => {
+                let existing: Option<UserRow> = sqlx::query_as(
+                    "SELECT id, email, username, password_hash FROM users WHERE email = $1",
+                )
+                .bind(email)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(internal)?;
+                // Only a verified email may claim an existing account.
+                match existing {
+                    Some(user) if provider.link_by_email && identity.email_verified => user,
+                    Some(_) => {
+                        return Err(Status::already_exists(
+                            "an account with this email already exists; sign in to it and link this identity from there",
+                        ))
+                    }
+                    None if provider.auto_provision => {
+                        self.provision_user(email, identity).await.map_err(internal)?
+                    }
+                    None => {
+                        return Err(Status::permission_denied(
+                            "no hyperlog account is linked to this identity",
+                        ))
+                    }
+                }
+            }
+            (None, None) => {
+                return Err(Status::permission_denied(
+                    "no hyperlog account is linked to this identity, and it has no verified email that may be used",
+                ))
+            }
+        };
+
+        sqlx::query(
+            "INSERT INTO user_identities (provider, subject, user_id, email) VALUES ($1,$2,$3,$4)",
+        )
+        .bind(&provider.name)
+        .bind(&identity.subject)
+        .bind(user.id)
+        .bind(&identity.email)
+        .execute(&self.db)
+        .await
+        .map_err(internal)?;
+        Ok(user)
+    }
+
+    /// Create a user for an external identity. It gets no usable password;
+    /// one can be set through the reset flow.
+    async fn provision_user(&self, email: &str, identity: &Identity) -> anyhow::Result<UserRow> {
+        let base = username_base(identity.preferred_username.as_deref(), email);
+        let mut username = base.clone();
+        for n in 2.. {
+            let taken: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM users WHERE username = $1")
+                .bind(&username)
+                .fetch_optional(&self.db)
+                .await?;
+            if taken.is_none() {
+                break;
+            }
+            username = if n < 10 {
+                format!("{base}{n}")
+            } else {
+                format!("{base}-{}", &Uuid::new_v4().simple().to_string()[..6])
+            };
+        }
+
+        let user = UserRow {
+            id: Uuid::new_v4(),
+            email: email.to_string(),
+            username,
+            password_hash: String::new(),
+        };
+        let mut tx = self.db.begin().await?;
+        sqlx::query("INSERT INTO users (id, email, username, password_hash) VALUES ($1,$2,$3,$4)")
+            .bind(user.id)
+            .bind(&user.email)
+            .bind(&user.username)
+            .bind(&user.password_hash)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO roots (id, root_name, user_id) VALUES ($1,$2,$3)")
+            .bind(Uuid::new_v4())
+            .bind("personal")
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        tracing::info!("provisioned user {} for an external identity", user.id);
+        Ok(user)
+    }
+
+    /// Set a new password and revoke every sign-in but `except` in one
+    /// transaction, so no sign-in outlives the password it was made with.
+    /// Refresh tokens minted before sessions existed, which no session would
+    /// catch, are revoked too. Returns the number of sessions revoked.
+    async fn replace_password(
+        &self,
+        uid: Uuid,
+        password: &str,
+        except: Option<Uuid>,
+    ) -> anyhow::Result<u64> {
+        let password_hash = hash_password(password)?;
+        let mut tx = self.db.begin().await?;
+        sqlx::query("UPDATE users SET password_hash = $2 WHERE id = $1")
+            .bind(uid)
+            .bind(&password_hash)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked = true WHERE user_id = $1 AND session_id IS NULL",
+        )
+        .bind(uid)
+        .execute(&mut *tx)
+        .await?;
+        let revoked = revoke_sessions_in(&mut tx, uid, None, except).await?;
+        tx.commit().await?;
+        Ok(revoked)
+    }
+
+    /// Create a reset token for `user` and mail it, unless one was sent very
+    /// recently. Earlier unused tokens stop working.
+    async fn send_reset(&self, user: &UserRow) -> anyhow::Result<()> {
+        let recent: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT id FROM password_reset_tokens WHERE user_id = $1 AND created_at > $2 LIMIT 1",
+        )
+        .bind(user.id)
+        .bind(OffsetDateTime::now_utc() - Duration::seconds(RESET_RESEND_SECS))
+        .fetch_optional(&self.db)
+        .await?;
+        if recent.is_some() {
+            tracing::debug!("reset for {} requested again too soon, ignoring", user.id);
+            return Ok(());
+        }
+
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let raw = hex::encode(bytes);
+        let expires = OffsetDateTime::now_utc() + Duration::seconds(RESET_TTL_SECS);
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "UPDATE password_reset_tokens SET used_at = now() WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "INSERT INTO password_reset_tokens (id, user_id, token_hash, expires_at) VALUES ($1,$2,$3,$4)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(user.id)
+        .bind(sha256_hex(&raw))
+        .bind(expires)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.mailer
+            .send(Mail {
+                to: user.email.clone(),
+                subject: "Reset your hyperlog password".to_string(),
+                body: format!(
+                    "Hi {},\n\nSomeone asked to reset the password for your hyperlog account.\n\
+                     Use this token to choose a new one within the next {} minutes:\n\n    {}\n\n\
+                     If this wasn't you, you can ignore this mail.\n",
+                    user.username,
+                    RESET_TTL_SECS / 60,
+                    raw
+                ),
+            })
+            .await
+    }
+}
+
+/// [`AuthService::revoke_sessions`] inside a transaction the caller owns.
+async fn revoke_sessions_in(
+    conn: &mut sqlx::PgConnection,
+    uid: Uuid,
+    only: Option<Uuid>,
+    except: Option<Uuid>,
+) -> anyhow::Result<u64> {
+    let revoked: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        UPDATE sessions SET revoked = true
+        WHERE user_id = $1 AND revoked = false
+          AND ($2::uuid IS NULL OR id = $2)
+          AND ($3::uuid IS NULL OR id <> $3)
+        RETURNING id
+        "#,
+    )
+    .bind(uid)
+    .bind(only)
+    .bind(except)
+    .fetch_all(&mut *conn)
+    .await?;
+    let ids: Vec<Uuid> = revoked.into_iter().map(|(id,)| id).collect();
+    sqlx::query("UPDATE refresh_tokens SET revoked = true WHERE session_id = ANY($1)")
+        .bind(&ids)
+        .execute(&mut *conn)
+        .await?;
+    Ok(ids.len() as u64)
+}
+
+/// Ten characters from the base32 alphabet, shown as `xxxxx-xxxxx`.
+fn generate_recovery_code() -> String {
+    let mut bytes = [0u8; 7];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let code = totp::base32_encode(&bytes).to_lowercase();
+    format!("{}-{}", &code[..5], &code[5..10])
+}
+
+/// A username from the IdP's preferred username or the email's local part,
+/// reduced to characters safe everywhere.
+fn username_base(preferred: Option<&str>, email: &str) -> String {
+    let raw = preferred.unwrap_or_else(|| email.split('@').next().unwrap_or_default());
+    let cleaned: String = raw
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        .take(48)
+        .collect();
+    if cleaned.len() < 2 {
+        "user".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    s.chars().take(max_chars).collect()
+}
+
+#[allow(clippy::result_large_err)]
+fn authed_user<T>(request: &Request<T>) -> Result<Uuid, Status> {
+    request
+        .extensions()
+        .get::<AuthedUser>()
+        .map(|u| u.0)
+        .ok_or_else(|| Status::unauthenticated("not authenticated"))
 }
 
 fn sha256_hex(s: &str) -> String {
@@ -153,6 +674,19 @@ fn internal<E: std::fmt::Display>(e: E) -> Status {
     Status::internal(e.to_string())
 }
 
+/// `ResourceExhausted` carrying the wait both in the message and as a
+/// `retry-after` (seconds) trailer for clients that want to back off.
+fn too_many_attempts(wait: Duration) -> Status {
+    let secs = (wait.whole_milliseconds() as u64).div_ceil(1000).max(1);
+    let mut metadata = tonic::metadata::MetadataMap::new();
+    metadata.insert("retry-after", secs.into());
+    Status::with_metadata(
+        tonic::Code::ResourceExhausted,
+        format!("too many login attempts, retry in {secs}s"),
+        metadata,
+    )
+}
+
 fn is_unique_violation(e: &sqlx::Error) -> bool {
     matches!(e, sqlx::Error::Database(db) if db.code().as_deref() == Some("23505"))
 }
@@ -163,6 +697,7 @@ impl Auth for AuthService {
         &self,
         request: Request<RegisterRequest>,
     ) -> Result<Response<AuthResponse>, Status> {
+        let client = ClientInfo::from_request(&request, &request.get_ref().client_name);
         let req = request.into_inner();
         let email = req.email.trim().to_lowercase();
         let username = req.username.trim().to_string();
@@ -172,8 +707,10 @@ impl Auth for AuthService {
         if username.len() < 2 {
             return Err(Status::invalid_argument("username too short"));
         }
-        if req.password.len() < 8 {
-            return Err(Status::invalid_argument("password must be at least 8 characters"));
+        if req.password.len() < MIN_PASSWORD_LEN {
+            return Err(Status::invalid_argument(format!(
+                "password must be at least {MIN_PASSWORD_LEN} characters"
+            )));
         }
 
         let password_hash = hash_password(&req.password).map_err(internal)?;
@@ -207,13 +744,17 @@ impl Auth for AuthService {
             username,
             password_hash,
         };
-        Ok(Response::new(self.auth_response(user).await.map_err(internal)?))
+        Ok(Response::new(
+            self.login_response(user, &client).await.map_err(internal)?,
+        ))
     }
 
     async fn login(
         &self,
         request: Request<LoginRequest>,
     ) -> Result<Response<AuthResponse>, Status> {
+        let client = ClientInfo::from_request(&request, &request.get_ref().client_name);
+        let peer = request.remote_addr().map(|addr| addr.ip());
         let req = request.into_inner();
         let ident = req.identifier.trim().to_lowercase();
         let user: Option<UserRow> = sqlx::query_as(
@@ -224,33 +765,68 @@ impl Auth for AuthService {
         .await
         .map_err(internal)?;
 
-        let user = user.ok_or_else(|| Status::unauthenticated("invalid credentials"))?;
-        if !verify_password(&req.password, &user.password_hash) {
-            return Err(Status::unauthenticated("invalid credentials"));
+        let attempt = Attempt {
+            identifier: &ident,
+            account: user.as_ref().map(|u| u.id),
+            second_factor: false,
+            peer,
+        };
+        if let Some(wait) = self.throttle.reserve(&attempt).await.map_err(internal)? {
+            return Err(too_many_attempts(wait));
+        }
+
+        let user = match user {
+            Some(user) if verify_password(&req.password, &user.password_hash) => user,
+            user => {
+                self.throttle
+                    .record_failure(&attempt, user.map(|u| u.id))
+                    .await
+                    .map_err(internal)?;
+                return Err(Status::unauthenticated("invalid credentials"));
+            }
+        };
+        let uid = user.id;
+        let response = self.sign_in(user, &client).await.map_err(internal)?;
+        // With a second factor the login only succeeds once VerifyTotp does.
+        if response.second_factor.is_none() {
+            self.throttle
+                .record_success(&attempt, uid)
+                .await
+                .map_err(internal)?;
         }
-        Ok(Response::new(self.auth_response(user).await.map_err(internal)?))
+
+        Ok(Response::new(response))
     }
 
     async fn refresh(
         &self,
         request: Request<RefreshRequest>,
     ) -> Result<Response<AuthResponse>, Status> {
+        let client = ClientInfo::from_request(&request, "");
         let req = request.into_inner();
         let hash = sha256_hex(&req.refresh_token);
 
-        let row: Option<(Uuid, Uuid, bool, OffsetDateTime)> = sqlx::query_as(
-            "SELECT id, user_id, revoked, expires_at FROM refresh_tokens WHERE token_hash = $1",
-        )
-        .bind(&hash)
-        .fetch_optional(&self.db)
-        .await
-        .map_err(internal)?;
+        let row: Option<(Uuid, Uuid, bool, OffsetDateTime, Option<Uuid>, Option<bool>)> =
+            sqlx::query_as(
+                "SELECT t.id, t.user_id, t.revoked, t.expires_at, t.session_id, s.revoked
+                 FROM refresh_tokens t LEFT JOIN sessions s ON s.id = t.session_id
+                 WHERE t.token_hash = $1",
+            )
+            .bind(&hash)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(internal)?;
 
-        let (token_id, user_id, revoked, expires_at) =
+        let (token_id, user_id, revoked, expires_at, session_id, session_revoked) =
             row.ok_or_else(|| Status::unauthenticated("invalid refresh token"))?;
 
+        if session_revoked == Some(true) {
+            // Explicitly signed out elsewhere; not a reuse, don't burn the chain.
+            return Err(Status::unauthenticated("session revoked"));
+        }
         if revoked {
             // Reuse of an already-rotated token => likely theft. Burn the chain.
+            let _ = self.revoke_sessions(user_id, None, None).await;
             let _ = sqlx::query("UPDATE refresh_tokens SET revoked = true WHERE user_id = $1")
                 .bind(user_id)
                 .execute(&self.db)
@@ -268,12 +844,33 @@ impl Auth for AuthService {
                 .await
                 .map_err(internal)?;
 
-        let resp = self.auth_response(user).await.map_err(internal)?;
+        let session_id = match session_id {
+            Some(id) => {
+                sqlx::query("UPDATE sessions SET last_used_at = now() WHERE id = $1")
+                    .bind(id)
+                    .execute(&self.db)
+                    .await
+                    .map_err(internal)?;
+                id
+            }
+            // Token from before sessions existed: adopt it into a fresh one.
+            None => self
+                .start_session(user_id, &client)
+                .await
+                .map_err(internal)?,
+        };
+
+        let (resp, new_token_id) = self
+            .auth_response(user, session_id)
+            .await
+            .map_err(internal)?;
         // Rotate: revoke the presented token.
-        let _ = sqlx::query("UPDATE refresh_tokens SET revoked = true WHERE id = $1")
-            .bind(token_id)
-            .execute(&self.db)
-            .await;
+        let _ =
+            sqlx::query("UPDATE refresh_tokens SET revoked = true, replaced_by = $2 WHERE id = $1")
+                .bind(token_id)
+                .bind(new_token_id)
+                .execute(&self.db)
+                .await;
         Ok(Response::new(resp))
     }
 
@@ -283,20 +880,23 @@ impl Auth for AuthService {
     ) -> Result<Response<LogoutResponse>, Status> {
         let req = request.into_inner();
         let hash = sha256_hex(&req.refresh_token);
-        sqlx::query("UPDATE refresh_tokens SET revoked = true WHERE token_hash = $1")
-            .bind(&hash)
-            .execute(&self.db)
-            .await
-            .map_err(internal)?;
+        let session: Option<(Uuid, Option<Uuid>)> = sqlx::query_as(
+            "UPDATE refresh_tokens SET revoked = true WHERE token_hash = $1 RETURNING user_id, session_id",
+        )
+        .bind(&hash)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(internal)?;
+        if let Some((user_id, Some(session_id))) = session {
+            self.revoke_sessions(user_id, Some(session_id), None)
+                .await
+                .map_err(internal)?;
+        }
         Ok(Response::new(LogoutResponse {}))
     }
 
     async fn me(&self, request: Request<MeRequest>) -> Result<Response<PbUser>, Status> {
-        let uid = request
-            .extensions()
-            .get::<AuthedUser>()
-            .map(|u| u.0)
-            .ok_or_else(|| Status::unauthenticated("not authenticated"))?;
+        let uid = authed_user(&request)?;
         let user: UserRow =
             sqlx::query_as("SELECT id, email, username, password_hash FROM users WHERE id = $1")
                 .bind(uid)
@@ -309,4 +909,531 @@ impl Auth for AuthService {
             username: user.username,
         }))
     }
+
+    async fn list_sessions(
+        &self,
+        request: Request<ListSessionsRequest>,
+    ) -> Result<Response<ListSessionsResponse>, Status> {
+        let uid = authed_user(&request)?;
+        let current = request.extensions().get::<AuthedSession>().map(|s| s.0);
+
+        // Active = not revoked and still holding a live refresh token.
+        let rows: Vec<SessionRow> = sqlx::query_as(
+            r#"
+            SELECT
+                s.id,
+                s.user_agent,
+                s.client_name,
+                extract(epoch from s.created_at)::bigint AS created_unix,
+                extract(epoch from s.last_used_at)::bigint AS last_used_unix
+            FROM sessions s
+            WHERE s.user_id = $1 AND s.revoked = false
+              AND EXISTS (
+                SELECT 1 FROM refresh_tokens t
+                WHERE t.session_id = s.id AND t.revoked = false AND t.expires_at > now()
+              )
+            ORDER BY s.last_used_at DESC
+            "#,
+        )
+        .bind(uid)
+        .fetch_all(&self.db)
+        .await
+        .map_err(internal)?;
+
+        Ok(Response::new(ListSessionsResponse {
+            sessions: rows
+                .into_iter()
+                .map(|r| PbSession {
+                    id: r.id.to_string(),
+                    user_agent: r.user_agent,
+                    client_name: r.client_name,
+                    created_unix: r.created_unix,
+                    last_used_unix: r.last_used_unix,
+                    current: Some(r.id) == current,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn revoke_session(
+        &self,
+        request: Request<RevokeSessionRequest>,
+    ) -> Result<Response<RevokeSessionResponse>, Status> {
+        let uid = authed_user(&request)?;
+        let session_id = Uuid::parse_str(request.get_ref().session_id.trim())
+            .map_err(|_| Status::invalid_argument("session_id must be a uuid"))?;
+
+        let revoked = self
+            .revoke_sessions(uid, Some(session_id), None)
+            .await
+            .map_err(internal)?;
+        if revoked == 0 {
+            return Err(Status::not_found("session not found"));
+        }
+        Ok(Response::new(RevokeSessionResponse {}))
+    }
+
+    async fn revoke_all_other_sessions(
+        &self,
+        request: Request<RevokeAllOtherSessionsRequest>,
+    ) -> Result<Response<RevokeAllOtherSessionsResponse>, Status> {
+        let uid = authed_user(&request)?;
+        let current = request
+            .extensions()
+            .get::<AuthedSession>()
+            .map(|s| s.0)
+            .ok_or_else(|| {
+                Status::failed_precondition("current session unknown; refresh or log in again")
+            })?;
+
+        let revoked = self
+            .revoke_sessions(uid, None, Some(current))
+            .await
+            .map_err(internal)?;
+        Ok(Response::new(RevokeAllOtherSessionsResponse {
+            revoked: revoked as i64,
+        }))
+    }
+
+    async fn change_password(
+        &self,
+        request: Request<ChangePasswordRequest>,
+    ) -> Result<Response<ChangePasswordResponse>, Status> {
+        let uid = authed_user(&request)?;
+        let current = request.extensions().get::<AuthedSession>().map(|s| s.0);
+        let peer = request.remote_addr().map(|addr| addr.ip());
+        let req = request.into_inner();
+        if req.new_password.len() < MIN_PASSWORD_LEN {
+            return Err(Status::invalid_argument(format!(
+                "password must be at least {MIN_PASSWORD_LEN} characters"
+            )));
+        }
+
+        let user: UserRow =
+            sqlx::query_as("SELECT id, email, username, password_hash FROM users WHERE id = $1")
+                .bind(uid)
+                .fetch_one(&self.db)
+                .await
+                .map_err(|_| Status::not_found("user not found"))?;
+
+        // Guessing the old password with a stolen access token is throttled
+        // just like guessing it at login.
+        let attempt = Attempt {
+            identifier: &user.email,
+            account: Some(uid),
+            second_factor: false,
+            peer,
+        };
+        if let Some(wait) = self.throttle.reserve(&attempt).await.map_err(internal)? {
+            return Err(too_many_attempts(wait));
+        }
+        if !verify_password(&req.old_password, &user.password_hash) {
+            self.throttle
+                .record_failure(&attempt, Some(uid))
+                .await
+                .map_err(internal)?;
+            return Err(Status::unauthenticated("invalid credentials"));
+        }
+        self.throttle.release(&attempt).await.map_err(internal)?;
+
+        let revoked = self
+            .replace_password(uid, &req.new_password, current)
+            .await
+            .map_err(internal)?;
+        Ok(Response::new(ChangePasswordResponse {
+            revoked_sessions: revoked as i64,
+        }))
+    }
+
+    async fn request_password_reset(
+        &self,
+        request: Request<RequestPasswordResetRequest>,
+    ) -> Result<Response<RequestPasswordResetResponse>, Status> {
+        let req = request.into_inner();
+        let ident = req.identifier.trim().to_lowercase();
+        let user: Option<UserRow> = sqlx::query_as(
+            "SELECT id, email, username, password_hash FROM users WHERE email = $1 OR username = $1",
+        )
+        .bind(&ident)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(internal)?;
+
+        // Same answer either way, so this can't be used to probe for accounts.
+        if let Some(user) = user {
+            if let Err(e) = self.send_reset(&user).await {
+                tracing::error!("failed to send password reset for {}: {e:#}", user.id);
+            }
+        }
+        Ok(Response::new(RequestPasswordResetResponse {}))
+    }
+
+    async fn reset_password(
+        &self,
+        request: Request<ResetPasswordRequest>,
+    ) -> Result<Response<ResetPasswordResponse>, Status> {
+        let req = request.into_inner();
+        if req.new_password.len() < MIN_PASSWORD_LEN {
+            return Err(Status::invalid_argument(format!(
+                "password must be at least {MIN_PASSWORD_LEN} characters"
+            )));
+        }
+
+        // Spend the token first so it can't be used twice, even concurrently.
+        let row: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            UPDATE password_reset_tokens SET used_at = now()
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > now()
+            RETURNING user_id
+            "#,
+        )
+        .bind(sha256_hex(req.token.trim()))
+        .fetch_optional(&self.db)
+        .await
+        .map_err(internal)?;
+        let (uid,) =
+            row.ok_or_else(|| Status::unauthenticated("invalid or expired reset token"))?;
+
+        self.replace_password(uid, &req.new_password, None)
+            .await
+            .map_err(internal)?;
+
+        self.throttle.clear(uid).await.map_err(internal)?;
+        Ok(Response::new(ResetPasswordResponse {}))
+    }
+
+    async fn enroll_totp(
+        &self,
+        request: Request<EnrollTotpRequest>,
+    ) -> Result<Response<EnrollTotpResponse>, Status> {
+        let uid = authed_user(&request)?;
+        if self
+            .totp(uid)
+            .await
+            .map_err(internal)?
+            .is_some_and(|t| t.enabled)
+        {
+            return Err(Status::failed_precondition(
+                "TOTP is already enabled; disable it before enrolling again",
+            ));
+        }
+        let user: UserRow =
+            sqlx::query_as("SELECT id, email, username, password_hash FROM users WHERE id = $1")
+                .bind(uid)
+                .fetch_one(&self.db)
+                .await
+                .map_err(|_| Status::not_found("user not found"))?;
+
+        // Enrolling again before confirming just replaces the pending secret.
+        let secret = totp::generate_secret();
+        sqlx::query(
+            r#"
+            INSERT INTO user_totp (user_id, secret) VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET
+                secret = excluded.secret, last_used_step = NULL, created_at = now()
+            "#,
+        )
+        .bind(uid)
+        .bind(totp::base32_encode(&secret))
+        .execute(&self.db)
+        .await
+        .map_err(internal)?;
+
+        Ok(Response::new(EnrollTotpResponse {
+            secret: totp::base32_encode(&secret),
+            provisioning_uri: totp::provisioning_uri(&self.totp_issuer, &user.email, &secret),
+        }))
+    }
+
+    async fn confirm_totp(
+        &self,
+        request: Request<ConfirmTotpRequest>,
+    ) -> Result<Response<ConfirmTotpResponse>, Status> {
+        let uid = authed_user(&request)?;
+        let req = request.into_inner();
+        let pending = match self.totp(uid).await.map_err(internal)? {
+            Some(t) if t.enabled => {
+                return Err(Status::failed_precondition("TOTP is already enabled"))
+            }
+            Some(t) => t,
+            None => return Err(Status::failed_precondition("no pending TOTP enrolment")),
+        };
+        let secret = totp::base32_decode(&pending.secret)
+            .ok_or_else(|| internal("stored TOTP secret is not base32"))?;
+        let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+        let step = totp::verify(&secret, &req.code, now, None)
+            .ok_or_else(|| Status::invalid_argument("invalid code"))?;
+
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| generate_recovery_code())
+            .collect();
+        let mut tx = self.db.begin().await.map_err(internal)?;
+        sqlx::query("UPDATE user_totp SET enabled = true, last_used_step = $2 WHERE user_id = $1")
+            .bind(uid)
+            .bind(step as i64)
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?;
+        sqlx::query("DELETE FROM totp_recovery_codes WHERE user_id = $1")
+            .bind(uid)
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?;
+        for code in &codes {
+            sqlx::query(
+                "INSERT INTO totp_recovery_codes (id, user_id, code_hash) VALUES ($1,$2,$3)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(uid)
+            .bind(sha256_hex(&normalize_recovery_code(code)))
+            .execute(&mut *tx)
+            .await
+            .map_err(internal)?;
+        }
+        tx.commit().await.map_err(internal)?;
+
+        Ok(Response::new(ConfirmTotpResponse {
+            recovery_codes: codes,
+        }))
+    }
+
+    async fn disable_totp(
+        &self,
+        request: Request<DisableTotpRequest>,
+    ) -> Result<Response<DisableTotpResponse>, Status> {
+        let uid = authed_user(&request)?;
+        let peer = request.remote_addr().map(|addr| addr.ip());
+        let req = request.into_inner();
+        let user: UserRow =
+            sqlx::query_as("SELECT id, email, username, password_hash FROM users WHERE id = $1")
+                .bind(uid)
+                .fetch_one(&self.db)
+                .await
+                .map_err(|_| Status::not_found("user not found"))?;
+
+        let attempt = Attempt {
+            identifier: &user.email,
+            account: Some(uid),
+            second_factor: false,
+            peer,
+        };
+        if let Some(wait) = self.throttle.reserve(&attempt).await.map_err(internal)? {
+            return Err(too_many_attempts(wait));
+        }
+        if !verify_password(&req.password, &user.password_hash)
+            || !self
+                .check_second_factor(uid, &req.code)
+                .await
+                .map_err(internal)?
+        {
+            self.throttle
+                .record_failure(&attempt, Some(uid))
+                .await
+                .map_err(internal)?;
+            return Err(Status::unauthenticated("invalid credentials"));
+        }
+        self.throttle.release(&attempt).await.map_err(internal)?;
+
+        let mut tx = self.db.begin().await.map_err(internal)?;
+        for table in ["user_totp", "totp_recovery_codes"] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE user_id = $1"))
+                .bind(uid)
+                .execute(&mut *tx)
+                .await
+                .map_err(internal)?;
+        }
+        tx.commit().await.map_err(internal)?;
+        Ok(Response::new(DisableTotpResponse {}))
+    }
+
+    async fn verify_totp(
+        &self,
+        request: Request<VerifyTotpRequest>,
+    ) -> Result<Response<AuthResponse>, Status> {
+        let peer = request.remote_addr().map(|addr| addr.ip());
+        let req = request.into_inner();
+        // Every guess is counted before it is checked, and only while the
+        // challenge has guesses left, so concurrent guesses can't go past them.
+        let row: Option<(Uuid, Uuid, String, String)> = sqlx::query_as(
+            r#"
+            UPDATE login_challenges SET failed_attempts = failed_attempts + 1
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > now()
+                AND failed_attempts < $2
+            RETURNING id, user_id, user_agent, client_name
+            "#,
+        )
+        .bind(sha256_hex(req.challenge_token.trim()))
+        .bind(CHALLENGE_MAX_FAILURES)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(internal)?;
+        let (challenge_id, uid, user_agent, client_name) =
+            row.ok_or_else(|| Status::unauthenticated("invalid or expired challenge"))?;
+
+        // Throttled per account rather than per challenge, so logging in again
+        // for a fresh challenge doesn't buy more guesses.
+        let identifier = format!("totp:{uid}");
+        let attempt = Attempt {
+            identifier: &identifier,
+            account: Some(uid),
+            second_factor: true,
+            peer,
+        };
+        if let Some(wait) = self.throttle.reserve(&attempt).await.map_err(internal)? {
+            return Err(too_many_attempts(wait));
+        }
+        if !self
+            .check_second_factor(uid, &req.code)
+            .await
+            .map_err(internal)?
+        {
+            self.throttle
+                .record_failure(&attempt, Some(uid))
+                .await
+                .map_err(internal)?;
+            return Err(Status::unauthenticated("invalid code"));
+        }
+
+        let res = sqlx::query(
+            "UPDATE login_challenges SET used_at = now() WHERE id = $1 AND used_at IS NULL",
+        )
+        .bind(challenge_id)
+        .execute(&self.db)
+        .await
+        .map_err(internal)?;
+        if res.rows_affected() != 1 {
+            return Err(Status::unauthenticated("invalid or expired challenge"));
+        }
+
+        let user: UserRow =
+            sqlx::query_as("SELECT id, email, username, password_hash FROM users WHERE id = $1")
+                .bind(uid)
+                .fetch_one(&self.db)
+                .await
+                .map_err(internal)?;
+        // The login this challenge completes only succeeds now, so its
+        // password attempt is given back here rather than at Login.
+        self.throttle.release(&attempt).await.map_err(internal)?;
+        self.throttle
+            .record_success(
+                &Attempt {
+                    identifier: &user.email,
+                    account: Some(uid),
+                    second_factor: false,
+                    peer,
+                },
+                uid,
+            )
+            .await
+            .map_err(internal)?;
+        let client = ClientInfo {
+            user_agent,
+            client_name,
+        };
+        Ok(Response::new(
+            self.login_response(user, &client).await.map_err(internal)?,
+        ))
+    }
+
+    async fn list_oidc_providers(
+        &self,
+        _request: Request<ListOidcProvidersRequest>,
+    ) -> Result<Response<ListOidcProvidersResponse>, Status> {
+        Ok(Response::new(ListOidcProvidersResponse {
+            providers: self
+                .oidc
+                .providers()
+                .iter()
+                .map(|p| OidcProvider {
+                    name: p.name.clone(),
+                    display_name: p.display_name.clone().unwrap_or_else(|| p.name.clone()),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn start_oidc_login(
+        &self,
+        request: Request<StartOidcLoginRequest>,
+    ) -> Result<Response<StartOidcLoginResponse>, Status> {
+        let client = ClientInfo::from_request(&request, &request.get_ref().client_name);
+        // Remembered so that only this user can finish the login into a link.
+        let initiated_by = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        let provider = self
+            .oidc
+            .provider(&req.provider)
+            .ok_or_else(|| Status::not_found(format!("unknown provider {}", req.provider)))?;
+        let auth = self
+            .oidc
+            .authorization_request(provider)
+            .await
+            .map_err(|e| Status::unavailable(format!("provider {}: {e:#}", provider.name)))?;
+
+        sqlx::query(
+            "INSERT INTO oidc_login_states (state_hash, provider, nonce, code_verifier, user_agent, client_name, expires_at, initiated_by) VALUES ($1,$2,$3,$4,$5,$6,$7,$8)",
+        )
+        .bind(sha256_hex(&auth.state))
+        .bind(&provider.name)
+        .bind(&auth.nonce)
+        .bind(&auth.code_verifier)
+        .bind(&client.user_agent)
+        .bind(&client.client_name)
+        .bind(OffsetDateTime::now_utc() + Duration::seconds(OIDC_STATE_TTL_SECS))
+        .bind(initiated_by)
+        .execute(&self.db)
+        .await
+        .map_err(internal)?;
+
+        Ok(Response::new(StartOidcLoginResponse {
+            authorization_url: auth.url,
+            state: auth.state,
+            expires_in: OIDC_STATE_TTL_SECS,
+        }))
+    }
+
+    async fn finish_oidc_login(
+        &self,
+        request: Request<FinishOidcLoginRequest>,
+    ) -> Result<Response<AuthResponse>, Status> {
+        let caller = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+
+        // Deleting makes the state single-use.
+        let row: Option<(String, String, String, String, String, Option<Uuid>)> = sqlx::query_as(
+            r#"
+            DELETE FROM oidc_login_states WHERE state_hash = $1 AND expires_at > now()
+            RETURNING provider, nonce, code_verifier, user_agent, client_name, initiated_by
+            "#,
+        )
+        .bind(sha256_hex(&req.state))
+        .fetch_optional(&self.db)
+        .await
+        .map_err(internal)?;
+        let (provider, nonce, code_verifier, user_agent, client_name, initiated_by) =
+            row.ok_or_else(|| Status::unauthenticated("invalid or expired login state"))?;
+        let link_to = link_target(initiated_by, caller);
+        let provider = self
+            .oidc
+            .provider(&provider)
+            .ok_or_else(|| Status::failed_precondition("provider is no longer configured"))?;
+
+        let identity = self
+            .oidc
+            .exchange(provider, &req.code, &code_verifier, &nonce)
+            .await
+            .map_err(|e| {
+                tracing::info!("oidc login with {} failed: {e:#}", provider.name);
+                Status::unauthenticated("identity provider did not confirm the login")
+            })?;
+        let user = self.oidc_user(provider, &identity, link_to).await?;
+
+        let client = ClientInfo {
+            user_agent,
+            client_name,
+        };
+        Ok(Response::new(
+            self.sign_in(user, &client).await.map_err(internal)?,
+        ))
+    }
 }
@@ -0,0 +1,285 @@
+//! Access token signing keys. Tokens are signed EdDSA (Ed25519) with the
+//! current signing key and carry its `kid`; every loaded key verifies, and the
+//! public halves are published as a JWKS for other services.
+//!
+//! Config via env:
+//!   HYPERLOG_JWT_KEYS_DIR    directory of Ed25519 PKCS#8 PEM private keys,
+//!                            one per file; the file stem is the `kid`
+//!                            (`openssl genpkey -algorithm ed25519 -out 2026-10.pem`)
+//!   HYPERLOG_JWT_SIGNING_KID which key signs (default: the last by name)
+//!   HYPERLOG_JWT_SECRET      legacy HS256 secret; signs only when no keys are
+//!                            configured, otherwise it only verifies kid-less tokens
+//!   HYPERLOG_DEV_MODE        allows starting with no keys (an ephemeral one is
+//!                            generated) or with the old insecure default secret
+//!
+//! Rotating: add the new key everywhere while pinning HYPERLOG_JWT_SIGNING_KID
+//! to the old one, then unpin so the new key signs, and remove the old key
+//! once its last access tokens have expired. Refresh tokens are not JWTs, so
+//! nobody gets logged out along the way.
+
+use std::path::Path;
+
+use anyhow::Context;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse,
+    },
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use ring::{
+    rand::SystemRandom,
+    signature::{Ed25519KeyPair, KeyPair},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// What `HYPERLOG_JWT_SECRET` used to silently default to.
+const INSECURE_DEFAULT_SECRET: &str = "dev-insecure-secret-change-me";
+
+struct SigningKey {
+    kid: String,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    /// Raw 32 byte Ed25519 public key.
+    public: Vec<u8>,
+}
+
+impl SigningKey {
+    fn from_pkcs8(kid: String, der: &[u8]) -> anyhow::Result<Self> {
+        let pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der)
+            .map_err(|e| anyhow::anyhow!("key {kid} is not an Ed25519 PKCS#8 key: {e}"))?;
+        let public = pair.public_key().as_ref().to_vec();
+        Ok(Self {
+            encoding: EncodingKey::from_ed_der(der),
+            decoding: DecodingKey::from_ed_der(&public),
+            kid,
+            public,
+        })
+    }
+
+    fn jwk(&self) -> Jwk {
+        Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(KeyAlgorithm::EdDSA),
+                key_id: Some(self.kid.clone()),
+                ..Default::default()
+            },
+            algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(&self.public),
+            }),
+        }
+    }
+}
+
+struct LegacySecret {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+pub struct KeySet {
+    keys: Vec<SigningKey>,
+    /// Index into `keys` of the key that signs.
+    signing: usize,
+    legacy: Option<LegacySecret>,
+}
+
+impl KeySet {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let dev_mode = std::env::var("HYPERLOG_DEV_MODE")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        let mut keys = match std::env::var("HYPERLOG_JWT_KEYS_DIR") {
+            Ok(dir) => load_dir(Path::new(&dir))?,
+            Err(_) => Vec::new(),
+        };
+
+        let legacy = match std::env::var("HYPERLOG_JWT_SECRET") {
+            Ok(secret) if secret == INSECURE_DEFAULT_SECRET && !dev_mode => anyhow::bail!(
+                "HYPERLOG_JWT_SECRET is the well-known insecure default; \
+                 configure HYPERLOG_JWT_KEYS_DIR (or set HYPERLOG_DEV_MODE=true)"
+            ),
+            Ok(secret) => Some(LegacySecret {
+                encoding: EncodingKey::from_secret(secret.as_bytes()),
+                decoding: DecodingKey::from_secret(secret.as_bytes()),
+            }),
+            Err(_) => None,
+        };
+
+        if keys.is_empty() {
+            match (&legacy, dev_mode) {
+                (Some(_), _) => tracing::warn!(
+                    "signing access tokens with HS256 from HYPERLOG_JWT_SECRET; \
+                     configure HYPERLOG_JWT_KEYS_DIR to publish verifiable keys"
+                ),
+                (None, true) => {
+                    tracing::warn!("HYPERLOG_DEV_MODE: signing with an ephemeral key");
+                    keys.push(ephemeral_key()?);
+                }
+                (None, false) => anyhow::bail!(
+                    "no JWT signing keys configured; set HYPERLOG_JWT_KEYS_DIR \
+                     (or HYPERLOG_DEV_MODE=true for an ephemeral key)"
+                ),
+            }
+        }
+
+        let signing = match std::env::var("HYPERLOG_JWT_SIGNING_KID") {
+            Ok(kid) => keys
+                .iter()
+                .position(|k| k.kid == kid)
+                .with_context(|| format!("HYPERLOG_JWT_SIGNING_KID {kid} is not a loaded key"))?,
+            Err(_) => keys.len().saturating_sub(1),
+        };
+        if let Some(key) = keys.get(signing) {
+            tracing::info!("signing access tokens with kid {}", key.kid);
+        }
+
+        Ok(Self {
+            keys,
+            signing,
+            legacy,
+        })
+    }
+
+    pub fn sign<T: Serialize>(&self, claims: &T) -> anyhow::Result<String> {
+        match (self.keys.get(self.signing), &self.legacy) {
+            (Some(key), _) => {
+                let mut header = Header::new(Algorithm::EdDSA);
+                header.kid = Some(key.kid.clone());
+                Ok(encode(&header, claims, &key.encoding)?)
+            }
+            (None, Some(legacy)) => Ok(encode(
+                &Header::new(Algorithm::HS256),
+                claims,
+                &legacy.encoding,
+            )?),
+            (None, None) => anyhow::bail!("no signing key"),
+        }
+    }
+
+    pub fn verify<T: DeserializeOwned>(&self, token: &str) -> anyhow::Result<T> {
+        let header = decode_header(token)?;
+        let (key, alg) = match (&header.kid, &self.legacy) {
+            (Some(kid), _) => {
+                let key = self
+                    .keys
+                    .iter()
+                    .find(|k| &k.kid == kid)
+                    .with_context(|| format!("unknown kid {kid}"))?;
+                (&key.decoding, Algorithm::EdDSA)
+            }
+            (None, Some(legacy)) => (&legacy.decoding, Algorithm::HS256),
+            (None, None) => anyhow::bail!("token has no kid"),
+        };
+        Ok(decode::<T>(token, key, &Validation::new(alg))?.claims)
+    }
+
+    /// Public keys of every loaded key, for `/.well-known/jwks.json`.
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self.keys.iter().map(SigningKey::jwk).collect(),
+        }
+    }
+}
+
+fn load_dir(dir: &Path) -> anyhow::Result<Vec<SigningKey>> {
+    let mut paths = std::fs::read_dir(dir)
+        .with_context(|| format!("reading HYPERLOG_JWT_KEYS_DIR {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|p| p.extension().is_some_and(|ext| ext == "pem"));
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let kid = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .with_context(|| format!("{} has no usable name", path.display()))?
+                .to_string();
+            let contents = std::fs::read(path)?;
+            let pem = pem::parse(contents)
+                .with_context(|| format!("{} is not a PEM file", path.display()))?;
+            SigningKey::from_pkcs8(kid, pem.contents())
+        })
+        .collect()
+}
+
+fn ephemeral_key() -> anyhow::Result<SigningKey> {
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+        .map_err(|_| anyhow::anyhow!("generating ephemeral key"))?;
+    let kid = format!("dev-{}", uuid::Uuid::new_v4().simple());
+    SigningKey::from_pkcs8(kid, pkcs8.as_ref())
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Claims {
+        sub: String,
+        exp: usize,
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            sub: "someone".into(),
+            exp: 4_000_000_000,
+        }
+    }
+
+    #[test]
+    fn tokens_signed_by_a_rotated_out_key_still_verify() -> anyhow::Result<()> {
+        let old = ephemeral_key()?;
+        let new = ephemeral_key()?;
+        let old_kid = old.kid.clone();
+        let mut keys = KeySet {
+            keys: vec![old, new],
+            signing: 0,
+            legacy: None,
+        };
+        let before = keys.sign(&claims())?;
+        assert_eq!(Some(old_kid), decode_header(&before)?.kid);
+
+        keys.signing = 1;
+        let after = keys.sign(&claims())?;
+        assert_eq!(claims(), keys.verify::<Claims>(&before)?);
+        assert_eq!(claims(), keys.verify::<Claims>(&after)?);
+
+        keys.keys.remove(0);
+        assert!(keys.verify::<Claims>(&before).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn jwks_publishes_keys_that_verify_tokens() -> anyhow::Result<()> {
+        let keys = KeySet {
+            keys: vec![ephemeral_key()?],
+            signing: 0,
+            legacy: None,
+        };
+        let token = keys.sign(&claims())?;
+
+        let jwks: JwkSet = serde_json::from_str(&serde_json::to_string(&keys.jwks())?)?;
+        let kid = decode_header(&token)?.kid.unwrap();
+        let jwk = jwks.find(&kid).expect("signing key is published");
+        let decoded = decode::<Claims>(
+            &token,
+            &DecodingKey::from_jwk(jwk)?,
+            &Validation::new(Algorithm::EdDSA),
+        )?;
+        assert_eq!(claims(), decoded.claims);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! Outgoing mail. Anything that can deliver a [`Mail`] implements
+//! [`MailTransport`]; the default [`OutboxTransport`] writes each message to a
+//! local directory so the reset flow works without an SMTP server.
+//!
+//! Config via env:
+//!   HYPERLOG_MAIL_OUTBOX (default ./outbox)
+//!   HYPERLOG_MAIL_FROM   (default hyperlog@localhost)
+
+use std::path::PathBuf;
+
+use time::{format_description::well_known::Rfc2822, OffsetDateTime};
+use uuid::Uuid;
+
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[tonic::async_trait]
+pub trait MailTransport: Send + Sync {
+    async fn send(&self, mail: Mail) -> anyhow::Result<()>;
+}
+
+/// Writes one RFC 822-style `.eml` file per message into `dir`.
+pub struct OutboxTransport {
+    dir: PathBuf,
+    from: String,
+}
+
+impl OutboxTransport {
+    pub fn new(dir: impl Into<PathBuf>, from: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            from: from.into(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("HYPERLOG_MAIL_OUTBOX").unwrap_or_else(|_| "outbox".to_string()),
+            std::env::var("HYPERLOG_MAIL_FROM")
+                .unwrap_or_else(|_| "hyperlog@localhost".to_string()),
+        )
+    }
+
+    fn render(&self, mail: &Mail, now: OffsetDateTime) -> anyhow::Result<String> {
+        Ok(format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+            self.from,
+            mail.to,
+            mail.subject,
+            now.format(&Rfc2822)?,
+            mail.body.replace('\n', "\r\n"),
+        ))
+    }
+}
+
+#[tonic::async_trait]
+impl MailTransport for OutboxTransport {
+    async fn send(&self, mail: Mail) -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let contents = self.render(&mail, now)?;
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self
+            .dir
+            .join(format!("{}-{}.eml", now.unix_timestamp(), Uuid::new_v4()));
+        tokio::fs::write(&path, contents).await?;
+        tracing::info!("wrote mail to {} into {}", mail.to, path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn outbox_writes_one_file_per_mail() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let transport = OutboxTransport::new(dir.path(), "hyperlog@example.com");
+
+        transport
+            .send(Mail {
+                to: "kasper@example.com".into(),
+                subject: "Reset your password".into(),
+                body: "line one\nline two".into(),
+            })
+            .await?;
+
+        let mut entries = std::fs::read_dir(dir.path())?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(1, entries.len());
+        let contents = std::fs::read_to_string(entries.remove(0).path())?;
+        assert!(contents.starts_with(
+            "From: hyperlog@example.com\r\nTo: kasper@example.com\r\nSubject: Reset your password\r\n"
+        ));
+        assert!(contents.ends_with("\r\n\r\nline one\r\nline two"));
+
+        Ok(())
+    }
+}
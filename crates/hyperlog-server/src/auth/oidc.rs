@@ -0,0 +1,681 @@
+//! OpenID Connect authorization-code login (with PKCE) against external
+//! identity providers. This module only talks to the providers: discovery,
+//! building the authorization URL, exchanging the code and verifying the
+//! returned id token. Linking the identity to a user happens in `auth`, and
+//! only for the user who started the login, see [`link_target`].
+//!
+//! Providers are configured in the TOML file named by `HYPERLOG_OIDC_CONFIG`:
+//!
+//! ```toml
+//! [[provider]]
+//! name = "corp"                       # used in StartOidcLogin
+//! display_name = "Corp SSO"
+//! issuer = "https://sso.example.com"  # discovery is fetched from here
+//! client_id = "hyperlog"
+//! client_secret_env = "CORP_OIDC_SECRET" # or client_secret = "..."
+//! redirect_uri = "http://127.0.0.1:8787/callback"
+//! scopes = ["openid", "email", "profile"]
+//! auto_provision = true        # create users on first login
+//! link_by_email = false        # attach to an existing user with the same verified email
+//! allowed_email_domains = ["example.com"]
+//! ```
+//!
+//! Issuers must be https, except on loopback addresses so a local mock IdP
+//! can be used for testing.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use http_body_util::{BodyExt, Full};
+use hyper::{body::Bytes, header, Method, StatusCode};
+use hyper_util::rt::TokioIo;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use rand::RngCore;
+use serde::{de::DeserializeOwned, Deserialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+    sync::RwLock,
+};
+use url::Url;
+use uuid::Uuid;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn default_scopes() -> Vec<String> {
+    vec!["openid".into(), "email".into(), "profile".into()]
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ProviderConfig {
+    pub name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    pub issuer: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// Read the secret from this env var instead of the config file.
+    #[serde(default)]
+    pub client_secret_env: Option<String>,
+    pub redirect_uri: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub auto_provision: bool,
+    #[serde(default)]
+    pub link_by_email: bool,
+    /// When non-empty, only emails in these domains may be provisioned or
+    /// linked by email.
+    #[serde(default)]
+    pub allowed_email_domains: Vec<String>,
+}
+
+impl ProviderConfig {
+    pub fn email_allowed(&self, email: &str) -> bool {
+        self.allowed_email_domains.is_empty()
+            || email.rsplit_once('@').is_some_and(|(_, domain)| {
+                self.allowed_email_domains
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(domain))
+            })
+    }
+}
+
+#[derive(Deserialize, Default, Debug)]
+pub struct OidcConfig {
+    #[serde(default, rename = "provider")]
+    pub providers: Vec<ProviderConfig>,
+}
+
+impl OidcConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let Ok(path) = std::env::var("HYPERLOG_OIDC_CONFIG") else {
+            return Ok(Self::default());
+        };
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading HYPERLOG_OIDC_CONFIG {path}"))?;
+        let mut config: Self =
+            toml::from_str(&contents).with_context(|| format!("parsing {path}"))?;
+        for provider in &mut config.providers {
+            if let Some(var) = &provider.client_secret_env {
+                provider.client_secret = Some(std::env::var(var).with_context(|| {
+                    format!("{var} (client secret of {}) is not set", provider.name)
+                })?);
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for provider in &self.providers {
+            anyhow::ensure!(
+                seen.insert(&provider.name),
+                "OIDC provider {} is configured twice",
+                provider.name
+            );
+            check_endpoint(&Url::parse(&provider.issuer)?)
+                .with_context(|| format!("issuer of OIDC provider {}", provider.name))?;
+        }
+        Ok(())
+    }
+}
+
+/// Only https, or plain http to a loopback address.
+fn check_endpoint(url: &Url) -> anyhow::Result<()> {
+    let loopback = match url.host() {
+        Some(url::Host::Domain(domain)) => domain == "localhost",
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    };
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if loopback => Ok(()),
+        _ => anyhow::bail!("{url} must use https"),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+/// A login in flight: send the user to `url`, and keep the rest for
+/// [`Oidc::exchange`] when they come back.
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+}
+
+/// The user a finished login links its identity to: the one finishing it, but
+/// only if they also started it. A login started by someone else (say an
+/// attacker who got a signed-in victim to submit their state and code) is a
+/// plain login, so the attacker's identity never lands on the victim's account.
+pub fn link_target(initiated_by: Option<Uuid>, finished_by: Option<Uuid>) -> Option<Uuid> {
+    finished_by.filter(|user| initiated_by == Some(*user))
+}
+
+/// Who the provider says logged in.
+#[derive(Debug, PartialEq)]
+pub struct Identity {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+}
+
+impl Identity {
+    /// The email, if the provider verified it and `provider` accepts its
+    /// domain. Only such an email may be matched against an existing account
+    /// or used to provision a new one.
+    pub fn usable_email(&self, provider: &ProviderConfig) -> Option<&str> {
+        self.email
+            .as_deref()
+            .filter(|_| self.email_verified)
+            .filter(|email| provider.email_allowed(email))
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    #[serde(default)]
+    preferred_username: Option<String>,
+}
+
+pub struct Oidc {
+    config: OidcConfig,
+    discovery: RwLock<HashMap<String, Arc<Discovery>>>,
+}
+
+impl Oidc {
+    pub fn new(config: OidcConfig) -> Self {
+        Self {
+            config,
+            discovery: RwLock::default(),
+        }
+    }
+
+    pub fn providers(&self) -> &[ProviderConfig] {
+        &self.config.providers
+    }
+
+    pub fn provider(&self, name: &str) -> Option<&ProviderConfig> {
+        self.config.providers.iter().find(|p| p.name == name)
+    }
+
+    async fn discover(&self, provider: &ProviderConfig) -> anyhow::Result<Arc<Discovery>> {
+        if let Some(discovery) = self.discovery.read().await.get(&provider.name) {
+            return Ok(discovery.clone());
+        }
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            provider.issuer.trim_end_matches('/')
+        );
+        let discovery: Discovery = get_json(&Url::parse(&url)?).await?;
+        anyhow::ensure!(
+            discovery.issuer.trim_end_matches('/') == provider.issuer.trim_end_matches('/'),
+            "provider {} reports issuer {}",
+            provider.name,
+            discovery.issuer
+        );
+        for endpoint in [&discovery.token_endpoint, &discovery.jwks_uri] {
+            check_endpoint(&Url::parse(endpoint)?)?;
+        }
+        let discovery = Arc::new(discovery);
+        self.discovery
+            .write()
+            .await
+            .insert(provider.name.clone(), discovery.clone());
+        Ok(discovery)
+    }
+
+    pub async fn authorization_request(
+        &self,
+        provider: &ProviderConfig,
+    ) -> anyhow::Result<AuthorizationRequest> {
+        let discovery = self.discover(provider).await?;
+        let (state, nonce, code_verifier) = (random_token(), random_token(), random_token());
+        let mut url = Url::parse(&discovery.authorization_endpoint)?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &provider.client_id)
+            .append_pair("redirect_uri", &provider.redirect_uri)
+            .append_pair("scope", &provider.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &pkce_challenge(&code_verifier))
+            .append_pair("code_challenge_method", "S256");
+        Ok(AuthorizationRequest {
+            url: url.into(),
+            state,
+            nonce,
+            code_verifier,
+        })
+    }
+
+    /// Redeem an authorization code and verify the id token that comes back.
+    pub async fn exchange(
+        &self,
+        provider: &ProviderConfig,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> anyhow::Result<Identity> {
+        let discovery = self.discover(provider).await?;
+        let form = form_urlencoded::Serializer::new(String::new())
+            .append_pair("grant_type", "authorization_code")
+            .append_pair("code", code)
+            .append_pair("redirect_uri", &provider.redirect_uri)
+            .append_pair("client_id", &provider.client_id)
+            .append_pair("code_verifier", code_verifier)
+            .finish();
+        let basic_auth = provider.client_secret.as_ref().map(|secret| {
+            let credentials = format!(
+                "{}:{}",
+                form_urlencoded::byte_serialize(provider.client_id.as_bytes()).collect::<String>(),
+                form_urlencoded::byte_serialize(secret.as_bytes()).collect::<String>(),
+            );
+            format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(credentials)
+            )
+        });
+        let tokens: TokenResponse =
+            post_form(&Url::parse(&discovery.token_endpoint)?, form, basic_auth).await?;
+
+        let jwks: JwkSet = get_json(&Url::parse(&discovery.jwks_uri)?).await?;
+        let claims = verify_id_token(
+            &tokens.id_token,
+            &jwks,
+            &discovery.issuer,
+            &provider.client_id,
+        )?;
+        anyhow::ensure!(
+            claims.nonce.as_deref() == Some(nonce),
+            "id token nonce does not match"
+        );
+
+        Ok(Identity {
+            subject: claims.sub,
+            email: claims.email.map(|e| e.trim().to_lowercase()),
+            email_verified: claims.email_verified,
+            preferred_username: claims.preferred_username,
+        })
+    }
+}
+
+fn verify_id_token(
+    token: &str,
+    jwks: &JwkSet,
+    issuer: &str,
+    client_id: &str,
+) -> anyhow::Result<IdTokenClaims> {
+    let header = decode_header(token)?;
+    anyhow::ensure!(
+        !matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ),
+        "id token uses a symmetric algorithm"
+    );
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .context("no matching key for id token")?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[issuer]);
+    validation.set_audience(&[client_id]);
+    Ok(decode::<IdTokenClaims>(token, &DecodingKey::from_jwk(jwk)?, &validation)?.claims)
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+async fn get_json<T: DeserializeOwned>(url: &Url) -> anyhow::Result<T> {
+    let body = request(Method::GET, url, None, None).await?;
+    serde_json::from_slice(&body).with_context(|| format!("decoding response from {url}"))
+}
+
+async fn post_form<T: DeserializeOwned>(
+    url: &Url,
+    form: String,
+    authorization: Option<String>,
+) -> anyhow::Result<T> {
+    let body = request(Method::POST, url, Some(form), authorization).await?;
+    serde_json::from_slice(&body).with_context(|| format!("decoding response from {url}"))
+}
+
+/// One-shot HTTP/1.1 request; plenty for the handful of calls a login makes.
+async fn request(
+    method: Method,
+    url: &Url,
+    form: Option<String>,
+    authorization: Option<String>,
+) -> anyhow::Result<Bytes> {
+    let host = url.host_str().context("url has no host")?.to_string();
+    let port = url.port_or_known_default().context("url has no port")?;
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+
+    let mut builder = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .header(header::HOST, url.authority())
+        .header(header::ACCEPT, "application/json")
+        .header(header::USER_AGENT, "hyperlog-server");
+    if let Some(authorization) = authorization {
+        builder = builder.header(header::AUTHORIZATION, authorization);
+    }
+    let req = match form {
+        Some(form) => builder
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Full::new(Bytes::from(form)))?,
+        None => builder.body(Full::new(Bytes::new()))?,
+    };
+
+    let send = async {
+        let tcp = TcpStream::connect((host.as_str(), port)).await?;
+        match url.scheme() {
+            "https" => {
+                let mut roots = tokio_rustls::rustls::RootCertStore::empty();
+                roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+                let provider = tokio_rustls::rustls::crypto::ring::default_provider();
+                let tls =
+                    tokio_rustls::rustls::ClientConfig::builder_with_provider(Arc::new(provider))
+                        .with_safe_default_protocol_versions()?
+                        .with_root_certificates(roots)
+                        .with_no_client_auth();
+                let server_name = host.clone().try_into()?;
+                let stream = tokio_rustls::TlsConnector::from(Arc::new(tls))
+                    .connect(server_name, tcp)
+                    .await?;
+                send_over(stream, req).await
+            }
+            _ => send_over(tcp, req).await,
+        }
+    };
+    let (status, body) = tokio::time::timeout(HTTP_TIMEOUT, send)
+        .await
+        .with_context(|| format!("{url} timed out"))??;
+    anyhow::ensure!(
+        status.is_success(),
+        "{url} answered {status}: {}",
+        String::from_utf8_lossy(&body)
+    );
+    Ok(body)
+}
+
+async fn send_over<S>(
+    io: S,
+    req: hyper::Request<Full<Bytes>>,
+) -> anyhow::Result<(StatusCode, Bytes)>
+where
+    S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(io)).await?;
+    tokio::spawn(async move {
+        if let Err(e) = conn.await {
+            tracing::debug!("oidc http connection: {e}");
+        }
+    });
+    let resp = sender.send_request(req).await?;
+    let status = resp.status();
+    Ok((status, resp.into_body().collect().await?.to_bytes()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use axum::{extract::State, routing, Form, Json, Router};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use ring::{
+        rand::SystemRandom,
+        signature::{Ed25519KeyPair, KeyPair},
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    /// A minimal IdP: discovery, JWKS and a token endpoint that accepts the
+    /// code "good-code" and answers with `id_token_claims` signed by its key.
+    struct MockIdp {
+        issuer: String,
+        pkcs8: Vec<u8>,
+        public: Vec<u8>,
+        id_token_claims: serde_json::Value,
+    }
+
+    async fn serve_mock_idp(claims: impl FnOnce(&str) -> serde_json::Value) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .unwrap()
+            .as_ref()
+            .to_vec();
+        let public = Ed25519KeyPair::from_pkcs8(&pkcs8)
+            .unwrap()
+            .public_key()
+            .as_ref()
+            .to_vec();
+        let idp = Arc::new(MockIdp {
+            id_token_claims: claims(&issuer),
+            issuer: issuer.clone(),
+            pkcs8,
+            public,
+        });
+
+        let app =
+            Router::new()
+                .route(
+                    "/.well-known/openid-configuration",
+                    routing::get(|State(idp): State<Arc<MockIdp>>| async move {
+                        Json(json!({
+                            "issuer": idp.issuer,
+                            "authorization_endpoint": format!("{}/authorize", idp.issuer),
+                            "token_endpoint": format!("{}/token", idp.issuer),
+                            "jwks_uri": format!("{}/jwks", idp.issuer),
+                        }))
+                    }),
+                )
+                .route(
+                    "/jwks",
+                    routing::get(|State(idp): State<Arc<MockIdp>>| async move {
+                        Json(json!({"keys": [{
+                            "kty": "OKP", "crv": "Ed25519", "kid": "mock", "alg": "EdDSA",
+                            "x": URL_SAFE_NO_PAD.encode(&idp.public),
+                        }]}))
+                    }),
+                )
+                .route(
+                    "/token",
+                    routing::post(
+                        |State(idp): State<Arc<MockIdp>>,
+                         Form(form): Form<HashMap<String, String>>| async move {
+                            if form.get("code").map(String::as_str) != Some("good-code")
+                                || !form.contains_key("code_verifier")
+                            {
+                                return Err(StatusCode::BAD_REQUEST);
+                            }
+                            let mut header = Header::new(Algorithm::EdDSA);
+                            header.kid = Some("mock".into());
+                            let id_token = encode(
+                                &header,
+                                &idp.id_token_claims,
+                                &EncodingKey::from_ed_der(&idp.pkcs8),
+                            )
+                            .unwrap();
+                            Ok(Json(json!({"id_token": id_token, "token_type": "Bearer"})))
+                        },
+                    ),
+                )
+                .with_state(idp);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        issuer
+    }
+
+    fn provider(issuer: &str) -> ProviderConfig {
+        toml::from_str(&format!(
+            r#"
+            name = "mock"
+            issuer = "{issuer}"
+            client_id = "hyperlog"
+            client_secret = "s3cret"
+            redirect_uri = "http://127.0.0.1:8787/callback"
+            "#
+        ))
+        .unwrap()
+    }
+
+    fn claims(issuer: &str, aud: &str) -> serde_json::Value {
+        json!({
+            "iss": issuer,
+            "aud": aud,
+            "sub": "user-123",
+            "exp": 4_000_000_000u64,
+            "nonce": "the-nonce",
+            "email": "Kasper@Example.com",
+            "email_verified": true,
+            "preferred_username": "kasper",
+        })
+    }
+
+    #[tokio::test]
+    async fn code_flow_against_mock_idp() -> anyhow::Result<()> {
+        let issuer = serve_mock_idp(|issuer| claims(issuer, "hyperlog")).await;
+        let provider = provider(&issuer);
+        let oidc = Oidc::new(OidcConfig {
+            providers: vec![provider.clone()],
+        });
+
+        let auth = oidc.authorization_request(&provider).await?;
+        let url = Url::parse(&auth.url)?;
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(
+            format!("{issuer}/authorize"),
+            url[..url::Position::AfterPath]
+        );
+        assert_eq!(Some(&auth.state), query.get("state"));
+        assert_eq!(
+            Some(&pkce_challenge(&auth.code_verifier)),
+            query.get("code_challenge")
+        );
+
+        let identity = oidc
+            .exchange(&provider, "good-code", &auth.code_verifier, "the-nonce")
+            .await?;
+        assert_eq!(
+            Identity {
+                subject: "user-123".into(),
+                email: Some("kasper@example.com".into()),
+                email_verified: true,
+                preferred_username: Some("kasper".into()),
+            },
+            identity
+        );
+
+        assert!(oidc
+            .exchange(&provider, "good-code", &auth.code_verifier, "other-nonce")
+            .await
+            .is_err());
+        assert!(oidc
+            .exchange(&provider, "bad-code", &auth.code_verifier, "the-nonce")
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn finishing_a_strangers_login_does_not_link() {
+        let (attacker, victim) = (Uuid::new_v4(), Uuid::new_v4());
+
+        // The attacker starts a login, signed in or not, and completes it at
+        // the IdP as themselves; the victim then submits the state and code.
+        assert_eq!(None, link_target(None, Some(victim)));
+        assert_eq!(None, link_target(Some(attacker), Some(victim)));
+
+        assert_eq!(Some(victim), link_target(Some(victim), Some(victim)));
+        assert_eq!(None, link_target(Some(victim), None));
+    }
+
+    #[test]
+    fn unverified_emails_are_never_used() {
+        let mut provider = provider("https://sso.example.com");
+        let mut identity = Identity {
+            subject: "user-123".into(),
+            email: Some("kasper@example.com".into()),
+            email_verified: false,
+            preferred_username: None,
+        };
+        assert_eq!(None, identity.usable_email(&provider));
+
+        identity.email_verified = true;
+        assert_eq!(Some("kasper@example.com"), identity.usable_email(&provider));
+
+        provider.allowed_email_domains = vec!["example.org".into()];
+        assert_eq!(None, identity.usable_email(&provider));
+    }
+
+    #[tokio::test]
+    async fn id_token_for_another_client_is_rejected() -> anyhow::Result<()> {
+        let issuer = serve_mock_idp(|issuer| claims(issuer, "someone-else")).await;
+        let provider = provider(&issuer);
+        let oidc = Oidc::new(OidcConfig {
+            providers: vec![provider.clone()],
+        });
+
+        assert!(oidc
+            .exchange(&provider, "good-code", "verifier", "the-nonce")
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn issuers_must_be_https_unless_loopback() {
+        for (issuer, ok) in [
+            ("https://sso.example.com", true),
+            ("http://127.0.0.1:9000", true),
+            ("http://localhost:9000", true),
+            ("http://sso.example.com", false),
+        ] {
+            assert_eq!(ok, check_endpoint(&Url::parse(issuer).unwrap()).is_ok());
+        }
+    }
+}
@@ -0,0 +1,447 @@
+//! Login throttling. Failed logins are counted per account and per peer
+//! address; an identifier that names no account is counted as typed. Past a
+//! few free attempts each further failure doubles the wait before the next
+//! attempt is accepted, and enough failures lock the key out for a fixed
+//! period. Counters live in the database so every replica sees the same
+//! state.
+//!
+//! An attempt is counted as a failure before the credentials are checked,
+//! in the same statement that checks the wait, so concurrent guesses can't
+//! all slip in under the limit. A success gives the reservation back.
+//!
+//! Config via env (defaults in parentheses), `<SCOPE>` being `IDENT` or `PEER`:
+//!   HYPERLOG_LOGIN_<SCOPE>_FREE_ATTEMPTS    (ident 3, peer 20)
+//!   HYPERLOG_LOGIN_<SCOPE>_BACKOFF_BASE_SECS (1)
+//!   HYPERLOG_LOGIN_<SCOPE>_BACKOFF_MAX_SECS  (300)
+//!   HYPERLOG_LOGIN_<SCOPE>_LOCKOUT_AFTER     (ident 10, peer 100)
+//!   HYPERLOG_LOGIN_<SCOPE>_LOCKOUT_SECS      (900)
+
+use std::{net::IpAddr, sync::Arc};
+
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThrottlePolicy {
+    /// Failures tolerated before any backoff applies.
+    pub free_attempts: i64,
+    pub backoff_base: Duration,
+    pub backoff_max: Duration,
+    /// Failures after which the key is locked out for `lockout_for`.
+    pub lockout_after: i64,
+    /// Lockout length; also how long a quiet key keeps its failure count.
+    pub lockout_for: Duration,
+}
+
+impl ThrottlePolicy {
+    fn from_env(scope: &str, default: ThrottlePolicy) -> Self {
+        let var = |name: &str, default: i64| -> i64 {
+            let key = format!("HYPERLOG_LOGIN_{scope}_{name}");
+            match std::env::var(&key) {
+                Ok(v) => v.parse().unwrap_or_else(|_| {
+                    tracing::warn!("{key}={v} is not a number, using {default}");
+                    default
+                }),
+                Err(_) => default,
+            }
+        };
+        Self {
+            free_attempts: var("FREE_ATTEMPTS", default.free_attempts),
+            backoff_base: Duration::seconds(var(
+                "BACKOFF_BASE_SECS",
+                default.backoff_base.whole_seconds(),
+            )),
+            backoff_max: Duration::seconds(var(
+                "BACKOFF_MAX_SECS",
+                default.backoff_max.whole_seconds(),
+            )),
+            lockout_after: var("LOCKOUT_AFTER", default.lockout_after),
+            lockout_for: Duration::seconds(var(
+                "LOCKOUT_SECS",
+                default.lockout_for.whole_seconds(),
+            )),
+        }
+    }
+
+    /// When the next attempt is allowed after `failures` consecutive failures,
+    /// the latest at `last_failure`. `None` means no wait.
+    pub fn blocked_until(
+        &self,
+        failures: i64,
+        last_failure: OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
+        if failures >= self.lockout_after {
+            return Some(last_failure + self.lockout_for);
+        }
+        let over = failures - self.free_attempts;
+        if over <= 0 {
+            return None;
+        }
+        // base * 2^(over-1), saturating well before the shift overflows.
+        let factor = 1i32
+            .checked_shl((over - 1).min(30) as u32)
+            .unwrap_or(i32::MAX);
+        let delay = self
+            .backoff_base
+            .checked_mul(factor)
+            .unwrap_or(self.backoff_max)
+            .min(self.backoff_max);
+        Some(last_failure + delay)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ThrottleConfig {
+    pub identifier: ThrottlePolicy,
+    pub peer: ThrottlePolicy,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            identifier: ThrottlePolicy {
+                free_attempts: 3,
+                backoff_base: Duration::seconds(1),
+                backoff_max: Duration::minutes(5),
+                lockout_after: 10,
+                lockout_for: Duration::minutes(15),
+            },
+            // Many users can share an address (NAT, offices), so be lenient.
+            peer: ThrottlePolicy {
+                free_attempts: 20,
+                backoff_base: Duration::seconds(1),
+                backoff_max: Duration::minutes(5),
+                lockout_after: 100,
+                lockout_for: Duration::minutes(15),
+            },
+        }
+    }
+}
+
+impl ThrottleConfig {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            identifier: ThrottlePolicy::from_env("IDENT", default.identifier),
+            peer: ThrottlePolicy::from_env("PEER", default.peer),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Scope {
+    Identifier,
+    Peer,
+}
+
+impl Scope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Identifier => "identifier",
+            Scope::Peer => "peer",
+        }
+    }
+}
+
+/// Who is attempting to log in.
+pub struct Attempt<'a> {
+    /// What was typed, normalized; logged, and the throttle key when it names
+    /// no account.
+    pub identifier: &'a str,
+    /// The account the identifier resolved to. Its email and username share
+    /// one budget, so alternating between them buys no extra guesses.
+    pub account: Option<Uuid>,
+    /// Guessing the account's second factor rather than its password, which
+    /// has a budget of its own.
+    pub second_factor: bool,
+    pub peer: Option<IpAddr>,
+}
+
+impl Attempt<'_> {
+    /// Keys are prefixed by kind, so nothing typed at login can name an
+    /// account's bucket.
+    fn identifier_key(&self) -> String {
+        match (self.account, self.second_factor) {
+            (Some(id), true) => format!("totp:{id}"),
+            (Some(id), false) => account_key(id),
+            (None, _) => format!("ident:{}", self.identifier.to_lowercase()),
+        }
+    }
+}
+
+fn account_key(id: Uuid) -> String {
+    format!("user:{id}")
+}
+
+#[derive(Clone)]
+pub struct LoginThrottle {
+    db: PgPool,
+    config: Arc<ThrottleConfig>,
+}
+
+impl LoginThrottle {
+    pub fn new(db: PgPool, config: ThrottleConfig) -> Self {
+        Self {
+            db,
+            config: Arc::new(config),
+        }
+    }
+
+    fn policy(&self, scope: Scope) -> &ThrottlePolicy {
+        match scope {
+            Scope::Identifier => &self.config.identifier,
+            Scope::Peer => &self.config.peer,
+        }
+    }
+
+    fn keys(attempt: &Attempt<'_>) -> Vec<(Scope, String)> {
+        let mut keys = vec![(Scope::Identifier, attempt.identifier_key())];
+        if let Some(peer) = attempt.peer {
+            keys.push((Scope::Peer, peer.to_string()));
+        }
+        keys
+    }
+
+    /// Count the attempt as a failure against every key, unless one of them
+    /// still has to wait: then nothing is counted and the wait is returned.
+    /// Call [`LoginThrottle::release`] or [`LoginThrottle::record_success`]
+    /// once the attempt turns out to be good.
+    pub async fn reserve(&self, attempt: &Attempt<'_>) -> anyhow::Result<Option<Duration>> {
+        let now = OffsetDateTime::now_utc();
+        let mut tx = self.db.begin().await?;
+        for (scope, key) in Self::keys(attempt) {
+            let policy = self.policy(scope);
+            // A key that has been quiet for a full lockout period starts over.
+            // Otherwise the count only goes up once the wait after the last
+            // failure is over, see `ThrottlePolicy::blocked_until`.
+            let counted: Option<(i64,)> = sqlx::query_as(
+                r#"
+                INSERT INTO login_throttle (scope, throttle_key, failures, last_failure_at)
+                VALUES ($1, $2, 1, $3)
+                ON CONFLICT (scope, throttle_key) DO UPDATE SET
+                    failures = CASE WHEN login_throttle.last_failure_at < $4
+                        THEN 1 ELSE login_throttle.failures + 1 END,
+                    last_failure_at = $3
+                WHERE login_throttle.last_failure_at < $4
+                    OR login_throttle.last_failure_at + (CASE
+                        WHEN login_throttle.failures >= $8 THEN $9
+                        WHEN login_throttle.failures <= $5 THEN 0
+                        ELSE LEAST($7, ($6::FLOAT8 * power(2::FLOAT8, LEAST(login_throttle.failures - $5 - 1, 30)::FLOAT8))::INT8)
+                    END) * INTERVAL '1 second' <= $3
+                RETURNING failures
+                "#,
+            )
+            .bind(scope.as_str())
+            .bind(&key)
+            .bind(now)
+            .bind(now - policy.lockout_for)
+            .bind(policy.free_attempts)
+            .bind(policy.backoff_base.whole_seconds())
+            .bind(policy.backoff_max.whole_seconds())
+            .bind(policy.lockout_after)
+            .bind(policy.lockout_for.whole_seconds())
+            .fetch_optional(&mut *tx)
+            .await?;
+            if counted.is_some() {
+                continue;
+            }
+
+            let (failures, last_failure): (i64, OffsetDateTime) = sqlx::query_as(
+                "SELECT failures, last_failure_at FROM login_throttle WHERE scope = $1 AND throttle_key = $2",
+            )
+            .bind(scope.as_str())
+            .bind(&key)
+            .fetch_one(&mut *tx)
+            .await?;
+            let until = policy.blocked_until(failures, last_failure).unwrap_or(now);
+            tx.rollback().await?;
+            return Ok(Some((until - now).max(Duration::seconds(1))));
+        }
+        tx.commit().await?;
+        Ok(None)
+    }
+
+    /// Log a failed attempt; it was already counted by
+    /// [`LoginThrottle::reserve`].
+    pub async fn record_failure(
+        &self,
+        attempt: &Attempt<'_>,
+        user_id: Option<Uuid>,
+    ) -> anyhow::Result<()> {
+        self.log(attempt, user_id, false).await
+    }
+
+    /// Give back a good attempt's reservation and log the success.
+    pub async fn record_success(&self, attempt: &Attempt<'_>, user_id: Uuid) -> anyhow::Result<()> {
+        self.release(attempt).await?;
+        self.log(attempt, Some(user_id), true).await
+    }
+
+    /// Give back a good attempt's reservation: the identifier's failures are
+    /// cleared, while the peer only gets this attempt back, as one good
+    /// password from an address says nothing about the other accounts being
+    /// tried from it.
+    pub async fn release(&self, attempt: &Attempt<'_>) -> anyhow::Result<()> {
+        self.clear_key(&attempt.identifier_key()).await?;
+        if let Some(peer) = attempt.peer {
+            sqlx::query(
+                "UPDATE login_throttle SET failures = failures - 1 WHERE scope = $1 AND throttle_key = $2 AND failures > 0",
+            )
+            .bind(Scope::Peer.as_str())
+            .bind(peer.to_string())
+            .execute(&self.db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Forget the account's failures, e.g. once its password was reset.
+    pub async fn clear(&self, user_id: Uuid) -> anyhow::Result<()> {
+        self.clear_key(&account_key(user_id)).await
+    }
+
+    async fn clear_key(&self, key: &str) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM login_throttle WHERE scope = $1 AND throttle_key = $2")
+            .bind(Scope::Identifier.as_str())
+            .bind(key)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn log(
+        &self,
+        attempt: &Attempt<'_>,
+        user_id: Option<Uuid>,
+        succeeded: bool,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO login_attempts (id, identifier, peer_addr, user_id, succeeded) VALUES ($1,$2,$3,$4,$5)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(attempt.identifier)
+        .bind(attempt.peer.map(|p| p.to_string()))
+        .bind(user_id)
+        .bind(succeeded)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn at() -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(1_767_268_800).unwrap()
+    }
+
+    #[test]
+    fn backoff_doubles_after_free_attempts_then_locks_out() {
+        let policy = ThrottleConfig::default().identifier;
+        let at = at();
+
+        assert_eq!(None, policy.blocked_until(0, at));
+        assert_eq!(None, policy.blocked_until(3, at));
+        assert_eq!(Some(at + Duration::seconds(1)), policy.blocked_until(4, at));
+        assert_eq!(Some(at + Duration::seconds(2)), policy.blocked_until(5, at));
+        assert_eq!(
+            Some(at + Duration::seconds(32)),
+            policy.blocked_until(9, at)
+        );
+        assert_eq!(
+            Some(at + Duration::minutes(15)),
+            policy.blocked_until(10, at)
+        );
+        assert_eq!(
+            Some(at + Duration::minutes(15)),
+            policy.blocked_until(500, at)
+        );
+    }
+
+    #[test]
+    fn failures_count_against_the_account_once_resolved() {
+        let id = Uuid::new_v4();
+        let by_email = Attempt {
+            identifier: "kasper@example.com",
+            account: Some(id),
+            second_factor: false,
+            peer: None,
+        };
+        let by_username = Attempt {
+            identifier: "kjuulh",
+            account: Some(id),
+            second_factor: false,
+            peer: None,
+        };
+        let unknown = Attempt {
+            identifier: "nobody",
+            account: None,
+            second_factor: false,
+            peer: Some("127.0.0.1".parse().unwrap()),
+        };
+
+        let keys = |attempt| {
+            LoginThrottle::keys(attempt)
+                .into_iter()
+                .map(|(_, key)| key)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(&by_email), keys(&by_username));
+        assert_eq!(vec![format!("user:{id}")], keys(&by_email));
+        assert_eq!(
+            vec!["ident:nobody".to_string(), "127.0.0.1".to_string()],
+            keys(&unknown)
+        );
+    }
+
+    #[test]
+    fn typed_identifiers_never_name_an_account_key() {
+        let id = Uuid::new_v4();
+        let key = |identifier: &str, account, second_factor| {
+            Attempt {
+                identifier,
+                account,
+                second_factor,
+                peer: None,
+            }
+            .identifier_key()
+        };
+        let password = key("kjuulh", Some(id), false);
+        let totp = key("kjuulh", Some(id), true);
+
+        assert_ne!(password, totp);
+        for typed in [&password, &totp, &password.to_uppercase()] {
+            let typed = key(typed, None, false);
+            assert_ne!(password, typed);
+            assert_ne!(totp, typed);
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let policy = ThrottlePolicy {
+            free_attempts: 0,
+            backoff_base: Duration::seconds(10),
+            backoff_max: Duration::seconds(60),
+            lockout_after: i64::MAX,
+            lockout_for: Duration::minutes(15),
+        };
+        let at = at();
+
+        assert_eq!(
+            Some(at + Duration::seconds(40)),
+            policy.blocked_until(3, at)
+        );
+        assert_eq!(
+            Some(at + Duration::seconds(60)),
+            policy.blocked_until(4, at)
+        );
+        assert_eq!(
+            Some(at + Duration::seconds(60)),
+            policy.blocked_until(200, at)
+        );
+    }
+}
@@ -3,7 +3,14 @@
 //!   EXTERNAL_GRPC_HOST (default 127.0.0.1:4000)
 //!   EXTERNAL_HOST      (default 127.0.0.1:3000)
 //!   INTERNAL_HOST      (default 127.0.0.1:3001)
-//!   DATABASE_URL, HYPERLOG_JWT_SECRET
+//!   DATABASE_URL
+//!   HYPERLOG_JWT_KEYS_DIR, HYPERLOG_JWT_SIGNING_KID, HYPERLOG_JWT_SECRET,
+//!   HYPERLOG_DEV_MODE (token signing, see auth/keys.rs)
+//!   HYPERLOG_LOGIN_* (login throttling, see auth/throttle.rs)
+//!   HYPERLOG_MAIL_OUTBOX, HYPERLOG_MAIL_FROM (password reset mail, see auth/mail.rs)
+//!   HYPERLOG_TOTP_ISSUER (default hyperlog; label in authenticator apps)
+//!   HYPERLOG_OIDC_CONFIG (TOML file of OIDC providers, see auth/oidc.rs)
+//!   HYPERLOG_ROOT_PURGE_AFTER_DAYS (default 30; grace period for deleted roots)
 use std::net::SocketAddr;
 
 fn env_addr(key: &str, default: &str) -> anyhow::Result<SocketAddr> {
@@ -1,16 +1,32 @@
-use hyperlog_core::log::ItemState;
+use hyperlog_core::{
+    checklist::ChecklistEntry,
+    convert::Kind,
+    log::{ItemState, SectionMeta},
+};
 
 use crate::{
     services::{
+        add_dependency::{self, AddDependency, AddDependencyExt},
+        apply_template::{self, ApplyTemplate, ApplyTemplateExt},
         archive::{self, Archive, ArchiveExt},
+        convert_node::{self, ConvertNode, ConvertNodeExt},
+        copy_node::{self, CopyNode, CopyNodeExt},
         create_item::{self, CreateItem, CreateItemExt},
         create_root::{self, CreateRoot, CreateRootExt},
         create_section::{self, CreateSection, CreateSectionExt},
+        delete_root::{self, DeleteRoot, DeleteRootExt},
         move_node::{self, MoveNode, MoveNodeExt},
+        remove_dependency::{self, RemoveDependency, RemoveDependencyExt},
+        rename::{self, Rename, RenameExt},
+        rename_root::{self, RenameRoot, RenameRootExt},
         reorder::{self, Reorder, ReorderExt},
         restore::{self, Restore, RestoreExt},
+        restore_root::{self, RestoreRoot, RestoreRootExt},
+        set_item_state::{self, SetItemState, SetItemStateExt},
+        snooze_item::{self, SnoozeItem, SnoozeItemExt},
         toggle_item::{self, ToggleItem, ToggleItemExt},
         update_item::{self, UpdateItem, UpdateItemExt},
+        update_section::{self, UpdateSection, UpdateSectionExt},
     },
     state::SharedState,
 };
@@ -20,6 +36,16 @@ pub enum Command {
     CreateRoot {
         root: String,
     },
+    RenameRoot {
+        root: String,
+        new_name: String,
+    },
+    DeleteRoot {
+        root: String,
+    },
+    RestoreRoot {
+        root: String,
+    },
     CreateSection {
         root: String,
         path: Vec<String>,
@@ -31,7 +57,12 @@ pub enum Command {
         description: String,
         state: ItemState,
         due: Option<String>,
+        start: Option<String>,
+        recur: Option<String>,
         links: Vec<hyperlog_core::log::Link>,
+        tags: Vec<String>,
+        priority: Option<hyperlog_core::log::Priority>,
+        checklist: Vec<ChecklistEntry>,
     },
     UpdateItem {
         root: String,
@@ -40,15 +71,28 @@ pub enum Command {
         description: String,
         state: ItemState,
         due: Option<String>,
+        start: Option<String>,
+        recur: Option<String>,
         links: Vec<hyperlog_core::log::Link>,
+        tags: Vec<String>,
+        priority: Option<hyperlog_core::log::Priority>,
+        checklist: Vec<ChecklistEntry>,
     },
     ToggleItem {
         root: String,
         path: Vec<String>,
+        /// A checklist entry, counted from 0, instead of the item.
+        checklist_entry: Option<usize>,
+    },
+    SetItemState {
+        root: String,
+        path: Vec<String>,
+        state: ItemState,
     },
     Move {
         root: String,
         src: Vec<String>,
+        dest_root: Option<String>,
         dest: Vec<String>,
     },
     Reorder {
@@ -56,6 +100,52 @@ pub enum Command {
         path: Vec<String>,
         order: Vec<String>,
     },
+    Rename {
+        root: String,
+        path: Vec<String>,
+        new_key: String,
+        update_links: bool,
+    },
+    Copy {
+        root: String,
+        src: Vec<String>,
+        dest_root: Option<String>,
+        dest: Vec<String>,
+        reset_state: bool,
+        drop_due: bool,
+    },
+    ApplyTemplate {
+        root: String,
+        template: String,
+        dest: Vec<String>,
+        vars: std::collections::BTreeMap<String, String>,
+        anchor: Option<String>,
+    },
+    AddDependency {
+        root: String,
+        path: Vec<String>,
+        depends_on: Vec<String>,
+    },
+    RemoveDependency {
+        root: String,
+        path: Vec<String>,
+        depends_on: Vec<String>,
+    },
+    SnoozeItem {
+        root: String,
+        path: Vec<String>,
+        start: Option<String>,
+    },
+    Convert {
+        root: String,
+        path: Vec<String>,
+        kind: Kind,
+    },
+    UpdateSection {
+        root: String,
+        path: Vec<String>,
+        meta: SectionMeta,
+    },
     Archive {
         root: String,
         path: Vec<String>,
@@ -69,38 +159,52 @@ pub enum Command {
 #[allow(dead_code)]
 pub struct Commander {
     create_root: CreateRoot,
+    rename_root: RenameRoot,
+    delete_root: DeleteRoot,
+    restore_root: RestoreRoot,
     create_section: CreateSection,
     create_item: CreateItem,
     update_item: UpdateItem,
     toggle_item: ToggleItem,
+    set_item_state: SetItemState,
     archive: Archive,
     restore: Restore,
     move_node: MoveNode,
     reorder: Reorder,
+    rename: Rename,
+    copy_node: CopyNode,
+    apply_template: ApplyTemplate,
+    add_dependency: AddDependency,
+    remove_dependency: RemoveDependency,
+    snooze_item: SnoozeItem,
+    convert_node: ConvertNode,
+    update_section: UpdateSection,
 }
 
 impl Commander {
-    pub fn new(
-        create_root: CreateRoot,
-        create_section: CreateSection,
-        create_item: CreateItem,
-        update_item: UpdateItem,
-        toggle_item: ToggleItem,
-        archive: Archive,
-        restore: Restore,
-        move_node: MoveNode,
-        reorder: Reorder,
-    ) -> Self {
+    pub fn new(state: &SharedState) -> Self {
         Self {
-            create_root,
-            create_section,
-            create_item,
-            update_item,
-            toggle_item,
-            archive,
-            restore,
-            move_node,
-            reorder,
+            create_root: state.create_root_service(),
+            rename_root: state.rename_root_service(),
+            delete_root: state.delete_root_service(),
+            restore_root: state.restore_root_service(),
+            create_section: state.create_section_service(),
+            create_item: state.create_item_service(),
+            update_item: state.update_item_service(),
+            toggle_item: state.toggle_item_service(),
+            set_item_state: state.set_item_state_service(),
+            archive: state.archive_service(),
+            restore: state.restore_service(),
+            move_node: state.move_node_service(),
+            reorder: state.reorder_service(),
+            rename: state.rename_service(),
+            copy_node: state.copy_node_service(),
+            apply_template: state.apply_template_service(),
+            add_dependency: state.add_dependency_service(),
+            remove_dependency: state.remove_dependency_service(),
+            snooze_item: state.snooze_item_service(),
+            convert_node: state.convert_node_service(),
+            update_section: state.update_section_service(),
         }
     }
 
@@ -117,6 +221,31 @@ impl Commander {
 
                 Ok(())
             }
+            Command::RenameRoot { root, new_name } => {
+                self.rename_root
+                    .execute(rename_root::Request {
+                        root,
+                        new_name,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::DeleteRoot { root } => {
+                self.delete_root
+                    .execute(delete_root::Request { root, user_id })
+                    .await?;
+
+                Ok(())
+            }
+            Command::RestoreRoot { root } => {
+                self.restore_root
+                    .execute(restore_root::Request { root, user_id })
+                    .await?;
+
+                Ok(())
+            }
             Command::CreateSection { root, path } => {
                 self.create_section
                     .execute(create_section::Request {
@@ -135,7 +264,12 @@ impl Commander {
                 description,
                 state,
                 due,
+                start,
+                recur,
                 links,
+                tags,
+                priority,
+                checklist,
             } => {
                 self.create_item
                     .execute(create_item::Request {
@@ -146,7 +280,12 @@ impl Commander {
                         description,
                         state,
                         due,
+                        start,
+                        recur,
                         links,
+                        tags,
+                        priority,
+                        checklist,
                     })
                     .await?;
 
@@ -159,7 +298,12 @@ impl Commander {
                 description,
                 state,
                 due,
+                start,
+                recur,
                 links,
+                tags,
+                priority,
+                checklist,
             } => {
                 self.update_item
                     .execute(update_item::Request {
@@ -170,28 +314,56 @@ impl Commander {
                         description,
                         state,
                         due,
+                        start,
+                        recur,
                         links,
+                        tags,
+                        priority,
+                        checklist,
                     })
                     .await?;
 
                 Ok(())
             }
-            Command::ToggleItem { root, path } => {
+            Command::ToggleItem {
+                root,
+                path,
+                checklist_entry,
+            } => {
                 self.toggle_item
                     .execute(toggle_item::Request {
                         root,
                         path,
+                        checklist_entry,
                         user_id,
                     })
                     .await?;
 
                 Ok(())
             }
-            Command::Move { root, src, dest } => {
+            Command::SetItemState { root, path, state } => {
+                self.set_item_state
+                    .execute(set_item_state::Request {
+                        root,
+                        path,
+                        state,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::Move {
+                root,
+                src,
+                dest_root,
+                dest,
+            } => {
                 self.move_node
                     .execute(move_node::Request {
                         root,
                         src,
+                        dest_root,
                         dest,
                         user_id,
                     })
@@ -211,6 +383,134 @@ impl Commander {
 
                 Ok(())
             }
+            Command::Rename {
+                root,
+                path,
+                new_key,
+                update_links,
+            } => {
+                self.rename
+                    .execute(rename::Request {
+                        root,
+                        path,
+                        new_key,
+                        update_links,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::Copy {
+                root,
+                src,
+                dest_root,
+                dest,
+                reset_state,
+                drop_due,
+            } => {
+                self.copy_node
+                    .execute(copy_node::Request {
+                        root,
+                        src,
+                        dest_root,
+                        dest,
+                        reset_state,
+                        drop_due,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::ApplyTemplate {
+                root,
+                template,
+                dest,
+                vars,
+                anchor,
+            } => {
+                self.apply_template
+                    .execute(apply_template::Request {
+                        root,
+                        template,
+                        dest,
+                        vars,
+                        anchor,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::AddDependency {
+                root,
+                path,
+                depends_on,
+            } => {
+                self.add_dependency
+                    .execute(add_dependency::Request {
+                        root,
+                        path,
+                        depends_on,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::RemoveDependency {
+                root,
+                path,
+                depends_on,
+            } => {
+                self.remove_dependency
+                    .execute(remove_dependency::Request {
+                        root,
+                        path,
+                        depends_on,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::SnoozeItem { root, path, start } => {
+                self.snooze_item
+                    .execute(snooze_item::Request {
+                        root,
+                        path,
+                        start,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::Convert { root, path, kind } => {
+                self.convert_node
+                    .execute(convert_node::Request {
+                        root,
+                        path,
+                        kind,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
+            Command::UpdateSection { root, path, meta } => {
+                self.update_section
+                    .execute(update_section::Request {
+                        root,
+                        path,
+                        meta,
+                        user_id,
+                    })
+                    .await?;
+
+                Ok(())
+            }
             Command::Archive { root, path } => {
                 self.archive
                     .execute(archive::Request {
@@ -243,16 +543,6 @@ pub trait CommanderExt {
 
 impl CommanderExt for SharedState {
     fn commander(&self) -> Commander {
-        Commander::new(
-            self.create_root_service(),
-            self.create_section_service(),
-            self.create_item_service(),
-            self.update_item_service(),
-            self.toggle_item_service(),
-            self.archive_service(),
-            self.restore_service(),
-            self.move_node_service(),
-            self.reorder_service(),
-        )
+        Commander::new(self)
     }
 }
@@ -7,7 +7,7 @@ use std::{collections::HashMap, net::SocketAddr};
 use tonic::{transport, Request, Response, Status};
 
 use crate::{
-    auth::{AuthService, AuthedUser},
+    auth::{Access, AuthService, AuthedSession, AuthedUser, KeySet},
     commands::{Command, Commander, CommanderExt},
     querier::{Querier, QuerierExt},
     state::SharedState,
@@ -88,27 +88,30 @@ impl Graph for Server {
         };
 
         self.commander
-            .execute(Command::CreateItem {
-                root: req.root,
-                path: req.path,
-                title: item.title,
-                description: item.description,
-                state: match item.item_state {
-                    Some(item_graph_item::ItemState::Done(_)) => {
-                        hyperlog_core::log::ItemState::Done
-                    }
-                    Some(item_graph_item::ItemState::NotDone(_)) => {
-                        hyperlog_core::log::ItemState::NotDone
-                    }
-                    None => hyperlog_core::log::ItemState::default(),
+            .execute(
+                Command::CreateItem {
+                    root: req.root,
+                    path: req.path,
+                    title: item.title,
+                    description: item.description,
+                    state: to_core_state(item.item_state),
+                    due: Some(item.due).filter(|s| !s.is_empty()),
+                    start: Some(item.start).filter(|s| !s.is_empty()),
+                    recur: Some(item.recur).filter(|s| !s.is_empty()),
+                    links: item
+                        .links
+                        .into_iter()
+                        .map(|l| hyperlog_core::log::Link {
+                            title: l.title,
+                            url: l.url,
+                        })
+                        .collect(),
+                    tags: item.tags,
+                    priority: to_core_priority(&item.priority)?,
+                    checklist: to_core_checklist(item.checklist),
                 },
-                due: Some(item.due).filter(|s| !s.is_empty()),
-                links: item
-                    .links
-                    .into_iter()
-                    .map(|l| hyperlog_core::log::Link { title: l.title, url: l.url })
-                    .collect(),
-            }, user_id)
+                user_id,
+            )
             .await
             .map_err(to_tonic_err)?;
 
@@ -138,6 +141,88 @@ impl Graph for Server {
         Ok(Response::new(CreateRootResponse {}))
     }
 
+    async fn rename_root(
+        &self,
+        request: tonic::Request<RenameRootRequest>,
+    ) -> std::result::Result<tonic::Response<RenameRootResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("rename root: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+
+        if req.new_name.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "new_name cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(
+                Command::RenameRoot {
+                    root: req.root,
+                    new_name: req.new_name,
+                },
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(RenameRootResponse {}))
+    }
+
+    async fn delete_root(
+        &self,
+        request: tonic::Request<DeleteRootRequest>,
+    ) -> std::result::Result<tonic::Response<DeleteRootResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("delete root: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(Command::DeleteRoot { root: req.root }, user_id)
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(DeleteRootResponse {}))
+    }
+
+    async fn restore_root(
+        &self,
+        request: tonic::Request<RestoreRootRequest>,
+    ) -> std::result::Result<tonic::Response<RestoreRootResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("restore root: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(Command::RestoreRoot { root: req.root }, user_id)
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(RestoreRootResponse {}))
+    }
+
     async fn create_section(
         &self,
         request: tonic::Request<CreateSectionRequest>,
@@ -211,9 +296,11 @@ impl Graph for Server {
 
         tracing::trace!("get: req({:?})", msg);
 
+        let hide_deferred = deferred_cutoff(msg.include_deferred, &msg.today)?;
+
         let res = self
             .querier
-            .get(&msg.root, msg.paths.clone(), user_id)
+            .get(&msg.root, msg.paths.clone(), hide_deferred, user_id)
             .await
             .map_err(to_tonic_err)?;
 
@@ -317,27 +404,30 @@ impl Graph for Server {
         };
 
         self.commander
-            .execute(Command::UpdateItem {
-                root: req.root,
-                path: req.path,
-                title: item.title,
-                description: item.description,
-                state: match item.item_state {
-                    Some(item_graph_item::ItemState::Done(_)) => {
-                        hyperlog_core::log::ItemState::Done
-                    }
-                    Some(item_graph_item::ItemState::NotDone(_)) => {
-                        hyperlog_core::log::ItemState::NotDone
-                    }
-                    None => hyperlog_core::log::ItemState::default(),
+            .execute(
+                Command::UpdateItem {
+                    root: req.root,
+                    path: req.path,
+                    title: item.title,
+                    description: item.description,
+                    state: to_core_state(item.item_state),
+                    due: Some(item.due).filter(|s| !s.is_empty()),
+                    start: Some(item.start).filter(|s| !s.is_empty()),
+                    recur: Some(item.recur).filter(|s| !s.is_empty()),
+                    links: item
+                        .links
+                        .into_iter()
+                        .map(|l| hyperlog_core::log::Link {
+                            title: l.title,
+                            url: l.url,
+                        })
+                        .collect(),
+                    tags: item.tags,
+                    priority: to_core_priority(&item.priority)?,
+                    checklist: to_core_checklist(item.checklist),
                 },
-                due: Some(item.due).filter(|s| !s.is_empty()),
-                links: item
-                    .links
-                    .into_iter()
-                    .map(|l| hyperlog_core::log::Link { title: l.title, url: l.url })
-                    .collect(),
-            }, user_id)
+                user_id,
+            )
             .await
             .map_err(to_tonic_err)?;
 
@@ -394,11 +484,24 @@ impl Graph for Server {
             ));
         }
 
+        // Entries are numbered from 1; 0 toggles the item itself.
+        let checklist_entry = match usize::try_from(req.checklist_entry) {
+            Ok(0) => None,
+            Ok(entry) => Some(entry - 1),
+            Err(_) => {
+                return Err(tonic::Status::new(
+                    tonic::Code::InvalidArgument,
+                    "checklist entry cannot be negative".to_string(),
+                ));
+            }
+        };
+
         self.commander
             .execute(
                 Command::ToggleItem {
                     root: req.root,
                     path: req.path,
+                    checklist_entry,
                 },
                 user_id,
             )
@@ -408,6 +511,50 @@ impl Graph for Server {
         Ok(Response::new(ToggleItemResponse {}))
     }
 
+    async fn set_item_state(
+        &self,
+        request: tonic::Request<SetItemStateRequest>,
+    ) -> std::result::Result<tonic::Response<SetItemStateResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("set item state: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+
+        if req.path.is_empty() || req.path.iter().any(|p| p.is_empty() || p.contains('.')) {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "path must be non-empty, without empty segments or `.`".to_string(),
+            ));
+        }
+
+        let Some(state) = req.item.and_then(|i| i.item_state) else {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "item.item_state must be set".to_string(),
+            ));
+        };
+
+        self.commander
+            .execute(
+                Command::SetItemState {
+                    root: req.root,
+                    path: req.path,
+                    state: to_core_state(Some(state)),
+                },
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(SetItemStateResponse {}))
+    }
+
     async fn archive(
         &self,
         request: tonic::Request<ArchiveRequest>,
@@ -505,6 +652,7 @@ impl Graph for Server {
                 Command::Move {
                     root: req.root,
                     src: req.src,
+                    dest_root: Some(req.dest_root).filter(|r| !r.is_empty()),
                     dest: req.dest,
                 },
                 user_id,
@@ -515,13 +663,13 @@ impl Graph for Server {
         Ok(Response::new(MoveResponse {}))
     }
 
-    async fn reorder(
+    async fn rename(
         &self,
-        request: tonic::Request<ReorderRequest>,
-    ) -> std::result::Result<tonic::Response<ReorderResponse>, tonic::Status> {
+        request: tonic::Request<RenameRequest>,
+    ) -> std::result::Result<tonic::Response<RenameResponse>, tonic::Status> {
         let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
         let req = request.into_inner();
-        tracing::trace!("reorder: req({:?})", req);
+        tracing::trace!("rename: req({:?})", req);
 
         if req.root.is_empty() {
             return Err(tonic::Status::new(
@@ -529,111 +677,733 @@ impl Graph for Server {
                 "root cannot be empty".to_string(),
             ));
         }
+        if req.path.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "path cannot be empty".to_string(),
+            ));
+        }
+        if req.new_key.is_empty() || req.new_key.contains('.') {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "new_key must be non-empty and cannot contain '.'".to_string(),
+            ));
+        }
 
         self.commander
             .execute(
-                Command::Reorder {
+                Command::Rename {
                     root: req.root,
                     path: req.path,
-                    order: req.order,
+                    new_key: req.new_key,
+                    update_links: req.update_links,
                 },
                 user_id,
             )
             .await
             .map_err(to_tonic_err)?;
 
-        Ok(Response::new(ReorderResponse {}))
+        Ok(Response::new(RenameResponse {}))
     }
 
-    async fn get_archived(
+    async fn copy(
         &self,
-        request: tonic::Request<GetArchivedRequest>,
-    ) -> std::result::Result<tonic::Response<GetArchivedResponse>, tonic::Status> {
+        request: tonic::Request<CopyRequest>,
+    ) -> std::result::Result<tonic::Response<CopyResponse>, tonic::Status> {
         let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
         let req = request.into_inner();
-        tracing::trace!("get archived: req({:?})", req);
+        tracing::trace!("copy: req({:?})", req);
 
-        let items = self
-            .querier
-            .get_archived(&req.root, user_id)
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+        if req.src.is_empty() || req.dest.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "src and dest cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(
+                Command::Copy {
+                    root: req.root,
+                    src: req.src,
+                    dest_root: Some(req.dest_root).filter(|r| !r.is_empty()),
+                    dest: req.dest,
+                    reset_state: req.reset_state,
+                    drop_due: req.drop_due,
+                },
+                user_id,
+            )
             .await
             .map_err(to_tonic_err)?;
 
-        Ok(Response::new(GetArchivedResponse {
-            items: items
-                .into_iter()
-                .map(|i| ArchivedItem {
-                    path: i.path,
-                    item_type: i.item_type,
-                    title: i.title,
-                })
-                .collect(),
-        }))
+        Ok(Response::new(CopyResponse {}))
     }
 
-    async fn get_view(
+    async fn apply_template(
         &self,
-        request: tonic::Request<GetViewRequest>,
-    ) -> std::result::Result<tonic::Response<GetViewResponse>, tonic::Status> {
+        request: tonic::Request<ApplyTemplateRequest>,
+    ) -> std::result::Result<tonic::Response<ApplyTemplateResponse>, tonic::Status> {
         let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
         let req = request.into_inner();
-        let max_depth = if req.max_depth <= 0 { 3 } else { req.max_depth };
-        let limits = if req.limits.is_empty() {
-            vec![10, 5, 3]
-        } else {
-            req.limits
-        };
-        let expanded: std::collections::HashSet<String> = req.expanded.into_iter().collect();
+        tracing::trace!("apply template: req({:?})", req);
 
-        let root = self
-            .querier
-            .get_view(&req.root, user_id, req.focus, expanded, max_depth, limits)
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+        if req.template.is_empty() || req.dest.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "template and dest cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(
+                Command::ApplyTemplate {
+                    root: req.root,
+                    template: req.template,
+                    dest: req.dest,
+                    vars: req.vars.into_iter().collect(),
+                    anchor: Some(req.anchor).filter(|a| !a.is_empty()),
+                },
+                user_id,
+            )
             .await
             .map_err(to_tonic_err)?;
 
-        Ok(Response::new(GetViewResponse {
-            root: Some(to_view_node(root)),
-        }))
+        Ok(Response::new(ApplyTemplateResponse {}))
     }
 
-    async fn backlinks(
+    async fn add_dependency(
         &self,
-        request: tonic::Request<BacklinksRequest>,
-    ) -> std::result::Result<tonic::Response<BacklinksResponse>, tonic::Status> {
+        request: tonic::Request<AddDependencyRequest>,
+    ) -> std::result::Result<tonic::Response<AddDependencyResponse>, tonic::Status> {
         let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
         let req = request.into_inner();
-        let hits = self
-            .querier
-            .backlinks(&req.root, user_id, req.path)
+        tracing::trace!("add dependency: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+        if req.path.is_empty() || req.depends_on.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "path and depends_on cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(
+                Command::AddDependency {
+                    root: req.root,
+                    path: req.path,
+                    depends_on: req.depends_on,
+                },
+                user_id,
+            )
             .await
             .map_err(to_tonic_err)?;
-        Ok(Response::new(BacklinksResponse {
-            items: hits
-                .into_iter()
-                .map(|h| ViewNode {
-                    key: h.key,
-                    path: h.path,
-                    kind: "item".into(),
-                    title: h.title,
-                    description: h.description,
-                    done: h.done,
-                    child_count: 0,
-                    truncated: false,
-                    children: Vec::new(),
-                    due: String::new(),
-                    created_unix: 0,
-                    links: Vec::new(),
-                })
-                .collect(),
-        }))
+
+        Ok(Response::new(AddDependencyResponse {}))
     }
-}
 
-fn to_view_node(v: crate::services::get_view::ViewItem) -> ViewNode {
-    ViewNode {
-        key: v.key,
-        path: v.path,
-        kind: v.kind,
+    async fn remove_dependency(
+        &self,
+        request: tonic::Request<RemoveDependencyRequest>,
+    ) -> std::result::Result<tonic::Response<RemoveDependencyResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("remove dependency: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+        if req.path.is_empty() || req.depends_on.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "path and depends_on cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(
+                Command::RemoveDependency {
+                    root: req.root,
+                    path: req.path,
+                    depends_on: req.depends_on,
+                },
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(RemoveDependencyResponse {}))
+    }
+
+    async fn snooze_item(
+        &self,
+        request: tonic::Request<SnoozeItemRequest>,
+    ) -> std::result::Result<tonic::Response<SnoozeItemResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("snooze item: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+        if req.path.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "path cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(
+                Command::SnoozeItem {
+                    root: req.root,
+                    path: req.path,
+                    start: Some(req.start).filter(|s| !s.is_empty()),
+                },
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(SnoozeItemResponse {}))
+    }
+
+    async fn convert(
+        &self,
+        request: tonic::Request<ConvertRequest>,
+    ) -> std::result::Result<tonic::Response<ConvertResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("convert: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+        if req.path.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "path cannot be empty".to_string(),
+            ));
+        }
+        let kind = req.kind.parse().map_err(|e: anyhow::Error| {
+            tonic::Status::new(tonic::Code::InvalidArgument, e.to_string())
+        })?;
+
+        self.commander
+            .execute(
+                Command::Convert {
+                    root: req.root,
+                    path: req.path,
+                    kind,
+                },
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(ConvertResponse {}))
+    }
+
+    async fn update_section(
+        &self,
+        request: tonic::Request<UpdateSectionRequest>,
+    ) -> std::result::Result<tonic::Response<UpdateSectionResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("update section: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+        if req.path.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "path cannot be empty".to_string(),
+            ));
+        }
+        let Some(section) = req.section else {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "section cannot be empty".to_string(),
+            ));
+        };
+
+        self.commander
+            .execute(
+                Command::UpdateSection {
+                    root: req.root,
+                    path: req.path,
+                    meta: hyperlog_core::log::SectionMeta {
+                        title: section.title,
+                        description: section.description,
+                        colour: section.colour,
+                        icon: section.icon,
+                        collapsed: section.collapsed,
+                    },
+                },
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(UpdateSectionResponse {}))
+    }
+
+    async fn reorder(
+        &self,
+        request: tonic::Request<ReorderRequest>,
+    ) -> std::result::Result<tonic::Response<ReorderResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("reorder: req({:?})", req);
+
+        if req.root.is_empty() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "root cannot be empty".to_string(),
+            ));
+        }
+
+        self.commander
+            .execute(
+                Command::Reorder {
+                    root: req.root,
+                    path: req.path,
+                    order: req.order,
+                },
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(ReorderResponse {}))
+    }
+
+    async fn get_archived(
+        &self,
+        request: tonic::Request<GetArchivedRequest>,
+    ) -> std::result::Result<tonic::Response<GetArchivedResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("get archived: req({:?})", req);
+
+        let items = self
+            .querier
+            .get_archived(&req.root, user_id)
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(GetArchivedResponse {
+            items: items
+                .into_iter()
+                .map(|i| ArchivedItem {
+                    path: i.path,
+                    item_type: i.item_type,
+                    title: i.title,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_view(
+        &self,
+        request: tonic::Request<GetViewRequest>,
+    ) -> std::result::Result<tonic::Response<GetViewResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        let max_depth = if req.max_depth <= 0 { 3 } else { req.max_depth };
+        let limits = if req.limits.is_empty() {
+            vec![10, 5, 3]
+        } else {
+            req.limits
+        };
+        let expanded: std::collections::HashSet<String> = req.expanded.into_iter().collect();
+        let by_priority = match req.sort.as_str() {
+            "" | "manual" => false,
+            "priority" => true,
+            sort => {
+                return Err(tonic::Status::new(
+                    tonic::Code::InvalidArgument,
+                    format!("sort must be manual or priority, got: {sort}"),
+                ));
+            }
+        };
+
+        let hide_deferred = deferred_cutoff(req.include_deferred, &req.today)?;
+
+        let root = self
+            .querier
+            .get_view(
+                &req.root,
+                user_id,
+                req.focus,
+                expanded,
+                max_depth,
+                limits,
+                req.ready,
+                Some(req.tag).filter(|t| !t.is_empty()),
+                by_priority,
+                hide_deferred,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(GetViewResponse {
+            root: Some(to_view_node(root)),
+        }))
+    }
+
+    async fn backlinks(
+        &self,
+        request: tonic::Request<BacklinksRequest>,
+    ) -> std::result::Result<tonic::Response<BacklinksResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        let hits = self
+            .querier
+            .backlinks(&req.root, user_id, req.path)
+            .await
+            .map_err(to_tonic_err)?;
+        Ok(Response::new(BacklinksResponse {
+            items: hits
+                .into_iter()
+                .map(|h| ViewNode {
+                    key: h.key,
+                    path: h.path,
+                    kind: "item".into(),
+                    title: h.title,
+                    description: h.description,
+                    done: h.done,
+                    child_count: 0,
+                    truncated: false,
+                    children: Vec::new(),
+                    due: String::new(),
+                    created_unix: 0,
+                    links: Vec::new(),
+                    depends_on: Vec::new(),
+                    blocked: false,
+                    tags: Vec::new(),
+                    priority: String::new(),
+                    start: String::new(),
+                    checklist: Vec::new(),
+                    checklist_progress: String::new(),
+                    colour: String::new(),
+                    icon: String::new(),
+                    collapsed: false,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn list_tags(
+        &self,
+        request: tonic::Request<ListTagsRequest>,
+    ) -> std::result::Result<tonic::Response<ListTagsResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("list tags: req({:?})", req);
+
+        let tags = self
+            .querier
+            .list_tags(Some(req.root).filter(|r| !r.is_empty()), user_id)
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(ListTagsResponse {
+            tags: tags
+                .into_iter()
+                .map(|(tag, count)| TagCount { tag, count })
+                .collect(),
+        }))
+    }
+
+    async fn search(
+        &self,
+        request: tonic::Request<SearchRequest>,
+    ) -> std::result::Result<tonic::Response<SearchResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("search: req({:?})", req);
+
+        let hits = self
+            .querier
+            .search(
+                Some(req.root).filter(|r| !r.is_empty()),
+                hyperlog_core::search::Search {
+                    text: req.query,
+                    tag: Some(req.tag).filter(|t| !t.is_empty()),
+                },
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(SearchResponse {
+            items: hits
+                .into_iter()
+                .map(|h| {
+                    let mut path = vec![h.root];
+                    path.extend(h.path);
+                    ViewNode {
+                        key: path.last().cloned().unwrap_or_default(),
+                        path,
+                        kind: "item".into(),
+                        title: h.title,
+                        description: String::new(),
+                        done: h.done,
+                        child_count: 0,
+                        truncated: false,
+                        children: Vec::new(),
+                        due: String::new(),
+                        created_unix: 0,
+                        links: Vec::new(),
+                        depends_on: Vec::new(),
+                        blocked: false,
+                        tags: h.tags,
+                        priority: String::new(),
+                        start: String::new(),
+                        checklist: Vec::new(),
+                        checklist_progress: String::new(),
+                        colour: String::new(),
+                        icon: String::new(),
+                        collapsed: false,
+                    }
+                })
+                .collect(),
+        }))
+    }
+
+    async fn agenda(
+        &self,
+        request: tonic::Request<AgendaRequest>,
+    ) -> std::result::Result<tonic::Response<AgendaResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("agenda: req({:?})", req);
+
+        if !req.today.is_empty() && hyperlog_core::due::parse_date(&req.today).is_none() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "today must be a YYYY-MM-DD date".to_string(),
+            ));
+        }
+
+        let agenda = self
+            .querier
+            .agenda(
+                Some(req.root).filter(|r| !r.is_empty()),
+                Some(req.today).filter(|t| !t.is_empty()),
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(AgendaResponse {
+            overdue: to_agenda_items(agenda.overdue),
+            today: to_agenda_items(agenda.today),
+            this_week: to_agenda_items(agenda.this_week),
+            later: to_agenda_items(agenda.later),
+        }))
+    }
+
+    async fn completed(
+        &self,
+        request: tonic::Request<CompletedRequest>,
+    ) -> std::result::Result<tonic::Response<CompletedResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("completed: req({:?})", req);
+
+        let Some(since) = hyperlog_core::due::parse_date(&req.since) else {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "since must be a YYYY-MM-DD date".to_string(),
+            ));
+        };
+        let until = match req.until.as_str() {
+            "" => hyperlog_core::due::today(),
+            until => hyperlog_core::due::parse_date(until).ok_or_else(|| {
+                tonic::Status::new(
+                    tonic::Code::InvalidArgument,
+                    "until must be a YYYY-MM-DD date".to_string(),
+                )
+            })?,
+        };
+
+        let completed = self
+            .querier
+            .completed(
+                Some(req.root).filter(|r| !r.is_empty()),
+                since,
+                until,
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(CompletedResponse {
+            items: completed
+                .items
+                .into_iter()
+                .map(|i| CompletedItem {
+                    root: i.root,
+                    path: i.path,
+                    title: i.title,
+                    completed_at: i.completed_at,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn standup(
+        &self,
+        request: tonic::Request<StandupRequest>,
+    ) -> std::result::Result<tonic::Response<StandupResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("standup: req({:?})", req);
+
+        if !req.today.is_empty() && hyperlog_core::due::parse_date(&req.today).is_none() {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "today must be a YYYY-MM-DD date".to_string(),
+            ));
+        }
+
+        let standup = self
+            .querier
+            .standup(
+                Some(req.root).filter(|r| !r.is_empty()),
+                Some(req.today).filter(|t| !t.is_empty()),
+                user_id,
+            )
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(StandupResponse {
+            today: hyperlog_core::due::format_date(standup.today),
+            since: hyperlog_core::due::format_date(standup.since),
+            markdown: standup.to_markdown(),
+            done: to_standup_items(standup.done),
+            open: to_standup_items(standup.open),
+            overdue: to_standup_items(standup.overdue),
+            blocked: to_standup_items(standup.blocked),
+        }))
+    }
+
+    async fn get_stats(
+        &self,
+        request: tonic::Request<GetStatsRequest>,
+    ) -> std::result::Result<tonic::Response<GetStatsResponse>, tonic::Status> {
+        let user_id = request.extensions().get::<AuthedUser>().map(|u| u.0);
+        let req = request.into_inner();
+        tracing::trace!("get stats: req({:?})", req);
+
+        let Some(since) = hyperlog_core::due::parse_date(&req.since) else {
+            return Err(tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "since must be a YYYY-MM-DD date".to_string(),
+            ));
+        };
+        let today = match req.today.as_str() {
+            "" => hyperlog_core::due::today(),
+            today => hyperlog_core::due::parse_date(today).ok_or_else(|| {
+                tonic::Status::new(
+                    tonic::Code::InvalidArgument,
+                    "today must be a YYYY-MM-DD date".to_string(),
+                )
+            })?,
+        };
+
+        let stats = self
+            .querier
+            .get_stats(req.root, req.path, since, today, user_id)
+            .await
+            .map_err(to_tonic_err)?;
+
+        Ok(Response::new(GetStatsResponse {
+            sections: stats
+                .sections
+                .into_iter()
+                .map(|s| SectionStats {
+                    path: s.path,
+                    total: s.counts.total,
+                    done: s.counts.done,
+                    overdue: s.counts.overdue,
+                })
+                .collect(),
+            days: stats
+                .days
+                .into_iter()
+                .map(|d| DayStats {
+                    date: hyperlog_core::due::format_date(d.date),
+                    created: d.created,
+                    completed: d.completed,
+                    remaining: d.remaining,
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn to_agenda_items(items: Vec<hyperlog_core::agenda::AgendaItem>) -> Vec<AgendaItem> {
+    items
+        .into_iter()
+        .map(|i| AgendaItem {
+            root: i.root,
+            path: i.path,
+            title: i.title,
+            due: i.due,
+        })
+        .collect()
+}
+
+fn to_standup_items(items: Vec<hyperlog_core::standup::StandupItem>) -> Vec<StandupItem> {
+    items
+        .into_iter()
+        .map(|i| StandupItem {
+            root: i.root,
+            path: i.path,
+            title: i.title,
+            detail: i.detail.unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn to_view_node(v: crate::services::get_view::ViewItem) -> ViewNode {
+    ViewNode {
+        key: v.key,
+        path: v.path,
+        kind: v.kind,
         title: v.title,
         description: v.description,
         done: v.done,
@@ -645,15 +1415,32 @@ fn to_view_node(v: crate::services::get_view::ViewItem) -> ViewNode {
         links: v
             .links
             .into_iter()
-            .map(|l| Link { title: l.title, url: l.url })
+            .map(|l| Link {
+                title: l.title,
+                url: l.url,
+            })
+            .collect(),
+        depends_on: v.depends_on,
+        blocked: v.blocked,
+        tags: v.tags,
+        priority: v.priority.map(|p| p.to_string()).unwrap_or_default(),
+        start: v.start.unwrap_or_default(),
+        checklist_progress: hyperlog_core::checklist::summary(&v.checklist).unwrap_or_default(),
+        checklist: v
+            .checklist
+            .into_iter()
+            .map(to_proto_checklist_entry)
             .collect(),
+        colour: v.colour,
+        icon: v.icon,
+        collapsed: v.collapsed,
     }
 }
 
 fn to_native(from: &hyperlog_core::log::GraphItem) -> anyhow::Result<GraphItem> {
     match from {
         hyperlog_core::log::GraphItem::User(section)
-        | hyperlog_core::log::GraphItem::Section(section) => {
+        | hyperlog_core::log::GraphItem::Section { items: section, .. } => {
             let mut root = HashMap::new();
             for (key, value) in section.iter() {
                 root.insert(key.to_string(), to_native(value)?);
@@ -662,9 +1449,14 @@ fn to_native(from: &hyperlog_core::log::GraphItem) -> anyhow::Result<GraphItem>
                 hyperlog_core::log::GraphItem::User(_) => Ok(GraphItem {
                     contents: Some(graph_item::Contents::User(UserGraphItem { items: root })),
                 }),
-                hyperlog_core::log::GraphItem::Section(_) => Ok(GraphItem {
+                hyperlog_core::log::GraphItem::Section { meta, .. } => Ok(GraphItem {
                     contents: Some(graph_item::Contents::Section(SectionGraphItem {
                         items: root,
+                        title: meta.title.clone(),
+                        description: meta.description.clone(),
+                        colour: meta.colour.clone(),
+                        icon: meta.icon.clone(),
+                        collapsed: meta.collapsed,
                     })),
                 }),
                 _ => {
@@ -676,39 +1468,155 @@ fn to_native(from: &hyperlog_core::log::GraphItem) -> anyhow::Result<GraphItem>
             title,
             description,
             state,
+            due,
+            start,
+            recur,
+            completions,
+            completed_at,
+            depends_on,
+            tags,
+            priority,
+            checklist,
         } => Ok(GraphItem {
             contents: Some(graph_item::Contents::Item(ItemGraphItem {
                 title: title.to_owned(),
                 description: description.to_owned(),
-                item_state: Some(match state {
-                    hyperlog_core::log::ItemState::NotDone => {
-                        item_graph_item::ItemState::NotDone(ItemStateNotDone {})
-                    }
-                    hyperlog_core::log::ItemState::Done => {
-                        item_graph_item::ItemState::Done(ItemStateDone {})
-                    }
-                }),
-                due: String::new(),
+                item_state: Some(to_proto_state(state)),
+                due: due.clone().unwrap_or_default(),
                 created_unix: 0,
                 links: Vec::new(),
+                recur: recur.clone().unwrap_or_default(),
+                completions: completions.clone(),
+                completed_at: completed_at.clone().unwrap_or_default(),
+                depends_on: depends_on.clone(),
+                tags: tags.clone(),
+                priority: priority.map(|p| p.to_string()).unwrap_or_default(),
+                start: start.clone().unwrap_or_default(),
+                checklist: checklist
+                    .iter()
+                    .cloned()
+                    .map(to_proto_checklist_entry)
+                    .collect(),
             })),
         }),
     }
 }
 
+/// The day items deferred past are hidden from, or `None` to include them.
+#[allow(clippy::result_large_err)]
+fn deferred_cutoff(
+    include_deferred: bool,
+    today: &str,
+) -> std::result::Result<Option<i64>, tonic::Status> {
+    if include_deferred {
+        return Ok(None);
+    }
+    if today.is_empty() {
+        return Ok(Some(hyperlog_core::due::today()));
+    }
+
+    hyperlog_core::due::parse_date(today)
+        .map(Some)
+        .ok_or_else(|| {
+            tonic::Status::new(
+                tonic::Code::InvalidArgument,
+                "today must be a YYYY-MM-DD date".to_string(),
+            )
+        })
+}
+
+#[allow(clippy::result_large_err)]
+fn to_core_priority(
+    priority: &str,
+) -> std::result::Result<Option<hyperlog_core::log::Priority>, tonic::Status> {
+    if priority.is_empty() {
+        return Ok(None);
+    }
+
+    priority
+        .parse()
+        .map(Some)
+        .map_err(|e: anyhow::Error| tonic::Status::new(tonic::Code::InvalidArgument, e.to_string()))
+}
+
+fn to_core_checklist(
+    checklist: Vec<ChecklistEntry>,
+) -> Vec<hyperlog_core::checklist::ChecklistEntry> {
+    checklist
+        .into_iter()
+        .map(|c| hyperlog_core::checklist::ChecklistEntry {
+            title: c.title,
+            done: c.done,
+        })
+        .collect()
+}
+
+fn to_proto_checklist_entry(entry: hyperlog_core::checklist::ChecklistEntry) -> ChecklistEntry {
+    ChecklistEntry {
+        title: entry.title,
+        done: entry.done,
+    }
+}
+
+fn to_core_state(state: Option<item_graph_item::ItemState>) -> hyperlog_core::log::ItemState {
+    match state {
+        Some(item_graph_item::ItemState::NotDone(_)) => hyperlog_core::log::ItemState::NotDone,
+        Some(item_graph_item::ItemState::InProgress(_)) => {
+            hyperlog_core::log::ItemState::InProgress
+        }
+        Some(item_graph_item::ItemState::Blocked(ItemStateBlocked { reason })) => {
+            hyperlog_core::log::ItemState::Blocked { reason }
+        }
+        Some(item_graph_item::ItemState::Done(_)) => hyperlog_core::log::ItemState::Done,
+        Some(item_graph_item::ItemState::Cancelled(_)) => hyperlog_core::log::ItemState::Cancelled,
+        None => hyperlog_core::log::ItemState::default(),
+    }
+}
+
+fn to_proto_state(state: &hyperlog_core::log::ItemState) -> item_graph_item::ItemState {
+    match state {
+        hyperlog_core::log::ItemState::NotDone => {
+            item_graph_item::ItemState::NotDone(ItemStateNotDone {})
+        }
+        hyperlog_core::log::ItemState::InProgress => {
+            item_graph_item::ItemState::InProgress(ItemStateInProgress {})
+        }
+        hyperlog_core::log::ItemState::Blocked { reason } => {
+            item_graph_item::ItemState::Blocked(ItemStateBlocked {
+                reason: reason.clone(),
+            })
+        }
+        hyperlog_core::log::ItemState::Done => item_graph_item::ItemState::Done(ItemStateDone {}),
+        hyperlog_core::log::ItemState::Cancelled => {
+            item_graph_item::ItemState::Cancelled(ItemStateCancelled {})
+        }
+    }
+}
+
 // TODO: create more defined protobuf categories for errors
 fn to_tonic_err(err: anyhow::Error) -> tonic::Status {
-    tonic::Status::new(tonic::Code::Unknown, err.to_string())
+    match err.downcast_ref::<hyperlog_core::validate::Invalid>() {
+        Some(invalid) => tonic::Status::new(tonic::Code::InvalidArgument, invalid.to_string()),
+        None => tonic::Status::new(tonic::Code::Unknown, err.to_string()),
+    }
 }
 
-/// Extract and verify the user id from an `authorization: Bearer <jwt>` header.
-fn bearer_uid(secret: &[u8], req: &Request<()>) -> Option<uuid::Uuid> {
+/// Extract and verify the access token from an `authorization: Bearer <jwt>`
+/// header.
+fn bearer_access(keys: &KeySet, req: &Request<()>) -> Option<Access> {
     let val = req.metadata().get("authorization")?;
     let s = val.to_str().ok()?;
     let token = s
         .strip_prefix("Bearer ")
         .or_else(|| s.strip_prefix("bearer "))?;
-    AuthService::verify_access(secret, token).ok()
+    AuthService::verify_access(keys, token).ok()
+}
+
+fn insert_access(req: &mut Request<()>, access: Access) {
+    req.extensions_mut().insert(AuthedUser(access.user_id));
+    if let Some(session_id) = access.session_id {
+        req.extensions_mut().insert(AuthedSession(session_id));
+    }
 }
 
 pub trait ServerExt {
@@ -725,8 +1633,7 @@ pub async fn serve(state: &SharedState, host: SocketAddr) -> anyhow::Result<()>
     tracing::info!("listening on {}", host);
 
     let graph_server = state.grpc_server();
-    let auth_server = AuthService::new(state.db.clone());
-    let secret = auth_server.jwt_secret();
+    let auth_server = AuthService::new(state.db.clone(), state.jwt_keys.clone())?;
 
     // When set, Graph calls REQUIRE a valid Bearer token; otherwise the token is
     // injected when present but tokenless calls still pass (legacy/no-auth mode).
@@ -736,27 +1643,26 @@ pub async fn serve(state: &SharedState, host: SocketAddr) -> anyhow::Result<()>
     tracing::info!("auth enforcement on Graph: {}", require_auth);
 
     // Auth service interceptor: inject the user if a valid token is present,
-    // never reject (register/login/refresh are public; Me self-checks).
-    let secret_auth = secret.clone();
+    // never reject (register/login/refresh, password reset and VerifyTotp are
+    // public; everything else self-checks).
+    let keys_auth = state.jwt_keys.clone();
     let auth_interceptor = move |mut req: Request<()>| -> Result<Request<()>, Status> {
-        if let Some(uid) = bearer_uid(secret_auth.as_slice(), &req) {
-            req.extensions_mut().insert(AuthedUser(uid));
+        if let Some(access) = bearer_access(&keys_auth, &req) {
+            insert_access(&mut req, access);
         }
         Ok(req)
     };
 
     // Graph interceptor: inject the user; reject when enforcement is on and no
     // valid token is present.
-    let secret_graph = secret.clone();
+    let keys_graph = state.jwt_keys.clone();
     let graph_interceptor = move |mut req: Request<()>| -> Result<Request<()>, Status> {
-        match bearer_uid(secret_graph.as_slice(), &req) {
-            Some(uid) => {
-                req.extensions_mut().insert(AuthedUser(uid));
+        match bearer_access(&keys_graph, &req) {
+            Some(access) => {
+                insert_access(&mut req, access);
                 Ok(req)
             }
-            None if require_auth => {
-                Err(Status::unauthenticated("authentication required"))
-            }
+            None if require_auth => Err(Status::unauthenticated("authentication required")),
             None => Ok(req),
         }
     };
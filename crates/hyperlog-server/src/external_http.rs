@@ -1,6 +1,12 @@
 use std::net::SocketAddr;
 
-use axum::{extract::MatchedPath, http::Request, routing::get, Router};
+use axum::{
+    extract::{MatchedPath, State},
+    http::Request,
+    routing::get,
+    Json, Router,
+};
+use jsonwebtoken::jwk::JwkSet;
 use tower_http::trace::TraceLayer;
 
 use crate::state::SharedState;
@@ -9,9 +15,15 @@ async fn root() -> &'static str {
     "Hello, hyperlog!"
 }
 
+/// Public keys access tokens are signed with, for verifying them elsewhere.
+async fn jwks(State(state): State<SharedState>) -> Json<JwkSet> {
+    Json(state.jwt_keys.jwks())
+}
+
 pub async fn serve(state: &SharedState, host: &SocketAddr) -> anyhow::Result<()> {
     let app = Router::new()
         .route("/", get(root))
+        .route("/.well-known/jwks.json", get(jwks))
         .with_state(state.clone())
         .layer(
             TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
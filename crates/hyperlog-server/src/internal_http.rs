@@ -1,35 +1,78 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
-use axum::{extract::MatchedPath, http::Request, routing::get, Router};
+use axum::{
+    extract::{MatchedPath, State},
+    http::{header::AUTHORIZATION, HeaderMap, Request, StatusCode},
+    routing::get,
+    Extension, Json, Router,
+};
+use sha2::{Digest, Sha256};
 use tower_http::trace::TraceLayer;
 
-use crate::state::SharedState;
+use crate::{
+    services::fsck::{self, FsckExt},
+    state::SharedState,
+};
 
 async fn root() -> &'static str {
     "Hello, hyperlog!"
 }
 
+/// SHA-256 of the token `/fsck` requires, from `HYPERLOG_FSCK_TOKEN`.
+#[derive(Clone)]
+struct FsckToken(Arc<[u8]>);
+
+/// Items whose stored due or links fail validation. Lists every user's items,
+/// so it requires `Authorization: Bearer <HYPERLOG_FSCK_TOKEN>`.
+async fn fsck(
+    State(state): State<SharedState>,
+    Extension(FsckToken(token)): Extension<FsckToken>,
+    headers: HeaderMap,
+) -> Result<Json<fsck::Response>, (StatusCode, String)> {
+    // Hashes are compared so the time taken says nothing about the token.
+    let presented = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| Sha256::digest(v.as_bytes()));
+    if presented.as_deref() != Some(&token[..]) {
+        return Err((StatusCode::UNAUTHORIZED, "invalid fsck token".to_string()));
+    }
+
+    state
+        .fsck_service()
+        .execute(fsck::Request {})
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))
+}
+
 pub async fn serve(state: &SharedState, host: &SocketAddr) -> anyhow::Result<()> {
-    let app = Router::new()
-        .route("/", get(root))
-        .with_state(state.clone())
-        .layer(
-            TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
-                // Log the matched route's path (with placeholders not filled in).
-                // Use request.uri() or OriginalUri if you want the real path.
-                let matched_path = request
-                    .extensions()
-                    .get::<MatchedPath>()
-                    .map(MatchedPath::as_str);
-
-                tracing::info_span!(
-                    "http_request",
-                    method = ?request.method(),
-                    matched_path,
-                    some_other_field = tracing::field::Empty,
-                )
-            }), // ...
-        );
+    let mut app = Router::new().route("/", get(root));
+    match std::env::var("HYPERLOG_FSCK_TOKEN") {
+        Ok(token) if !token.is_empty() => {
+            let token = FsckToken(Sha256::digest(token.as_bytes()).to_vec().into());
+            app = app.route("/fsck", get(fsck).layer(Extension(token)));
+        }
+        _ => tracing::info!("HYPERLOG_FSCK_TOKEN is not set, /fsck is disabled"),
+    }
+    let app = app.with_state(state.clone()).layer(
+        TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
+            // Log the matched route's path (with placeholders not filled in).
+            // Use request.uri() or OriginalUri if you want the real path.
+            let matched_path = request
+                .extensions()
+                .get::<MatchedPath>()
+                .map(MatchedPath::as_str);
+
+            tracing::info_span!(
+                "http_request",
+                method = ?request.method(),
+                matched_path,
+                some_other_field = tracing::field::Empty,
+            )
+        }), // ...
+    );
 
     tracing::info!("listening on {}", host);
     let listener = tokio::net::TcpListener::bind(host).await.unwrap();
@@ -1,6 +1,9 @@
 use std::{net::SocketAddr, sync::Arc};
 
-use crate::state::{SharedState, State};
+use crate::{
+    services::{fsck::FsckExt, purge_roots::PurgeRootsExt},
+    state::{SharedState, State},
+};
 
 mod external_grpc;
 mod external_http;
@@ -30,6 +33,17 @@ pub async fn serve(opts: ServeOptions) -> anyhow::Result<()> {
     tracing::debug!("setting up dependencies");
     let state = SharedState(Arc::new(State::new().await?));
 
+    let purge_roots = state.purge_roots_service();
+
+    // A full scan of every item, so only when asked for.
+    let fsck_on_start = std::env::var("HYPERLOG_FSCK_ON_START")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    if fsck_on_start {
+        let fsck = state.fsck_service();
+        tokio::spawn(async move { fsck.report().await });
+    }
+
     tracing::debug!("serve starting");
     tokio::select!(
         res = external_http::serve(&state, &opts.external_http) => {
@@ -41,6 +55,9 @@ pub async fn serve(opts: ServeOptions) -> anyhow::Result<()> {
         res = external_grpc::serve(&state, opts.external_grpc) => {
             res?
         }
+        res = purge_roots.run() => {
+            res?
+        }
         () = ctrl_c => {}
     );
     tracing::debug!("serve finalized");
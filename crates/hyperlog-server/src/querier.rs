@@ -4,11 +4,17 @@ use std::collections::HashSet;
 
 use crate::{
     services::{
+        agenda::{self, Agenda, AgendaExt},
         backlinks::{self, Backlinks, BacklinksExt},
+        completed::{self, Completed, CompletedExt},
         get_archived::{self, ArchivedItem, GetArchived, GetArchivedExt},
         get_available_roots::{self, GetAvailableRoots, GetAvailableRootsExt},
         get_graph::{GetGraph, GetGraphExt},
+        get_stats::{self, GetStats, GetStatsExt},
         get_view::{self, GetView, GetViewExt, ViewItem},
+        list_tags::{self, ListTags, ListTagsExt},
+        search::{self, Search, SearchExt},
+        standup::{self, Standup, StandupExt},
     },
     state::SharedState,
 };
@@ -19,25 +25,48 @@ pub struct Querier {
     get_archived: GetArchived,
     get_view: GetView,
     backlinks: Backlinks,
+    agenda: Agenda,
+    list_tags: ListTags,
+    search: Search,
+    completed: Completed,
+    standup: Standup,
+    get_stats: GetStats,
 }
 
 impl Querier {
-    pub fn new(
-        get_available_roots: GetAvailableRoots,
-        get_graph: GetGraph,
-        get_archived: GetArchived,
-        get_view: GetView,
-        backlinks: Backlinks,
-    ) -> Self {
+    pub fn new(state: &SharedState) -> Self {
         Self {
-            get_available_roots,
-            get_graph,
-            get_archived,
-            get_view,
-            backlinks,
+            get_available_roots: state.get_available_roots_service(),
+            get_graph: state.get_graph_service(),
+            get_archived: state.get_archived_service(),
+            get_view: state.get_view_service(),
+            backlinks: state.backlinks_service(),
+            agenda: state.agenda_service(),
+            list_tags: state.list_tags_service(),
+            search: state.search_service(),
+            completed: state.completed_service(),
+            standup: state.standup_service(),
+            get_stats: state.get_stats_service(),
         }
     }
 
+    pub async fn agenda(
+        &self,
+        root: Option<String>,
+        today: Option<String>,
+        user_id: Option<uuid::Uuid>,
+    ) -> anyhow::Result<hyperlog_core::agenda::Agenda> {
+        let res = self
+            .agenda
+            .execute(agenda::Request {
+                root,
+                today,
+                user_id,
+            })
+            .await?;
+        Ok(res.agenda)
+    }
+
     pub async fn backlinks(
         &self,
         root: &str,
@@ -55,6 +84,92 @@ impl Querier {
         Ok(res.items)
     }
 
+    pub async fn completed(
+        &self,
+        root: Option<String>,
+        since: i64,
+        until: i64,
+        user_id: Option<uuid::Uuid>,
+    ) -> anyhow::Result<hyperlog_core::completed::Completed> {
+        let res = self
+            .completed
+            .execute(completed::Request {
+                root,
+                since,
+                until,
+                user_id,
+            })
+            .await?;
+        Ok(res.completed)
+    }
+
+    pub async fn standup(
+        &self,
+        root: Option<String>,
+        today: Option<String>,
+        user_id: Option<uuid::Uuid>,
+    ) -> anyhow::Result<hyperlog_core::standup::Standup> {
+        let res = self
+            .standup
+            .execute(standup::Request {
+                root,
+                today,
+                user_id,
+            })
+            .await?;
+        Ok(res.standup)
+    }
+
+    pub async fn get_stats(
+        &self,
+        root: String,
+        path: Vec<String>,
+        since: i64,
+        today: i64,
+        user_id: Option<uuid::Uuid>,
+    ) -> anyhow::Result<hyperlog_core::stats::Stats> {
+        let res = self
+            .get_stats
+            .execute(get_stats::Request {
+                root,
+                path,
+                since,
+                today,
+                user_id,
+            })
+            .await?;
+        Ok(res.stats)
+    }
+
+    pub async fn list_tags(
+        &self,
+        root: Option<String>,
+        user_id: Option<uuid::Uuid>,
+    ) -> anyhow::Result<Vec<(String, i64)>> {
+        let res = self
+            .list_tags
+            .execute(list_tags::Request { root, user_id })
+            .await?;
+        Ok(res.tags)
+    }
+
+    pub async fn search(
+        &self,
+        root: Option<String>,
+        search: hyperlog_core::search::Search,
+        user_id: Option<uuid::Uuid>,
+    ) -> anyhow::Result<Vec<hyperlog_core::search::SearchHit>> {
+        let res = self
+            .search
+            .execute(search::Request {
+                root,
+                search,
+                user_id,
+            })
+            .await?;
+        Ok(res.items)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn get_view(
         &self,
@@ -64,6 +179,10 @@ impl Querier {
         expanded: HashSet<String>,
         max_depth: i32,
         limits: Vec<i32>,
+        ready: bool,
+        tag: Option<String>,
+        by_priority: bool,
+        hide_deferred: Option<i64>,
     ) -> anyhow::Result<ViewItem> {
         let res = self
             .get_view
@@ -74,6 +193,10 @@ impl Querier {
                 expanded,
                 max_depth,
                 limits,
+                ready,
+                tag,
+                by_priority,
+                hide_deferred,
             })
             .await?;
         Ok(res.root)
@@ -114,6 +237,7 @@ impl Querier {
         &self,
         root: &str,
         path: impl IntoIterator<Item = impl Into<String>>,
+        hide_deferred: Option<i64>,
         user_id: Option<uuid::Uuid>,
     ) -> anyhow::Result<Option<GraphItem>> {
         let graph = self
@@ -122,6 +246,7 @@ impl Querier {
                 root: root.into(),
                 path: path.into_iter().map(|s| s.into()).collect(),
                 user_id,
+                hide_deferred,
             })
             .await?;
 
@@ -135,12 +260,6 @@ pub trait QuerierExt {
 
 impl QuerierExt for SharedState {
     fn querier(&self) -> Querier {
-        Querier::new(
-            self.get_available_roots_service(),
-            self.get_graph_service(),
-            self.get_archived_service(),
-            self.get_view_service(),
-            self.backlinks_service(),
-        )
+        Querier::new(self)
     }
 }
@@ -1,15 +1,35 @@
+pub mod add_dependency;
+pub mod apply_template;
 pub mod archive;
+pub mod convert_node;
+pub mod copy_node;
 pub mod create_item;
 pub mod create_root;
 pub mod create_section;
+pub mod delete_root;
+pub mod fsck;
 pub mod move_node;
+pub mod purge_roots;
+pub mod remove_dependency;
+pub mod rename;
+pub mod rename_root;
 pub mod reorder;
 pub mod restore;
+pub mod restore_root;
+pub mod set_item_state;
+pub mod snooze_item;
 pub mod toggle_item;
 pub mod update_item;
+pub mod update_section;
 
+pub mod agenda;
 pub mod backlinks;
+pub mod completed;
 pub mod get_archived;
 pub mod get_available_roots;
 pub mod get_graph;
+pub mod get_stats;
 pub mod get_view;
+pub mod list_tags;
+pub mod search;
+pub mod standup;
@@ -0,0 +1,244 @@
+//! Dependency edges live in `item_content.depends_on` as dotted root-relative
+//! paths, see `hyperlog_core::deps`. Only items that have dependencies, and
+//! the items they point at, are loaded to check for cycles, so this stays cheap
+//! on large roots where few items are linked.
+
+use hyperlog_core::{deps::Dependencies, log::ItemState};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct AddDependency {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub path: Vec<String>,
+    pub depends_on: Vec<String>,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+#[derive(sqlx::FromRow)]
+struct DependencyRow {
+    path: String,
+    state: Option<Json<serde_json::Value>>,
+    depends_on: Option<Json<serde_json::Value>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ContentRow {
+    id: uuid::Uuid,
+    item_content: Option<Json<serde_json::Value>>,
+}
+
+/// The active items of the root that depend on something, everything they
+/// depend on, and the items at `also`.
+pub(crate) async fn load_dependencies(
+    conn: &mut sqlx::PgConnection,
+    root_id: uuid::Uuid,
+    also: &[String],
+) -> anyhow::Result<Dependencies> {
+    let mut rows: Vec<DependencyRow> = sqlx::query_as(
+        r#"
+SELECT
+    path,
+    item_content->'state' AS state,
+    item_content->'depends_on' AS depends_on
+FROM
+    nodes
+WHERE
+    root_id = $1
+    AND item_type = 'ITEM'
+    AND status = 'active'
+    AND item_content ? 'depends_on'
+        "#,
+    )
+    .bind(root_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut targets = also.to_vec();
+    for row in &rows {
+        if let Some(Json(serde_json::Value::Array(depends_on))) = &row.depends_on {
+            targets.extend(
+                depends_on
+                    .iter()
+                    .filter_map(|d| d.as_str())
+                    .map(String::from),
+            );
+        }
+    }
+    if !targets.is_empty() {
+        let rest: Vec<DependencyRow> = sqlx::query_as(
+            r#"
+SELECT
+    path,
+    item_content->'state' AS state,
+    NULL::JSONB AS depends_on
+FROM
+    nodes
+WHERE
+    root_id = $1
+    AND item_type = 'ITEM'
+    AND status = 'active'
+    AND path = ANY($2)
+    AND NOT (item_content ? 'depends_on')
+            "#,
+        )
+        .bind(root_id)
+        .bind(&targets)
+        .fetch_all(&mut *conn)
+        .await?;
+        rows.extend(rest);
+    }
+
+    let mut deps = Dependencies::default();
+    for row in rows {
+        let closed = row
+            .state
+            .and_then(|Json(state)| serde_json::from_value::<ItemState>(state).ok())
+            .is_some_and(|state| state.is_closed());
+        let depends_on = row
+            .depends_on
+            .and_then(|Json(d)| serde_json::from_value(d).ok())
+            .unwrap_or_default();
+        deps.insert(row.path, closed, depends_on);
+    }
+
+    Ok(deps)
+}
+
+/// Point dependencies at `src` or below it to `dest`, after the nodes moved
+/// there within the root. Archived items are included so they still line up
+/// once restored.
+pub(crate) async fn retarget_dependencies(
+    conn: &mut sqlx::PgConnection,
+    root_id: uuid::Uuid,
+    src: &str,
+    dest: &str,
+) -> anyhow::Result<()> {
+    rewrite_dependencies(conn, root_id, None, |depends_on| {
+        hyperlog_core::deps::retarget(depends_on, src, dest)
+    })
+    .await
+}
+
+/// Apply `rewrite` to the dependencies of the root's items, or only those at
+/// `subtree` and below it, saving the ones it changed.
+pub(crate) async fn rewrite_dependencies(
+    conn: &mut sqlx::PgConnection,
+    root_id: uuid::Uuid,
+    subtree: Option<&str>,
+    rewrite: impl Fn(&mut Vec<String>) -> bool,
+) -> anyhow::Result<()> {
+    let rows: Vec<ContentRow> = sqlx::query_as(
+        r#"SELECT id, item_content FROM nodes
+           WHERE root_id = $1 AND item_type = 'ITEM' AND item_content ? 'depends_on'
+             AND ($2::TEXT IS NULL OR path = $2 OR path LIKE $2 || '.%')"#,
+    )
+    .bind(root_id)
+    .bind(subtree)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for row in rows {
+        let Some(Json(mut content)) = row.item_content else {
+            continue;
+        };
+        let mut depends_on: Vec<String> =
+            serde_json::from_value(content["depends_on"].take()).unwrap_or_default();
+        if !rewrite(&mut depends_on) {
+            continue;
+        }
+        content["depends_on"] = serde_json::json!(depends_on);
+        sqlx::query(r#"UPDATE nodes SET item_content = $1 WHERE id = $2"#)
+            .bind(Json(content))
+            .bind(row.id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+impl AddDependency {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {}", req.root))?;
+
+        let item = req.path.join(".");
+        let dependency = req.depends_on.join(".");
+
+        // Serializable, so two edges added at once can't form a cycle together.
+        let mut tx = self.db.begin().await?;
+
+        let endpoints = [item.clone(), dependency.clone()];
+        let deps = load_dependencies(&mut tx, root_id, &endpoints).await?;
+        if !deps.contains(&item) {
+            anyhow::bail!("item not found: {item}");
+        }
+        if !deps.contains(&dependency) {
+            anyhow::bail!("dependency not found: {dependency}");
+        }
+        if deps.would_cycle(&item, &dependency) {
+            anyhow::bail!("{item} depending on {dependency} would create a cycle");
+        }
+        if deps.depends_on(&item).contains(&dependency) {
+            return Ok(Response {});
+        }
+
+        sqlx::query(
+            r#"
+UPDATE
+    nodes
+SET
+    item_content = jsonb_set(
+        item_content,
+        '{depends_on}',
+        COALESCE(item_content->'depends_on', '[]'::JSONB) || jsonb_build_array($1::TEXT)
+    )
+WHERE
+    root_id = $2
+    AND path = $3
+    AND item_type = 'ITEM'
+            "#,
+        )
+        .bind(&dependency)
+        .bind(root_id)
+        .bind(&item)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Response {})
+    }
+}
+
+pub trait AddDependencyExt {
+    fn add_dependency_service(&self) -> AddDependency;
+}
+
+impl AddDependencyExt for SharedState {
+    fn add_dependency_service(&self) -> AddDependency {
+        AddDependency::new(self.db.clone())
+    }
+}
@@ -0,0 +1,107 @@
+//! Not-done items with a due date, across the user's roots or in one of them,
+//! grouped by `hyperlog_core::agenda`. Reads the indexed `due_on` column rather
+//! than walking the graph, and only as far as the agenda lists.
+
+use hyperlog_core::{
+    agenda::{Agenda as AgendaBuckets, AgendaItem, MAX_ITEMS},
+    due,
+};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct Agenda {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    /// Only this root; all of the user's roots when `None`.
+    pub root: Option<String>,
+    /// `YYYY-MM-DD` in the caller's timezone; the server's UTC date when `None`.
+    pub today: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+}
+
+pub struct Response {
+    pub agenda: AgendaBuckets,
+}
+
+#[derive(sqlx::FromRow)]
+struct DueRow {
+    root_name: String,
+    path: String,
+    item_content: Option<Json<serde_json::Value>>,
+}
+
+impl Agenda {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let today = match &req.today {
+            Some(today) => due::parse_date(today)
+                .ok_or_else(|| anyhow::anyhow!("expected today as YYYY-MM-DD, got: {today}"))?,
+            None => due::today(),
+        };
+
+        let rows: Vec<DueRow> = sqlx::query_as(
+            r#"
+            SELECT r.root_name, n.path, n.item_content
+            FROM nodes n
+            JOIN roots r ON r.id = n.root_id
+            WHERE r.user_id IS NOT DISTINCT FROM $1
+              AND r.deleted_at IS NULL
+              AND ($2::TEXT IS NULL OR r.root_name = $2)
+              AND n.item_type = 'ITEM'
+              AND n.status = 'active'
+              AND n.due_on IS NOT NULL
+              AND COALESCE(n.item_content->>'state', '') NOT IN ('done', 'cancelled')
+            ORDER BY n.due_on, r.root_name, n.path
+            LIMIT $3
+            "#,
+        )
+        .bind(req.user_id)
+        .bind(&req.root)
+        .bind(MAX_ITEMS as i64)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut agenda = AgendaBuckets::default();
+        for row in rows {
+            let Some(Json(content)) = row.item_content else {
+                continue;
+            };
+            let text = |field: &str| {
+                content
+                    .get(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            agenda.push(
+                AgendaItem {
+                    root: row.root_name,
+                    path: row.path.split('.').map(str::to_string).collect(),
+                    title: text("title"),
+                    due: text("due"),
+                },
+                today,
+            );
+        }
+        agenda.sort();
+
+        Ok(Response { agenda })
+    }
+}
+
+pub trait AgendaExt {
+    fn agenda_service(&self) -> Agenda;
+}
+
+impl AgendaExt for SharedState {
+    fn agenda_service(&self) -> Agenda {
+        Agenda::new(self.db.clone())
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, HashSet};
+
+use hyperlog_core::{template, validate};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct ApplyTemplate {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    /// Name of a section under the root's templates section.
+    pub template: String,
+    pub dest: Vec<String>,
+    pub vars: BTreeMap<String, String>,
+    /// `YYYY-MM-DD` that relative dues count from; today when `None`.
+    pub anchor: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+#[derive(sqlx::FromRow)]
+struct Count {
+    count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct NodeRow {
+    path: String,
+    item_type: String,
+    item_content: Option<Json<serde_json::Value>>,
+    sort_order: Option<f64>,
+}
+
+impl ApplyTemplate {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Instantiate the template's children in a new section at `dest`.
+    /// `{{variables}}` are substituted in the destination, keys, titles,
+    /// descriptions, section metadata and dues; relative dues are resolved
+    /// against the anchor.
+    /// Items always start out not-done.
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        if req.dest.is_empty() {
+            anyhow::bail!("dest must be non-empty");
+        }
+        let anchor = template::anchor(req.anchor.as_deref())?;
+        let src = template::template_path(&req.template).join(".");
+        let dest = req
+            .dest
+            .iter()
+            .map(|key| template::substitute_key(key, &req.vars))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {}", req.root))?;
+
+        let rows: Vec<NodeRow> = sqlx::query_as(
+            r#"SELECT path, item_type, item_content, sort_order FROM nodes
+               WHERE root_id = $1 AND (path = $2 OR path LIKE $2 || '.%') AND status = 'active'
+               ORDER BY path"#,
+        )
+        .bind(root_id)
+        .bind(&src)
+        .fetch_all(&self.db)
+        .await?;
+        if !rows
+            .iter()
+            .any(|r| r.path == src && r.item_type == "SECTION")
+        {
+            anyhow::bail!("template not found: {}", req.template);
+        }
+
+        let dest = dest.join(".");
+        let Count { count: dest_count } = sqlx::query_as(
+            r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND (path = $2 OR path LIKE $2 || '.%')"#,
+        )
+        .bind(root_id)
+        .bind(&dest)
+        .fetch_one(&self.db)
+        .await?;
+        if dest_count > 0 {
+            anyhow::bail!("destination already exists: {dest}");
+        }
+
+        if let Some((parent, _)) = dest.rsplit_once('.') {
+            let Count { count: parent_count } = sqlx::query_as(
+                r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path = $2 AND item_type = 'SECTION' AND status = 'active'"#,
+            )
+            .bind(root_id)
+            .bind(parent)
+            .fetch_one(&self.db)
+            .await?;
+            if parent_count == 0 {
+                anyhow::bail!("destination parent section not found: {parent}");
+            }
+        }
+
+        // Render everything up front so a bad variable leaves nothing behind.
+        let mut seen = HashSet::new();
+        let mut nodes = Vec::with_capacity(rows.len());
+        for row in rows {
+            let below = row.path[src.len()..]
+                .split('.')
+                .filter(|key| !key.is_empty())
+                .map(|key| template::substitute_key(key, &req.vars))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let path = std::iter::once(dest.clone())
+                .chain(below)
+                .collect::<Vec<_>>()
+                .join(".");
+            if !seen.insert(path.clone()) {
+                anyhow::bail!("template renders two nodes at the same path: {path}");
+            }
+
+            let content = match row.item_content {
+                Some(Json(mut content)) if row.item_type == "ITEM" => {
+                    for field in ["title", "description"] {
+                        if let Some(text) = content.get(field).and_then(|v| v.as_str()) {
+                            content[field] =
+                                serde_json::Value::String(template::substitute(text, &req.vars)?);
+                        }
+                    }
+                    for field in ["due", "start"] {
+                        if let Some(date) = content.get(field).and_then(|v| v.as_str()) {
+                            let date = template::render_due(date, &req.vars, anchor)?;
+                            let (due, start) = match field {
+                                "due" => (Some(date.as_str()), None),
+                                _ => (None, Some(date.as_str())),
+                            };
+                            validate::item(
+                                &path.split('.').map(str::to_string).collect::<Vec<_>>(),
+                                due,
+                                start,
+                                None,
+                                &[],
+                            )?;
+                            content[field] = serde_json::Value::String(date);
+                        }
+                    }
+                    content["state"] = serde_json::Value::String("not-done".into());
+                    if let Some(content) = content.as_object_mut() {
+                        content.remove("completions");
+                        content.remove("completed_at");
+                        if let Some(entries) =
+                            content.get_mut("checklist").and_then(|c| c.as_array_mut())
+                        {
+                            for entry in entries {
+                                entry["done"] = serde_json::Value::Bool(false);
+                            }
+                        }
+                    }
+                    Some(Json(content))
+                }
+                Some(Json(content)) if row.item_type == "SECTION" => {
+                    let meta = template::render_meta(&serde_json::from_value(content)?, &req.vars)?;
+                    Some(Json(serde_json::to_value(meta)?))
+                }
+                content => content,
+            };
+
+            nodes.push((path, row.item_type, content, row.sort_order));
+        }
+
+        let mut tx = self.db.begin().await?;
+        for (path, item_type, content, sort_order) in nodes {
+            sqlx::query(
+                r#"
+    INSERT INTO nodes
+         (id, root_id, path, item_type, item_content, sort_order)
+     VALUES
+         ($1, $2, $3, $4, $5, $6)"#,
+            )
+            .bind(uuid::Uuid::new_v4())
+            .bind(root_id)
+            .bind(path)
+            .bind(item_type)
+            .bind(content)
+            .bind(sort_order)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(Response {})
+    }
+}
+
+pub trait ApplyTemplateExt {
+    fn apply_template_service(&self) -> ApplyTemplate;
+}
+
+impl ApplyTemplateExt for SharedState {
+    fn apply_template_service(&self) -> ApplyTemplate {
+        ApplyTemplate::new(self.db.clone())
+    }
+}
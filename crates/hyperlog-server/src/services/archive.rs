@@ -24,7 +24,7 @@ impl Archive {
 
     pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
         let Root { id: root_id, .. } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
         )
         .bind(req.root)
         .bind(req.user_id)
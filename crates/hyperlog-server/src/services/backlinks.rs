@@ -3,10 +3,9 @@
 //! resolved in Rust) so clients don't download the whole graph just to show
 //! backlinks. Mirrors the client's resolution exactly: a link target resolves
 //! by relative slash-path first, then by item title / section key (first match
-//! in path order), case-insensitive.
-
-use std::collections::HashMap;
+//! in path order), case-insensitive; see `hyperlog_core::wiki_links`.
 
+use hyperlog_core::wiki_links::{parse_targets, LinkResolver};
 use sqlx::types::Json;
 
 use crate::state::SharedState;
@@ -46,38 +45,11 @@ struct NodeRow {
     item_content: Option<Json<serde_json::Value>>,
 }
 
-fn norm(s: &str) -> String {
-    s.trim().to_lowercase()
-}
-
-/// The link targets written in [body] (`[[target]]` / `[[target|alias]]`),
-/// matching the client regex `\[\[([^\[\]\n]+?)\]\]` (no brackets/newlines).
-fn parse_targets(body: &str) -> Vec<String> {
-    let mut out = Vec::new();
-    let mut rest = body;
-    while let Some(open) = rest.find("[[") {
-        let after = &rest[open + 2..];
-        if let Some(close) = after.find("]]") {
-            let inner = &after[..close];
-            if !inner.is_empty()
-                && !inner.contains('[')
-                && !inner.contains(']')
-                && !inner.contains('\n')
-            {
-                let target = inner.split('|').next().unwrap_or("").trim();
-                if !target.is_empty() {
-                    out.push(target.to_string());
-                }
-            }
-            rest = &after[close + 2..];
-        } else {
-            break;
-        }
-    }
-    out
-}
-
-fn display_name(item_type: &str, key: &str, content: &Option<Json<serde_json::Value>>) -> String {
+pub(crate) fn display_name(
+    item_type: &str,
+    key: &str,
+    content: &Option<Json<serde_json::Value>>,
+) -> String {
     if item_type == "ITEM" {
         if let Some(t) = content
             .as_ref()
@@ -105,7 +77,7 @@ impl Backlinks {
         }
 
         let Root { id: root_id } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
         )
         .bind(&req.root)
         .bind(req.user_id)
@@ -122,37 +94,16 @@ impl Backlinks {
         .fetch_all(&self.db)
         .await?;
 
-        // Build resolution maps (first match wins) over all nodes.
-        let mut by_path: HashMap<String, usize> = HashMap::new();
-        let mut by_name: HashMap<String, usize> = HashMap::new();
-        let mut target_idx: Option<usize> = None;
-        for (i, r) in rows.iter().enumerate() {
-            if r.path == target_rel {
-                target_idx = Some(i);
-            }
-            by_path.entry(norm(&r.path.replace('.', "/"))).or_insert(i);
-            let key = r.path.rsplit('.').next().unwrap_or(&r.path);
-            let name = display_name(&r.item_type, key, &r.item_content);
-            by_name.entry(norm(&name)).or_insert(i);
-            by_name.entry(norm(key)).or_insert(i);
-        }
-        let Some(target_idx) = target_idx else {
+        let Some(target_idx) = rows.iter().position(|r| r.path == target_rel) else {
             return Ok(Response { items: Vec::new() });
         };
-
-        let resolve = |t: &str| -> Option<usize> {
-            let t = t.trim();
-            if t.is_empty() {
-                return None;
-            }
-            let n = norm(t);
-            if t.contains('/') {
-                if let Some(&i) = by_path.get(&n) {
-                    return Some(i);
-                }
-            }
-            by_name.get(&n).or_else(|| by_path.get(&n)).copied()
-        };
+        let resolver = LinkResolver::new(rows.iter().map(|r| {
+            let key = r.path.rsplit('.').next().unwrap_or(&r.path);
+            (
+                r.path.clone(),
+                display_name(&r.item_type, key, &r.item_content),
+            )
+        }));
 
         let mut items = Vec::new();
         for (i, r) in rows.iter().enumerate() {
@@ -170,7 +121,7 @@ impl Backlinks {
             }
             let links_here = parse_targets(body)
                 .iter()
-                .any(|t| resolve(t) == Some(target_idx));
+                .any(|t| resolver.resolve(t) == Some(target_idx));
             if !links_here {
                 continue;
             }
@@ -179,13 +130,22 @@ impl Backlinks {
             path.extend(r.path.split('.').map(|s| s.to_string()));
             let (title, description, done) = match &r.item_content {
                 Some(j) => (
-                    j.0.get("title").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    j.0.get("title")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string(),
                     body.to_string(),
                     j.0.get("state").and_then(|v| v.as_str()) == Some("done"),
                 ),
                 None => (String::new(), String::new(), false),
             };
-            items.push(Hit { key, path, title, description, done });
+            items.push(Hit {
+                key,
+                path,
+                title,
+                description,
+                done,
+            });
         }
 
         Ok(Response { items })
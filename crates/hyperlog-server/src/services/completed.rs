@@ -0,0 +1,106 @@
+//! Items completed within a date range, across the user's roots or in one of
+//! them, including those archived since. Dates are compared by
+//! `hyperlog_core::completed`.
+
+use hyperlog_core::{completed::Completed as CompletedItems, due};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct Completed {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    /// Only this root; all of the user's roots when `None`.
+    pub root: Option<String>,
+    /// First and last day, as days since 1970-01-01.
+    pub since: i64,
+    pub until: i64,
+    pub user_id: Option<uuid::Uuid>,
+}
+
+pub struct Response {
+    pub completed: CompletedItems,
+}
+
+#[derive(sqlx::FromRow)]
+struct ItemRow {
+    root_name: String,
+    path: String,
+    item_content: Option<Json<serde_json::Value>>,
+}
+
+#[derive(serde::Deserialize)]
+struct ItemContent {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    completed_at: Option<String>,
+    #[serde(default)]
+    completions: Vec<String>,
+}
+
+impl Completed {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        if req.since > req.until {
+            anyhow::bail!(
+                "since ({}) is after until ({})",
+                due::format_date(req.since),
+                due::format_date(req.until)
+            );
+        }
+
+        let rows: Vec<ItemRow> = sqlx::query_as(
+            r#"
+            SELECT r.root_name, n.path, n.item_content
+            FROM nodes n
+            JOIN roots r ON r.id = n.root_id
+            WHERE r.user_id IS NOT DISTINCT FROM $1
+              AND r.deleted_at IS NULL
+              AND ($2::TEXT IS NULL OR r.root_name = $2)
+              AND n.item_type = 'ITEM'
+              AND (n.item_content ? 'completed_at' OR n.item_content ? 'completions')
+            "#,
+        )
+        .bind(req.user_id)
+        .bind(&req.root)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut completed = CompletedItems::new(req.since, req.until);
+        for row in rows {
+            let Some(Ok(content)) = row
+                .item_content
+                .map(|Json(content)| serde_json::from_value::<ItemContent>(content))
+            else {
+                continue;
+            };
+            completed.push(
+                &row.root_name,
+                &row.path.split('.').map(str::to_string).collect::<Vec<_>>(),
+                &content.title,
+                content.completed_at.as_deref(),
+                &content.completions,
+            );
+        }
+        completed.sort();
+
+        Ok(Response { completed })
+    }
+}
+
+pub trait CompletedExt {
+    fn completed_service(&self) -> Completed;
+}
+
+impl CompletedExt for SharedState {
+    fn completed_service(&self) -> Completed {
+        Completed::new(self.db.clone())
+    }
+}
@@ -0,0 +1,148 @@
+//! Turning an item into a section or an empty section into an item, as
+//! `hyperlog_core::convert` does for the local backend. A section keeps its
+//! metadata as its `item_content`, which is empty for sections created as such.
+
+use hyperlog_core::{
+    convert::{self, Kind},
+    log::{ItemState, SectionMeta},
+};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct ConvertNode {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub path: Vec<String>,
+    pub kind: Kind,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+#[derive(sqlx::FromRow)]
+struct Count {
+    count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct NodeRow {
+    id: uuid::Uuid,
+    item_type: String,
+    item_content: Option<Json<serde_json::Value>>,
+}
+
+#[derive(serde::Deserialize)]
+struct ItemTitle {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(serde::Serialize)]
+struct ItemContent {
+    title: String,
+    description: String,
+    state: ItemState,
+}
+
+impl ConvertNode {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let Some(key) = req.path.last() else {
+            anyhow::bail!("a root can't be converted");
+        };
+        let path = req.path.join(".");
+
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {}", req.root))?;
+
+        let node: NodeRow = sqlx::query_as(
+            r#"SELECT id, item_type, item_content FROM nodes WHERE root_id = $1 AND path = $2 AND status = 'active'"#,
+        )
+        .bind(root_id)
+        .bind(&path)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("node not found: {path}"))?;
+
+        let (item_type, content) = match (node.item_type.as_str(), req.kind) {
+            ("ITEM", Kind::Section) => {
+                let ItemTitle { title, description } = match node.item_content {
+                    Some(Json(content)) => serde_json::from_value(content)?,
+                    None => anyhow::bail!("item has no content: {path}"),
+                };
+                let meta = convert::section_meta(&title, &description);
+                let content = (!meta.is_empty())
+                    .then(|| serde_json::to_value(meta))
+                    .transpose()?;
+                ("SECTION", content)
+            }
+            ("SECTION", Kind::Item) => {
+                // Archived children count too, restoring them needs a section.
+                let Count { count } = sqlx::query_as(
+                    r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path LIKE $2 || '.%'"#,
+                )
+                .bind(root_id)
+                .bind(&path)
+                .fetch_one(&self.db)
+                .await?;
+                if count > 0 {
+                    anyhow::bail!("section {key} is not empty");
+                }
+
+                let meta: SectionMeta = node
+                    .item_content
+                    .map(|Json(content)| serde_json::from_value(content))
+                    .transpose()?
+                    .unwrap_or_default();
+                let (title, description) = convert::item_fields(key, &meta);
+                let content = serde_json::to_value(ItemContent {
+                    title,
+                    description,
+                    state: ItemState::NotDone,
+                })?;
+                ("ITEM", Some(content))
+            }
+            ("ITEM", Kind::Item) => anyhow::bail!("{key} is already an item"),
+            (_, _) => anyhow::bail!("{key} is already a section"),
+        };
+
+        sqlx::query(r#"UPDATE nodes SET item_type = $1, item_content = $2 WHERE id = $3"#)
+            .bind(item_type)
+            .bind(content.map(Json))
+            .bind(node.id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(Response {})
+    }
+}
+
+pub trait ConvertNodeExt {
+    fn convert_node_service(&self) -> ConvertNode;
+}
+
+impl ConvertNodeExt for SharedState {
+    fn convert_node_service(&self) -> ConvertNode {
+        ConvertNode::new(self.db.clone())
+    }
+}
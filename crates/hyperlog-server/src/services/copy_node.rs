@@ -0,0 +1,194 @@
+use hyperlog_core::deps;
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct CopyNode {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub src: Vec<String>,
+    /// Root to copy into; the source root when `None`.
+    pub dest_root: Option<String>,
+    pub dest: Vec<String>,
+    /// Copied items start out not-done.
+    pub reset_state: bool,
+    /// Copied items lose their due date.
+    pub drop_due: bool,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+#[derive(sqlx::FromRow)]
+struct Count {
+    count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct NodeRow {
+    path: String,
+    item_type: String,
+    item_content: Option<Json<serde_json::Value>>,
+    sort_order: Option<f64>,
+}
+
+impl CopyNode {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    async fn root_id(&self, root: &str, user_id: Option<uuid::Uuid>) -> anyhow::Result<uuid::Uuid> {
+        let Root { id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(root)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {root}"))?;
+
+        Ok(id)
+    }
+
+    /// Clone the node at `src` and its active subtree to `dest`, possibly in
+    /// another of the user's roots. Copies get fresh ids and creation times;
+    /// archived descendants are left behind.
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        if req.src.is_empty() || req.dest.is_empty() {
+            anyhow::bail!("src and dest must be non-empty");
+        }
+        let src = req.src.join(".");
+        let dest = req.dest.join(".");
+
+        let src_root_id = self.root_id(&req.root, req.user_id).await?;
+        let dest_root_id = match &req.dest_root {
+            Some(dest_root) if *dest_root != req.root => {
+                self.root_id(dest_root, req.user_id).await?
+            }
+            _ => src_root_id,
+        };
+
+        let rows: Vec<NodeRow> = sqlx::query_as(
+            r#"SELECT path, item_type, item_content, sort_order FROM nodes
+               WHERE root_id = $1 AND (path = $2 OR path LIKE $2 || '.%') AND status = 'active'
+               ORDER BY path"#,
+        )
+        .bind(src_root_id)
+        .bind(&src)
+        .fetch_all(&self.db)
+        .await?;
+        if !rows.iter().any(|r| r.path == src) {
+            anyhow::bail!("source not found: {src}");
+        }
+
+        let Count { count: dest_count } = sqlx::query_as(
+            r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path = $2"#,
+        )
+        .bind(dest_root_id)
+        .bind(&dest)
+        .fetch_one(&self.db)
+        .await?;
+        if dest_count > 0 {
+            anyhow::bail!("destination already exists: {dest}");
+        }
+
+        if req.dest.len() > 1 {
+            let parent = req.dest[..req.dest.len() - 1].join(".");
+            let Count { count: parent_count } = sqlx::query_as(
+                r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path = $2 AND item_type = 'SECTION' AND status = 'active'"#,
+            )
+            .bind(dest_root_id)
+            .bind(&parent)
+            .fetch_one(&self.db)
+            .await?;
+            if parent_count == 0 {
+                anyhow::bail!("destination parent section not found: {parent}");
+            }
+        }
+
+        let mut tx = self.db.begin().await?;
+        for row in rows {
+            let below = &row.path[src.len()..];
+            let path = format!("{dest}{below}");
+            // The copy goes after any manually ordered siblings in its new parent.
+            let sort_order = if below.is_empty() {
+                None
+            } else {
+                row.sort_order
+            };
+            let content = row.item_content.map(|Json(mut content)| {
+                if row.item_type == "ITEM" {
+                    if req.reset_state {
+                        content["state"] = serde_json::Value::String("not-done".into());
+                        if let Some(content) = content.as_object_mut() {
+                            content.remove("completions");
+                            content.remove("completed_at");
+                            if let Some(entries) =
+                                content.get_mut("checklist").and_then(|c| c.as_array_mut())
+                            {
+                                for entry in entries {
+                                    entry["done"] = serde_json::Value::Bool(false);
+                                }
+                            }
+                        }
+                    }
+                    if req.drop_due {
+                        if let Some(content) = content.as_object_mut() {
+                            content.remove("due");
+                        }
+                    }
+                    // The copy depends on its own copied items, and on nothing
+                    // outside it once it is in another root.
+                    if let Some(depends_on) = content.get_mut("depends_on") {
+                        let mut edges: Vec<String> =
+                            serde_json::from_value(depends_on.take()).unwrap_or_default();
+                        if dest_root_id == src_root_id {
+                            deps::retarget(&mut edges, &src, &dest);
+                        } else {
+                            deps::carry(&mut edges, &src, &dest);
+                        }
+                        *depends_on = serde_json::json!(edges);
+                    }
+                }
+                Json(content)
+            });
+
+            sqlx::query(
+                r#"
+    INSERT INTO nodes
+         (id, root_id, path, item_type, item_content, sort_order)
+     VALUES
+         ($1, $2, $3, $4, $5, $6)"#,
+            )
+            .bind(uuid::Uuid::new_v4())
+            .bind(dest_root_id)
+            .bind(path)
+            .bind(row.item_type)
+            .bind(content)
+            .bind(sort_order)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(Response {})
+    }
+}
+
+pub trait CopyNodeExt {
+    fn copy_node_service(&self) -> CopyNode;
+}
+
+impl CopyNodeExt for SharedState {
+    fn copy_node_service(&self) -> CopyNode {
+        CopyNode::new(self.db.clone())
+    }
+}
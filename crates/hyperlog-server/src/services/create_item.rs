@@ -1,4 +1,9 @@
-use hyperlog_core::log::{ItemState, Link};
+use hyperlog_core::{
+    checklist::ChecklistEntry,
+    completed,
+    log::{ItemState, Link, Priority},
+    tags, validate,
+};
 use sqlx::types::Json;
 
 use crate::state::SharedState;
@@ -17,7 +22,12 @@ pub struct Request {
     pub description: String,
     pub state: ItemState,
     pub due: Option<String>,
+    pub start: Option<String>,
+    pub recur: Option<String>,
     pub links: Vec<Link>,
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    pub checklist: Vec<ChecklistEntry>,
 }
 pub struct Response {}
 
@@ -28,8 +38,20 @@ struct ItemContent {
     pub state: ItemState,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub due: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recur: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub links: Vec<Link>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
 }
 
 #[derive(sqlx::FromRow)]
@@ -46,8 +68,17 @@ impl CreateItem {
     }
 
     pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        validate::item(
+            &req.path,
+            req.due.as_deref(),
+            req.start.as_deref(),
+            req.recur.as_deref(),
+            &req.links,
+        )?;
+        validate::checklist(&req.checklist)?;
+
         let Root { id: root_id, .. } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
         )
         .bind(req.root)
         .bind(req.user_id)
@@ -75,6 +106,7 @@ impl CreateItem {
                     .await?;
                 }
 
+                let completed_at = matches!(req.state, ItemState::Done).then(completed::now);
                 let node_id = uuid::Uuid::new_v4();
                 sqlx::query(
                     r#"
@@ -92,7 +124,13 @@ impl CreateItem {
                     description: req.description,
                     state: req.state,
                     due: req.due,
+                    start: req.start,
+                    recur: req.recur,
                     links: req.links,
+                    tags: tags::normalize_all(&req.tags),
+                    priority: req.priority,
+                    checklist: req.checklist,
+                    completed_at,
                 }))
                 .execute(&self.db)
                 .await?;
@@ -0,0 +1,45 @@
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct DeleteRoot {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+impl DeleteRoot {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Soft delete: the root disappears from every query right away, but its
+    /// nodes are only removed by [`super::purge_roots`] after the grace period.
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let res = sqlx::query(
+            r#"UPDATE roots SET deleted_at = now() WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .execute(&self.db)
+        .await?;
+        if res.rows_affected() == 0 {
+            anyhow::bail!("root {} was not found", req.root);
+        }
+
+        Ok(Response {})
+    }
+}
+
+pub trait DeleteRootExt {
+    fn delete_root_service(&self) -> DeleteRoot;
+}
+
+impl DeleteRootExt for SharedState {
+    fn delete_root_service(&self) -> DeleteRoot {
+        DeleteRoot::new(self.db.clone())
+    }
+}
@@ -0,0 +1,130 @@
+//! Reports stored items whose due or links would be rejected today. Rows
+//! written before validation existed are left alone; the report lists them so
+//! they can be fixed by hand. Runs at startup when `HYPERLOG_FSCK_ON_START` is
+//! set, and on demand from the internal http server. Rows are streamed, so
+//! only the findings are held.
+
+use futures::TryStreamExt;
+use hyperlog_core::{log::Link, validate};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct Fsck {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {}
+
+#[derive(serde::Serialize)]
+pub struct Response {
+    pub checked: usize,
+    pub invalid: Vec<Finding>,
+}
+
+#[derive(serde::Serialize)]
+pub struct Finding {
+    pub root: String,
+    pub path: String,
+    pub errors: Vec<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ItemRow {
+    root_name: String,
+    path: String,
+    item_content: Option<Json<serde_json::Value>>,
+}
+
+#[derive(serde::Deserialize)]
+struct ItemContent {
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    start: Option<String>,
+    #[serde(default)]
+    recur: Option<String>,
+    #[serde(default)]
+    links: Vec<Link>,
+}
+
+impl Fsck {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, _req: Request) -> anyhow::Result<Response> {
+        let mut rows = sqlx::query_as::<_, ItemRow>(
+            r#"SELECT r.root_name, n.path, n.item_content
+               FROM nodes n JOIN roots r ON r.id = n.root_id
+               WHERE n.item_type = 'ITEM'
+               ORDER BY r.root_name, n.path"#,
+        )
+        .fetch(&self.db);
+
+        let mut checked = 0;
+        let mut invalid = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            checked += 1;
+            let errors = match row
+                .item_content
+                .map(|Json(content)| serde_json::from_value::<ItemContent>(content))
+            {
+                Some(Ok(content)) => {
+                    let path = row.path.split('.').map(str::to_string).collect::<Vec<_>>();
+                    match validate::item(
+                        &path,
+                        content.due.as_deref(),
+                        content.start.as_deref(),
+                        content.recur.as_deref(),
+                        &content.links,
+                    ) {
+                        Ok(()) => continue,
+                        Err(invalid) => invalid.0.iter().map(|e| e.to_string()).collect(),
+                    }
+                }
+                Some(Err(e)) => vec![format!("item_content: {e}")],
+                None => vec!["item_content: missing".to_string()],
+            };
+            invalid.push(Finding {
+                root: row.root_name,
+                path: row.path,
+                errors,
+            });
+        }
+
+        Ok(Response { checked, invalid })
+    }
+
+    /// Check once and log what was found.
+    pub async fn report(&self) {
+        match self.execute(Request {}).await {
+            Ok(Response { checked, invalid }) if invalid.is_empty() => {
+                tracing::debug!("fsck: {checked} items checked, none invalid")
+            }
+            Ok(Response { checked, invalid }) => {
+                tracing::warn!("fsck: {} of {checked} items are invalid", invalid.len());
+                for finding in invalid {
+                    tracing::warn!(
+                        "fsck: {}/{}: {}",
+                        finding.root,
+                        finding.path,
+                        finding.errors.join("; ")
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("fsck failed: {e:#}"),
+        }
+    }
+}
+
+pub trait FsckExt {
+    fn fsck_service(&self) -> Fsck;
+}
+
+impl FsckExt for SharedState {
+    fn fsck_service(&self) -> Fsck {
+        Fsck::new(self.db.clone())
+    }
+}
@@ -32,6 +32,7 @@ impl GetAvailableRoots {
         roots
     WHERE
         user_id IS NOT DISTINCT FROM $1
+        AND deleted_at IS NULL
     LIMIT
         100
             "#,
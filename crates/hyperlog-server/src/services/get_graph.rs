@@ -1,6 +1,10 @@
 use std::collections::BTreeMap;
 
-use hyperlog_core::log::{GraphItem, ItemState};
+use hyperlog_core::{
+    checklist::ChecklistEntry,
+    due,
+    log::{GraphItem, ItemState, Priority},
+};
 use serde::Deserialize;
 use sqlx::types::Json;
 
@@ -17,6 +21,9 @@ pub struct Request {
     pub root: String,
     pub path: Vec<String>,
     pub user_id: Option<uuid::Uuid>,
+    /// Today as `hyperlog_core::due` days; items starting after it are left
+    /// out. `None` includes them.
+    pub hide_deferred: Option<i64>,
 }
 pub struct Response {
     pub item: GraphItem,
@@ -32,6 +39,24 @@ struct Item {
     title: String,
     description: String,
     state: ItemState,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    start: Option<String>,
+    #[serde(default)]
+    recur: Option<String>,
+    #[serde(default)]
+    completions: Vec<String>,
+    #[serde(default)]
+    completed_at: Option<String>,
+    #[serde(default)]
+    depends_on: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    priority: Option<Priority>,
+    #[serde(default)]
+    checklist: Vec<ChecklistEntry>,
 }
 
 #[derive(sqlx::FromRow, Debug)]
@@ -48,7 +73,7 @@ impl GetGraph {
 
     pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
         let Root { id: root_id, .. } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
         )
         .bind(&req.root)
         .bind(req.user_id)
@@ -79,7 +104,7 @@ impl GetGraph {
         .fetch_all(&self.db)
         .await?;
 
-        let item = self.build_graph(req.root, req.path, nodes)?;
+        let item = self.build_graph(req.root, req.path, nodes, req.hide_deferred)?;
 
         Ok(Response { item })
     }
@@ -89,12 +114,13 @@ impl GetGraph {
         root: String,
         path: Vec<String>,
         mut nodes: Vec<Node>,
+        hide_deferred: Option<i64>,
     ) -> anyhow::Result<GraphItem> {
         nodes.sort_by(|a, b| a.path.cmp(&b.path));
         let mut engine = Engine::default();
         engine.create_root(&root)?;
 
-        self.get_graph_items(&root, &mut engine, &nodes)?;
+        self.get_graph_items(&root, &mut engine, &nodes, hide_deferred)?;
 
         engine
             .get(&root, &path.iter().map(|s| s.as_str()).collect::<Vec<_>>())
@@ -107,9 +133,15 @@ impl GetGraph {
         root: &str,
         engine: &mut Engine,
         nodes: &Vec<Node>,
+        hide_deferred: Option<i64>,
     ) -> anyhow::Result<()> {
         for node in nodes {
             if let Some(item) = self.get_graph_item(node) {
+                if let (GraphItem::Item { start, .. }, Some(today)) = (&item, hide_deferred) {
+                    if due::is_deferred(start.as_deref(), today) {
+                        continue;
+                    }
+                }
                 let path = node.path.split('.').collect::<Vec<_>>();
                 engine.create(root, &path, item)?;
             }
@@ -120,7 +152,15 @@ impl GetGraph {
 
     fn get_graph_item(&self, node: &Node) -> Option<GraphItem> {
         match node.item_type.as_str() {
-            "SECTION" => Some(GraphItem::Section(BTreeMap::default())),
+            "SECTION" => Some(GraphItem::Section {
+                // Sections created empty have no content.
+                meta: node
+                    .item_content
+                    .as_ref()
+                    .and_then(|content| serde_json::from_value(content.0.clone()).ok())
+                    .unwrap_or_default(),
+                items: BTreeMap::default(),
+            }),
             "ITEM" => {
                 if let Some(content) = &node.item_content {
                     let item: Item = serde_json::from_value(content.0.clone()).ok()?;
@@ -129,6 +169,15 @@ impl GetGraph {
                         title: item.title,
                         description: item.description,
                         state: item.state,
+                        due: item.due,
+                        start: item.start,
+                        recur: item.recur,
+                        completions: item.completions,
+                        completed_at: item.completed_at,
+                        depends_on: item.depends_on,
+                        tags: item.tags,
+                        priority: item.priority,
+                        checklist: item.checklist,
                     })
                 } else {
                     None
@@ -154,7 +203,7 @@ mod engine {
     use std::{collections::BTreeMap, fmt::Display};
 
     use anyhow::{anyhow, Context};
-    use hyperlog_core::log::{Graph, GraphItem, ItemState};
+    use hyperlog_core::log::{Graph, GraphItem};
 
     #[derive(Default)]
     pub struct Engine {
@@ -205,7 +254,7 @@ mod engine {
                         None => anyhow::bail!("path: {} section was not found", section),
                     },
                     GraphItem::Item { .. } => anyhow::bail!("path: {} was already found", section),
-                    GraphItem::Section(s) => match s.get_mut(section.to_owned()) {
+                    GraphItem::Section { items: s, .. } => match s.get_mut(section.to_owned()) {
                         Some(graph_item) => {
                             current_item = graph_item;
                         }
@@ -218,7 +267,7 @@ mod engine {
                 GraphItem::User(u) => {
                     u.insert(last.to_string(), item);
                 }
-                GraphItem::Section(s) => {
+                GraphItem::Section { items: s, .. } => {
                     s.insert(last.to_string(), item);
                 }
                 GraphItem::Item { .. } => anyhow::bail!("cannot insert an item into an item"),
@@ -273,7 +322,7 @@ mod engine {
                     }
                     u.insert(src_item.to_string(), src);
                 }
-                GraphItem::Section(s) => {
+                GraphItem::Section { items: s, .. } => {
                     if s.contains_key(*src_item) {
                         return Err(anyhow!("key was already found, aborting: {}", src_item));
                     }
@@ -300,10 +349,7 @@ mod engine {
         pub fn toggle_item(&mut self, root: &str, path: &[&str]) -> anyhow::Result<()> {
             if let Some(item) = self.get_mut(root, path) {
                 match item {
-                    GraphItem::Item { state, .. } => match state {
-                        ItemState::NotDone => *state = ItemState::Done,
-                        ItemState::Done => *state = ItemState::NotDone,
-                    },
+                    GraphItem::Item { state, .. } => *state = state.toggled(),
                     _ => {
                         anyhow::bail!("{}.{:?} is not an item", root, path)
                     }
@@ -323,7 +369,7 @@ mod engine {
             if let Some((name, dest_last)) = path.split_last() {
                 if let Some(parent) = self.get_mut(root, dest_last) {
                     match parent {
-                        GraphItem::User(s) | GraphItem::Section(s) => {
+                        GraphItem::User(s) | GraphItem::Section { items: s, .. } => {
                             if let Some(mut existing) = s.remove(*name) {
                                 match (&mut existing, item) {
                                     (
@@ -331,16 +377,24 @@ mod engine {
                                             title: ex_title,
                                             description: ex_desc,
                                             state: ex_state,
+                                            due: ex_due,
+                                            recur: ex_recur,
+                                            ..
                                         },
                                         GraphItem::Item {
                                             title,
                                             description,
                                             state,
+                                            due,
+                                            recur,
+                                            ..
                                         },
                                     ) => {
                                         ex_title.clone_from(title);
                                         ex_desc.clone_from(description);
                                         ex_state.clone_from(state);
+                                        ex_due.clone_from(due);
+                                        ex_recur.clone_from(recur);
 
                                         let title = title.replace(".", "-");
                                         s.insert(title, existing.clone());
@@ -0,0 +1,141 @@
+//! Progress of a root or one of its sections, counted by
+//! `hyperlog_core::stats`.
+//! Creation days come from the DB-owned `created_at` column. Nodes are
+//! streamed in path order, so each section is known before its items arrive
+//! and only the counts are held.
+
+use futures::TryStreamExt;
+use hyperlog_core::{
+    log::ItemState,
+    stats::{self, Stats, StatsEntry},
+};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct GetStats {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    /// Root-relative section; the whole root when empty.
+    pub path: Vec<String>,
+    /// First and last day of the series, as days since 1970-01-01.
+    pub since: i64,
+    pub today: i64,
+    pub user_id: Option<uuid::Uuid>,
+}
+
+pub struct Response {
+    pub stats: Stats,
+}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+#[derive(sqlx::FromRow)]
+struct NodeRow {
+    path: String,
+    item_type: String,
+    item_content: Option<Json<serde_json::Value>>,
+    created_unix: i64,
+}
+
+#[derive(serde::Deserialize)]
+struct ItemContent {
+    #[serde(default)]
+    state: ItemState,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    completed_at: Option<String>,
+}
+
+impl GetStats {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        stats::check_range(req.since, req.today)?;
+
+        let Some(Root { id: root_id }) = sqlx::query_as(
+            r#"SELECT id FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        else {
+            anyhow::bail!("root not found: {}", req.root);
+        };
+
+        let prefix = req.path.join(".");
+        let mut rows = sqlx::query_as::<_, NodeRow>(
+            r#"
+            SELECT
+                path,
+                item_type,
+                item_content,
+                COALESCE(extract(epoch from created_at)::bigint, 0) AS created_unix
+            FROM nodes
+            WHERE root_id = $1
+              AND status = 'active'
+              AND ($2 = '' OR path = $2 OR path LIKE $2 || '.%')
+            ORDER BY path
+            "#,
+        )
+        .bind(root_id)
+        .bind(&prefix)
+        .fetch(&self.db);
+
+        let mut found = prefix.is_empty();
+        let mut stats = Stats::new(req.path, req.since, req.today);
+        while let Some(row) = rows.try_next().await? {
+            if row.item_type == "SECTION" {
+                if row.path == prefix {
+                    found = true;
+                } else {
+                    stats.add_section(row.path.split('.').map(str::to_string).collect());
+                }
+                continue;
+            }
+            if row.item_type != "ITEM" {
+                continue;
+            }
+            let Some(Ok(content)) = row
+                .item_content
+                .map(|Json(content)| serde_json::from_value::<ItemContent>(content))
+            else {
+                continue;
+            };
+            stats.push(StatsEntry {
+                path: &row.path.split('.').map(str::to_string).collect::<Vec<_>>(),
+                state: &content.state,
+                due: content.due.as_deref(),
+                created_unix: Some(row.created_unix).filter(|c| *c > 0),
+                completed_at: content.completed_at.as_deref(),
+            });
+        }
+        if !found {
+            anyhow::bail!("no section at {}.{}", req.root, prefix);
+        }
+        stats.finish();
+
+        Ok(Response { stats })
+    }
+}
+
+pub trait GetStatsExt {
+    fn get_stats_service(&self) -> GetStats;
+}
+
+impl GetStatsExt for SharedState {
+    fn get_stats_service(&self) -> GetStats {
+        GetStats::new(self.db.clone())
+    }
+}
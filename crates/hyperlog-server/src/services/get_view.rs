@@ -5,17 +5,28 @@
 //! at `max_depth` — except nodes listed in `expanded`, whose direct children are
 //! fetched fully. Each section carries its true `child_count` + a `truncated`
 //! flag so the UI can show "+N more". Only children of INCLUDED nodes are
-//! fetched, so the result is always a consistent subtree.
+//! fetched, so the result is always a consistent subtree. With `ready`, closed
+//! items and items waiting on a state or dependency are left out, counts too;
+//! with `tag`, so are items not carrying it. With `by_priority`, items are
+//! ordered most urgent first, unprioritized items and then sections after them,
+//! each in manual order. With `hide_deferred`, items whose start date is still
+//! ahead are left out, counts too.
 
 use std::collections::HashSet;
 use std::future::Future;
 use std::pin::Pin;
 
-use hyperlog_core::log::Link;
+use hyperlog_core::{
+    checklist::ChecklistEntry,
+    log::{Link, Priority, SectionMeta},
+    tags,
+};
 use sqlx::types::Json;
 
 use crate::state::SharedState;
 
+use super::{add_dependency::load_dependencies, list_tags::tagged_paths};
+
 #[derive(Clone)]
 pub struct GetView {
     db: sqlx::PgPool,
@@ -28,6 +39,12 @@ pub struct Request {
     pub expanded: HashSet<String>,
     pub max_depth: i32,
     pub limits: Vec<i32>,
+    pub ready: bool,
+    pub tag: Option<String>,
+    pub by_priority: bool,
+    /// Today as `hyperlog_core::due` days; items starting after it are left
+    /// out. `None` includes them.
+    pub hide_deferred: Option<i64>,
 }
 
 pub struct ViewItem {
@@ -44,6 +61,16 @@ pub struct ViewItem {
     pub due: Option<String>,
     pub created_unix: i64,
     pub links: Vec<Link>,
+    pub depends_on: Vec<String>,
+    pub blocked: bool,     // waiting on an open dependency
+    pub tags: Vec<String>, // set and inline
+    pub priority: Option<Priority>,
+    pub start: Option<String>,
+    pub checklist: Vec<ChecklistEntry>,
+    // Section metadata (sections only; empty/false for items + root).
+    pub colour: String,
+    pub icon: String,
+    pub collapsed: bool,
 }
 
 pub struct Response {
@@ -77,12 +104,29 @@ SELECT
         WHERE g.root_id = $1 AND g.status = 'active'
           AND g.path LIKE c.path || '.%'
           AND g.path NOT LIKE c.path || '.%.%'
+          AND (NOT $4 OR g.item_type <> 'ITEM' OR (
+              COALESCE(g.item_content->>'state', '') NOT IN ('done', 'cancelled')
+              AND NOT COALESCE(g.item_content->'state' ? 'blocked', false)
+              AND g.path <> ALL($5)))
+          AND ($6::TEXT[] IS NULL OR g.item_type <> 'ITEM' OR g.path = ANY($6))
+          AND ($8::TEXT IS NULL OR g.item_type <> 'ITEM'
+               OR COALESCE(LEFT(g.item_content->>'start', 10), '') <= $8)
     ) ELSE 0 END AS own_child_count
 FROM nodes c
 WHERE c.root_id = $1 AND c.status = 'active'
   AND (CASE WHEN $2 = '' THEN c.path NOT LIKE '%.%'
             ELSE c.path LIKE $2 || '.%' AND c.path NOT LIKE $2 || '.%.%' END)
-ORDER BY c.sort_order ASC NULLS LAST, c.path
+  AND (NOT $4 OR c.item_type <> 'ITEM' OR (
+      COALESCE(c.item_content->>'state', '') NOT IN ('done', 'cancelled')
+      AND NOT COALESCE(c.item_content->'state' ? 'blocked', false)
+      AND c.path <> ALL($5)))
+  AND ($6::TEXT[] IS NULL OR c.item_type <> 'ITEM' OR c.path = ANY($6))
+  AND ($8::TEXT IS NULL OR c.item_type <> 'ITEM'
+       OR COALESCE(LEFT(c.item_content->>'start', 10), '') <= $8)
+ORDER BY
+    CASE WHEN $7 AND c.item_type = 'ITEM' THEN COALESCE(c.item_content->>'priority', 'P9') END ASC NULLS LAST,
+    c.sort_order ASC NULLS LAST,
+    c.path
 LIMIT $3
 "#;
 
@@ -92,6 +136,19 @@ struct ParsedItem {
     done: bool,
     due: Option<String>,
     links: Vec<Link>,
+    depends_on: Vec<String>,
+    tags: Vec<String>,
+    priority: Option<Priority>,
+    start: Option<String>,
+    checklist: Vec<ChecklistEntry>,
+}
+
+/// Paths that items are filtered on, sorted.
+struct Filters {
+    /// Items waiting on a dependency.
+    blocked: Vec<String>,
+    /// Items carrying the requested tag; `None` when not filtering by tag.
+    tagged: Option<Vec<String>>,
 }
 
 fn parse_item(c: &Option<Json<serde_json::Value>>) -> ParsedItem {
@@ -101,18 +158,51 @@ fn parse_item(c: &Option<Json<serde_json::Value>>) -> ParsedItem {
             let description =
                 j.0.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string();
             let done = j.0.get("state").and_then(|v| v.as_str()) == Some("done");
-            let due = j
-                .0
-                .get("due")
-                .and_then(|v| v.as_str())
-                .filter(|s| !s.is_empty())
-                .map(|s| s.to_string());
+            let date = |field: &str| {
+                j.0.get(field)
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+            };
+            let due = date("due");
+            let start = date("start");
             let links = j
                 .0
                 .get("links")
                 .and_then(|v| serde_json::from_value::<Vec<Link>>(v.clone()).ok())
                 .unwrap_or_default();
-            ParsedItem { title, description, done, due, links }
+            let depends_on = j
+                .0
+                .get("depends_on")
+                .and_then(|v| serde_json::from_value::<Vec<String>>(v.clone()).ok())
+                .unwrap_or_default();
+            let tags = j
+                .0
+                .get("tags")
+                .and_then(|v| serde_json::from_value::<Vec<String>>(v.clone()).ok())
+                .unwrap_or_default();
+            let tags = tags::item_tags(&tags, &description);
+            let priority = j
+                .0
+                .get("priority")
+                .and_then(|v| serde_json::from_value::<Priority>(v.clone()).ok());
+            let checklist = j
+                .0
+                .get("checklist")
+                .and_then(|v| serde_json::from_value::<Vec<ChecklistEntry>>(v.clone()).ok())
+                .unwrap_or_default();
+            ParsedItem {
+                title,
+                description,
+                done,
+                due,
+                links,
+                depends_on,
+                tags,
+                priority,
+                start,
+                checklist,
+            }
         }
         None => ParsedItem {
             title: String::new(),
@@ -120,6 +210,11 @@ fn parse_item(c: &Option<Json<serde_json::Value>>) -> ParsedItem {
             done: false,
             due: None,
             links: Vec::new(),
+            depends_on: Vec::new(),
+            tags: Vec::new(),
+            priority: None,
+            start: None,
+            checklist: Vec::new(),
         },
     }
 }
@@ -146,6 +241,7 @@ impl GetView {
         child_depth: i32,
         parent_expanded: bool,
         req: &'a Request,
+        filters: &'a Filters,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<(Vec<ViewItem>, i64)>> + Send + 'a>> {
         Box::pin(async move {
             let cap: i64 = if parent_expanded {
@@ -164,6 +260,11 @@ impl GetView {
                 .bind(root_id)
                 .bind(&parent_rel)
                 .bind(cap)
+                .bind(req.ready)
+                .bind(&filters.blocked)
+                .bind(&filters.tagged)
+                .bind(req.by_priority)
+                .bind(req.hide_deferred.map(hyperlog_core::due::format_date))
                 .fetch_all(&self.db)
                 .await?;
 
@@ -187,24 +288,45 @@ impl GetView {
                         due: item.due,
                         created_unix: r.created_unix,
                         links: item.links,
+                        blocked: filters.blocked.binary_search(&r.path).is_ok(),
+                        depends_on: item.depends_on,
+                        tags: item.tags,
+                        priority: item.priority,
+                        start: item.start,
+                        checklist: item.checklist,
+                        colour: String::new(),
+                        icon: String::new(),
+                        collapsed: false,
                     });
                 } else {
                     let child_expanded = req.expanded.contains(&r.path);
                     let recurse = child_depth < req.max_depth || child_expanded;
                     let children = if recurse {
-                        self.children_of(root_id, r.path.clone(), child_depth + 1, child_expanded, req)
-                            .await?
+                        self.children_of(
+                            root_id,
+                            r.path.clone(),
+                            child_depth + 1,
+                            child_expanded,
+                            req,
+                            filters,
+                        )
+                        .await?
                             .0
                     } else {
                         Vec::new()
                     };
                     let truncated = (r.own_child_count as usize) > children.len();
+                    let meta: SectionMeta = r
+                        .item_content
+                        .as_ref()
+                        .and_then(|content| serde_json::from_value(content.0.clone()).ok())
+                        .unwrap_or_default();
                     out.push(ViewItem {
                         key,
                         path,
                         kind: "section".into(),
-                        title: String::new(),
-                        description: String::new(),
+                        title: meta.title,
+                        description: meta.description,
                         done: false,
                         child_count: r.own_child_count as i32,
                         truncated,
@@ -212,6 +334,15 @@ impl GetView {
                         due: None,
                         created_unix: r.created_unix,
                         links: Vec::new(),
+                        depends_on: Vec::new(),
+                        blocked: false,
+                        tags: Vec::new(),
+                        priority: None,
+                        start: None,
+                        checklist: Vec::new(),
+                        colour: meta.colour,
+                        icon: meta.icon,
+                        collapsed: meta.collapsed,
                     });
                 }
             }
@@ -221,23 +352,51 @@ impl GetView {
 
     pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
         let Root { id: root_id } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
         )
         .bind(&req.root)
         .bind(req.user_id)
         .fetch_one(&self.db)
         .await?;
 
+        let mut conn = self.db.acquire().await?;
+        let deps = load_dependencies(&mut conn, root_id, &[]).await?;
+        drop(conn);
+        let tagged = match &req.tag {
+            Some(tag) => Some(tagged_paths(&self.db, root_id, tag).await?),
+            None => None,
+        };
+        let filters = Filters {
+            blocked: deps.blocked().into_iter().map(String::from).collect(),
+            tagged,
+        };
+
         let (children, total) = self
-            .children_of(root_id, req.focus.clone(), 1, false, &req)
+            .children_of(root_id, req.focus.clone(), 1, false, &req, &filters)
             .await?;
 
+        // A focused section carries its own metadata; the root has none.
+        let meta: SectionMeta = if req.focus.is_empty() {
+            SectionMeta::default()
+        } else {
+            sqlx::query_as::<_, (Option<Json<SectionMeta>>,)>(
+                r#"SELECT item_content FROM nodes WHERE root_id = $1 AND path = $2 AND item_type = 'SECTION' AND status = 'active'"#,
+            )
+            .bind(root_id)
+            .bind(&req.focus)
+            .fetch_optional(&self.db)
+            .await?
+            .and_then(|(content,)| content)
+            .map(|Json(meta)| meta)
+            .unwrap_or_default()
+        };
+
         let root = ViewItem {
             key: full_path(&req.root, &req.focus).last().cloned().unwrap_or_default(),
             path: full_path(&req.root, &req.focus),
             kind: if req.focus.is_empty() { "root".into() } else { "section".into() },
-            title: String::new(),
-            description: String::new(),
+            title: meta.title,
+            description: meta.description,
             done: false,
             child_count: total as i32,
             truncated: total as usize > children.len(),
@@ -245,6 +404,15 @@ impl GetView {
             due: None,
             created_unix: 0,
             links: Vec::new(),
+            depends_on: Vec::new(),
+            blocked: false,
+            tags: Vec::new(),
+            priority: None,
+            start: None,
+            checklist: Vec::new(),
+            colour: meta.colour,
+            icon: meta.icon,
+            collapsed: meta.collapsed,
         };
 
         Ok(Response { root })
@@ -0,0 +1,128 @@
+//! Tags in use across the user's roots or in one of them, with how many active
+//! items carry each. Inline `#tag`s live in the description, so tags are
+//! collected in Rust with `hyperlog_core::tags` rather than in SQL; rows are
+//! streamed so only the counts are held.
+
+use futures::TryStreamExt;
+use hyperlog_core::tags::{self, Tally};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+use super::search::like_literal;
+
+#[derive(Clone)]
+pub struct ListTags {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    /// Only this root; all of the user's roots when `None`.
+    pub root: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+}
+
+pub struct Response {
+    /// Most used first, then by name.
+    pub tags: Vec<(String, i64)>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TagRow {
+    path: String,
+    tags: Option<Json<serde_json::Value>>,
+    description: Option<String>,
+}
+
+impl TagRow {
+    fn item_tags(&self) -> Vec<String> {
+        let set = self
+            .tags
+            .as_ref()
+            .and_then(|Json(t)| serde_json::from_value::<Vec<String>>(t.clone()).ok())
+            .unwrap_or_default();
+        tags::item_tags(&set, self.description.as_deref().unwrap_or_default())
+    }
+}
+
+/// Dotted paths of the root's active items tagged with `tag`, sorted. SQL
+/// picks the items that have the tag set or mention `#tag` at all; which of
+/// the mentions are inline tags is left to `hyperlog_core::tags`.
+pub(crate) async fn tagged_paths(
+    db: &sqlx::PgPool,
+    root_id: uuid::Uuid,
+    tag: &str,
+) -> anyhow::Result<Vec<String>> {
+    let tag = tags::normalize(tag);
+    let inline_tag_pattern = tag
+        .as_deref()
+        .map(|tag| format!("%#{}%", like_literal(tag)));
+    let rows: Vec<TagRow> = sqlx::query_as(
+        r#"
+        SELECT path, item_content->'tags' AS tags, item_content->>'description' AS description
+        FROM nodes
+        WHERE root_id = $1 AND item_type = 'ITEM' AND status = 'active'
+          AND ($2::TEXT IS NULL
+            OR item_content->'tags' ? $2
+            OR item_content->>'description' ILIKE $3)
+        ORDER BY path
+        "#,
+    )
+    .bind(root_id)
+    .bind(&tag)
+    .bind(&inline_tag_pattern)
+    .fetch_all(db)
+    .await?;
+
+    let Some(tag) = tag else {
+        return Ok(rows.into_iter().map(|r| r.path).collect());
+    };
+    Ok(rows
+        .into_iter()
+        .filter(|r| r.item_tags().contains(&tag))
+        .map(|r| r.path)
+        .collect())
+}
+
+impl ListTags {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let mut rows = sqlx::query_as::<_, TagRow>(
+            r#"
+            SELECT n.path, n.item_content->'tags' AS tags, n.item_content->>'description' AS description
+            FROM nodes n
+            JOIN roots r ON r.id = n.root_id
+            WHERE r.user_id IS NOT DISTINCT FROM $1
+              AND r.deleted_at IS NULL
+              AND ($2::TEXT IS NULL OR r.root_name = $2)
+              AND n.item_type = 'ITEM'
+              AND n.status = 'active'
+            "#,
+        )
+        .bind(req.user_id)
+        .bind(&req.root)
+        .fetch(&self.db);
+
+        let mut tally = Tally::default();
+        while let Some(row) = rows.try_next().await? {
+            tally.add(row.item_tags());
+        }
+
+        Ok(Response {
+            tags: tally.into_counts(),
+        })
+    }
+}
+
+pub trait ListTagsExt {
+    fn list_tags_service(&self) -> ListTags;
+}
+
+impl ListTagsExt for SharedState {
+    fn list_tags_service(&self) -> ListTags {
+        ListTags::new(self.db.clone())
+    }
+}
@@ -1,5 +1,9 @@
+use hyperlog_core::deps;
+
 use crate::state::SharedState;
 
+use super::add_dependency::{retarget_dependencies, rewrite_dependencies};
+
 #[derive(Clone)]
 pub struct MoveNode {
     db: sqlx::PgPool,
@@ -8,6 +12,8 @@ pub struct MoveNode {
 pub struct Request {
     pub root: String,
     pub src: Vec<String>,
+    /// Root to move into; the source root when `None`.
+    pub dest_root: Option<String>,
     pub dest: Vec<String>,
     pub user_id: Option<uuid::Uuid>,
 }
@@ -28,30 +34,47 @@ impl MoveNode {
         Self { db }
     }
 
+    async fn root_id(&self, root: &str, user_id: Option<uuid::Uuid>) -> anyhow::Result<uuid::Uuid> {
+        let Root { id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(root)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {root}"))?;
+
+        Ok(id)
+    }
+
     /// Move the node at `src` (and its whole subtree) to `dest` by rewriting the
-    /// dotted materialized path prefix. Validates the move is consistent.
+    /// dotted materialized path prefix, optionally into another of the user's
+    /// roots. Nodes keep their ids, created_at, sort order and archive status.
+    /// Validates the move is consistent.
     pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
         if req.src.is_empty() || req.dest.is_empty() {
             anyhow::bail!("src and dest must be non-empty");
         }
         let src = req.src.join(".");
         let dest = req.dest.join(".");
-        if src == dest {
+
+        let root_id = self.root_id(&req.root, req.user_id).await?;
+        let dest_root_id = match &req.dest_root {
+            Some(dest_root) if *dest_root != req.root => {
+                self.root_id(dest_root, req.user_id).await?
+            }
+            _ => root_id,
+        };
+        let same_root = dest_root_id == root_id;
+
+        if same_root && src == dest {
             return Ok(Response {}); // no-op
         }
         // Can't move a node into its own subtree (would orphan/cycle).
-        if dest.starts_with(&format!("{src}.")) {
+        if same_root && dest.starts_with(&format!("{src}.")) {
             anyhow::bail!("cannot move a node into its own subtree");
         }
 
-        let Root { id: root_id, .. } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
-        )
-        .bind(&req.root)
-        .bind(req.user_id)
-        .fetch_one(&self.db)
-        .await?;
-
         // src must exist (active).
         let Count { count: src_count } = sqlx::query_as(
             r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path = $2 AND status = 'active'"#,
@@ -64,13 +87,15 @@ impl MoveNode {
             anyhow::bail!("source not found: {src}");
         }
 
-        // dest must be free.
-        let Count { count: dest_count } =
-            sqlx::query_as(r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path = $2"#)
-                .bind(root_id)
-                .bind(&dest)
-                .fetch_one(&self.db)
-                .await?;
+        // dest must be free, and so must everything below it: archived
+        // descendants come along and would collide with stray rows there.
+        let Count { count: dest_count } = sqlx::query_as(
+            r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND (path = $2 OR path LIKE $2 || '.%')"#,
+        )
+        .bind(dest_root_id)
+        .bind(&dest)
+        .fetch_one(&self.db)
+        .await?;
         if dest_count > 0 {
             anyhow::bail!("destination already exists: {dest}");
         }
@@ -81,7 +106,7 @@ impl MoveNode {
             let Count { count: parent_count } = sqlx::query_as(
                 r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path = $2 AND item_type = 'SECTION' AND status = 'active'"#,
             )
-            .bind(root_id)
+            .bind(dest_root_id)
             .bind(&parent)
             .fetch_one(&self.db)
             .await?;
@@ -90,20 +115,40 @@ impl MoveNode {
             }
         }
 
-        // Rewrite the path prefix for the node + every descendant.
+        let mut tx = self.db.begin().await?;
+
+        // Rewrite the root and path prefix for the node + every descendant.
         sqlx::query(
             r#"
             UPDATE nodes
-            SET path = $3 || substring(path from char_length($2) + 1)
+            SET root_id = $4, path = $3 || substring(path from char_length($2) + 1)
             WHERE root_id = $1 AND (path = $2 OR path LIKE $2 || '.%')
             "#,
         )
         .bind(root_id)
         .bind(&src)
         .bind(&dest)
-        .execute(&self.db)
+        .bind(dest_root_id)
+        .execute(&mut *tx)
         .await?;
 
+        // Across roots only the edges within the moved subtree still make
+        // sense, the ones crossing it are dropped on both sides.
+        if same_root {
+            retarget_dependencies(&mut tx, root_id, &src, &dest).await?;
+        } else {
+            rewrite_dependencies(&mut tx, dest_root_id, Some(&dest), |depends_on| {
+                deps::carry(depends_on, &src, &dest)
+            })
+            .await?;
+            rewrite_dependencies(&mut tx, root_id, None, |depends_on| {
+                deps::detach(depends_on, &src)
+            })
+            .await?;
+        }
+
+        tx.commit().await?;
+
         Ok(Response {})
     }
 }
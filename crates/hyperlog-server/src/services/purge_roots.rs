@@ -0,0 +1,85 @@
+//! Hard deletes roots whose soft delete is older than the grace period.
+//!
+//! Config via env:
+//!   HYPERLOG_ROOT_PURGE_AFTER_DAYS (default 30)
+
+use std::time::Duration;
+
+use crate::state::SharedState;
+
+const DEFAULT_GRACE_DAYS: i64 = 30;
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone)]
+pub struct PurgeRoots {
+    db: sqlx::PgPool,
+    grace: time::Duration,
+}
+
+pub struct Request {}
+pub struct Response {
+    pub purged: u64,
+}
+
+impl PurgeRoots {
+    pub fn new(db: sqlx::PgPool, grace: time::Duration) -> Self {
+        Self { db, grace }
+    }
+
+    pub fn grace_from_env() -> time::Duration {
+        let days = match std::env::var("HYPERLOG_ROOT_PURGE_AFTER_DAYS") {
+            Ok(v) => v.parse().unwrap_or_else(|_| {
+                tracing::warn!(
+                    "HYPERLOG_ROOT_PURGE_AFTER_DAYS={v} is not a number, using {DEFAULT_GRACE_DAYS}"
+                );
+                DEFAULT_GRACE_DAYS
+            }),
+            Err(_) => DEFAULT_GRACE_DAYS,
+        };
+        time::Duration::days(days)
+    }
+
+    pub async fn execute(&self, _req: Request) -> anyhow::Result<Response> {
+        let cutoff = time::OffsetDateTime::now_utc() - self.grace;
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            r#"DELETE FROM nodes WHERE root_id IN (SELECT id FROM roots WHERE deleted_at < $1)"#,
+        )
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?;
+        let res = sqlx::query(r#"DELETE FROM roots WHERE deleted_at < $1"#)
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(Response {
+            purged: res.rows_affected(),
+        })
+    }
+
+    /// Purge on an interval for as long as the server runs.
+    pub async fn run(&self) -> anyhow::Result<()> {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match self.execute(Request {}).await {
+                Ok(Response { purged: 0 }) => {}
+                Ok(Response { purged }) => tracing::info!("purged {purged} deleted roots"),
+                Err(e) => tracing::warn!("failed to purge deleted roots: {e:#}"),
+            }
+        }
+    }
+}
+
+pub trait PurgeRootsExt {
+    fn purge_roots_service(&self) -> PurgeRoots;
+}
+
+impl PurgeRootsExt for SharedState {
+    fn purge_roots_service(&self) -> PurgeRoots {
+        PurgeRoots::new(self.db.clone(), PurgeRoots::grace_from_env())
+    }
+}
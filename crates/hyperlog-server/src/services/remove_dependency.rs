@@ -0,0 +1,74 @@
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct RemoveDependency {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub path: Vec<String>,
+    pub depends_on: Vec<String>,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+impl RemoveDependency {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {}", req.root))?;
+
+        let res = sqlx::query(
+            r#"
+UPDATE
+    nodes
+SET
+    item_content = jsonb_set(
+        item_content,
+        '{depends_on}',
+        COALESCE(item_content->'depends_on', '[]'::JSONB) - $1::TEXT
+    )
+WHERE
+    root_id = $2
+    AND path = $3
+    AND item_type = 'ITEM'
+            "#,
+        )
+        .bind(req.depends_on.join("."))
+        .bind(root_id)
+        .bind(req.path.join("."))
+        .execute(&self.db)
+        .await?;
+
+        if res.rows_affected() != 1 {
+            anyhow::bail!("item not found: {}", req.path.join("."));
+        }
+
+        Ok(Response {})
+    }
+}
+
+pub trait RemoveDependencyExt {
+    fn remove_dependency_service(&self) -> RemoveDependency;
+}
+
+impl RemoveDependencyExt for SharedState {
+    fn remove_dependency_service(&self) -> RemoveDependency {
+        RemoveDependency::new(self.db.clone())
+    }
+}
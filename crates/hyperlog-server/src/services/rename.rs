@@ -0,0 +1,177 @@
+use hyperlog_core::wiki_links::LinkResolver;
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+use super::{add_dependency::retarget_dependencies, backlinks::display_name};
+
+#[derive(Clone)]
+pub struct Rename {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub path: Vec<String>,
+    pub new_key: String,
+    /// Also rewrite `[[wiki-links]]` that point at the node or below it.
+    pub update_links: bool,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+#[derive(sqlx::FromRow)]
+struct Count {
+    count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct NodeRow {
+    id: uuid::Uuid,
+    path: String,
+    item_type: String,
+    item_content: Option<Json<serde_json::Value>>,
+}
+
+impl Rename {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Change the key of the node at `path` within its parent, rewriting the
+    /// paths of its whole subtree (archived nodes included).
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let Some((old_key, parent)) = req.path.split_last() else {
+            anyhow::bail!("path must be non-empty");
+        };
+        if req.new_key.is_empty() {
+            anyhow::bail!("new key must be non-empty");
+        }
+        if req.new_key.contains('.') {
+            anyhow::bail!("new key cannot contain '.': {}", req.new_key);
+        }
+        if *old_key == req.new_key {
+            return Ok(Response {}); // no-op
+        }
+        let src = req.path.join(".");
+        let dest = parent
+            .iter()
+            .chain(std::iter::once(&req.new_key))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(".");
+
+        // The checks run in the transaction so a concurrent rename or insert
+        // cannot slip in between them and the update.
+        let mut tx = self.db.begin().await?;
+
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let Count { count: src_count } = sqlx::query_as(
+            r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path = $2 AND status = 'active'"#,
+        )
+        .bind(root_id)
+        .bind(&src)
+        .fetch_one(&mut *tx)
+        .await?;
+        if src_count == 0 {
+            anyhow::bail!("node not found: {src}");
+        }
+
+        // Archived siblings hold their key too, restoring them must not collide.
+        let Count { count: dest_count } = sqlx::query_as(
+            r#"SELECT count(*) as count FROM nodes WHERE root_id = $1 AND path = $2"#,
+        )
+        .bind(root_id)
+        .bind(&dest)
+        .fetch_one(&mut *tx)
+        .await?;
+        if dest_count > 0 {
+            anyhow::bail!("{} already exists in its parent", req.new_key);
+        }
+
+        if req.update_links {
+            // Resolve against the tree as it was, before the rename.
+            let rows: Vec<NodeRow> = sqlx::query_as(
+                r#"SELECT id, path, item_type, item_content FROM nodes
+                   WHERE root_id = $1 AND status = 'active' ORDER BY path"#,
+            )
+            .bind(root_id)
+            .fetch_all(&mut *tx)
+            .await?;
+            let resolver = LinkResolver::new(rows.iter().map(|r| {
+                let key = r.path.rsplit('.').next().unwrap_or(&r.path);
+                (
+                    r.path.clone(),
+                    display_name(&r.item_type, key, &r.item_content),
+                )
+            }));
+
+            for row in rows.iter().filter(|r| r.item_type == "ITEM") {
+                let Some(Json(mut content)) = row.item_content.clone() else {
+                    continue;
+                };
+                let Some(body) = content
+                    .get("description")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                else {
+                    continue;
+                };
+                if !body.contains("[[") {
+                    continue;
+                }
+                let rewritten = resolver.retarget(&body, &src, &dest);
+                if rewritten == body {
+                    continue;
+                }
+                content["description"] = serde_json::Value::String(rewritten);
+                sqlx::query(r#"UPDATE nodes SET item_content = $1 WHERE id = $2"#)
+                    .bind(Json(content))
+                    .bind(row.id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE nodes
+            SET path = $3 || substring(path from char_length($2) + 1)
+            WHERE root_id = $1 AND (path = $2 OR path LIKE $2 || '.%')
+            "#,
+        )
+        .bind(root_id)
+        .bind(&src)
+        .bind(&dest)
+        .execute(&mut *tx)
+        .await?;
+
+        retarget_dependencies(&mut tx, root_id, &src, &dest).await?;
+
+        tx.commit().await?;
+
+        Ok(Response {})
+    }
+}
+
+pub trait RenameExt {
+    fn rename_service(&self) -> Rename;
+}
+
+impl RenameExt for SharedState {
+    fn rename_service(&self) -> Rename {
+        Rename::new(self.db.clone())
+    }
+}
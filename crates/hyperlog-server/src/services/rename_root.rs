@@ -0,0 +1,56 @@
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct RenameRoot {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub new_name: String,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+impl RenameRoot {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let (taken,): (bool,) = sqlx::query_as(
+            r#"SELECT EXISTS (SELECT 1 FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL)"#,
+        )
+        .bind(&req.new_name)
+        .bind(req.user_id)
+        .fetch_one(&self.db)
+        .await?;
+        if taken {
+            anyhow::bail!("root {} already exists", req.new_name);
+        }
+
+        let res = sqlx::query(
+            r#"UPDATE roots SET root_name = $1 WHERE root_name = $2 AND user_id IS NOT DISTINCT FROM $3 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.new_name)
+        .bind(&req.root)
+        .bind(req.user_id)
+        .execute(&self.db)
+        .await?;
+        if res.rows_affected() == 0 {
+            anyhow::bail!("root {} was not found", req.root);
+        }
+
+        Ok(Response {})
+    }
+}
+
+pub trait RenameRootExt {
+    fn rename_root_service(&self) -> RenameRoot;
+}
+
+impl RenameRootExt for SharedState {
+    fn rename_root_service(&self) -> RenameRoot {
+        RenameRoot::new(self.db.clone())
+    }
+}
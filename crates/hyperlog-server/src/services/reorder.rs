@@ -26,7 +26,7 @@ impl Reorder {
     /// Assign sort_order = 0,1,2,… to the given child keys of `path`.
     pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
         let Root { id: root_id, .. } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
         )
         .bind(&req.root)
         .bind(req.user_id)
@@ -0,0 +1,67 @@
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct RestoreRoot {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+impl RestoreRoot {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Undo a delete that is still within its grace period. If the name was
+    /// deleted more than once, the most recent one comes back.
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT id FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NOT NULL ORDER BY deleted_at DESC LIMIT 1"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no deleted root named {}", req.root))?;
+
+        let (taken,): (bool,) = sqlx::query_as(
+            r#"SELECT EXISTS (SELECT 1 FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL)"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_one(&self.db)
+        .await?;
+        if taken {
+            anyhow::bail!(
+                "root {} already exists, rename it before restoring",
+                req.root
+            );
+        }
+
+        sqlx::query(r#"UPDATE roots SET deleted_at = NULL WHERE id = $1"#)
+            .bind(root_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(Response {})
+    }
+}
+
+pub trait RestoreRootExt {
+    fn restore_root_service(&self) -> RestoreRoot;
+}
+
+impl RestoreRootExt for SharedState {
+    fn restore_root_service(&self) -> RestoreRoot {
+        RestoreRoot::new(self.db.clone())
+    }
+}
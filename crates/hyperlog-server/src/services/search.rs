@@ -0,0 +1,126 @@
+//! Active items matching a text and/or a tag, across the user's roots or in
+//! one of them. SQL only narrows the candidates down, it can't see inline
+//! `#tag` boundaries; `hyperlog_core::search` makes the final call.
+
+use futures::TryStreamExt;
+use hyperlog_core::{
+    search::{Search as SearchQuery, SearchHit, MAX_HITS},
+    tags,
+};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct Search {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    /// Only this root; all of the user's roots when `None`.
+    pub root: Option<String>,
+    pub search: SearchQuery,
+    pub user_id: Option<uuid::Uuid>,
+}
+
+pub struct Response {
+    pub items: Vec<SearchHit>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ItemRow {
+    root_name: String,
+    path: String,
+    item_content: Option<Json<serde_json::Value>>,
+}
+
+impl Search {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let text = req.search.text.trim();
+        let text_pattern = (!text.is_empty()).then(|| format!("%{}%", like_literal(text)));
+        let tag = req.search.tag.as_deref().and_then(tags::normalize);
+        let inline_tag_pattern = tag
+            .as_deref()
+            .map(|tag| format!("%#{}%", like_literal(tag)));
+
+        let mut rows = sqlx::query_as::<_, ItemRow>(
+            r#"
+            SELECT r.root_name, n.path, n.item_content
+            FROM nodes n
+            JOIN roots r ON r.id = n.root_id
+            WHERE r.user_id IS NOT DISTINCT FROM $1
+              AND r.deleted_at IS NULL
+              AND ($2::TEXT IS NULL OR r.root_name = $2)
+              AND n.item_type = 'ITEM'
+              AND n.status = 'active'
+              AND ($3::TEXT IS NULL
+                OR n.item_content->>'title' ILIKE $3
+                OR n.item_content->>'description' ILIKE $3)
+              AND ($4::TEXT IS NULL
+                OR n.item_content->'tags' ? $4
+                OR n.item_content->>'description' ILIKE $5)
+            ORDER BY r.root_name, n.path
+            "#,
+        )
+        .bind(req.user_id)
+        .bind(&req.root)
+        .bind(&text_pattern)
+        .bind(&tag)
+        .bind(&inline_tag_pattern)
+        .fetch(&self.db);
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            if items.len() == MAX_HITS {
+                break;
+            }
+            let Some(Json(content)) = row.item_content else {
+                continue;
+            };
+            let text = |field: &str| {
+                content
+                    .get(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+            };
+            let set = content
+                .get("tags")
+                .and_then(|t| serde_json::from_value::<Vec<String>>(t.clone()).ok())
+                .unwrap_or_default();
+            if !req.search.matches(text("title"), text("description"), &set) {
+                continue;
+            }
+
+            items.push(SearchHit {
+                root: row.root_name,
+                path: row.path.split('.').map(str::to_string).collect(),
+                title: text("title").to_string(),
+                done: text("state") == "done",
+                tags: tags::item_tags(&set, text("description")),
+            });
+        }
+
+        Ok(Response { items })
+    }
+}
+
+/// `text` matched literally inside a LIKE pattern.
+pub(crate) fn like_literal(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub trait SearchExt {
+    fn search_service(&self) -> Search;
+}
+
+impl SearchExt for SharedState {
+    fn search_service(&self) -> Search {
+        Search::new(self.db.clone())
+    }
+}
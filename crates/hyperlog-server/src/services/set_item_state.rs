@@ -0,0 +1,115 @@
+use hyperlog_core::{completed, due, log::ItemState, recur};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+use super::toggle_item::Node;
+
+#[derive(Clone)]
+pub struct SetItemState {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub path: Vec<String>,
+    pub state: ItemState,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+impl SetItemState {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {}", req.root))?;
+
+        // Locked until the update commits, like toggle_item.
+        let mut tx = self.db.begin().await?;
+        let Node {
+            id: node_id,
+            mut item_content,
+        } = sqlx::query_as(
+            r#"
+SELECT
+    *
+FROM
+    nodes
+WHERE
+    root_id = $1
+    AND path = $2
+    AND item_type = 'ITEM'
+FOR UPDATE
+            "#,
+        )
+        .bind(root_id)
+        .bind(req.path.join("."))
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("item not found: {}", req.path.join(".")))?;
+
+        if let Some(Json(ref mut content)) = item_content {
+            match &mut content.recur {
+                // Done on a recurring item completes the occurrence, as toggle_item does.
+                Some(rule) if req.state == ItemState::Done && !content.state.is_closed() => {
+                    recur::complete(
+                        rule,
+                        &mut content.due,
+                        &mut content.completions,
+                        due::today(),
+                    )?
+                }
+                _ => {
+                    content.state = req.state;
+                    completed::track(&content.state, &mut content.completed_at, completed::now);
+                }
+            }
+        }
+
+        let res = sqlx::query(
+            r#"
+UPDATE
+    nodes
+SET
+    item_content = $1
+WHERE
+    id = $2
+            "#,
+        )
+        .bind(item_content)
+        .bind(node_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if res.rows_affected() != 1 {
+            anyhow::bail!("failed to update item");
+        }
+        tx.commit().await?;
+
+        Ok(Response {})
+    }
+}
+
+pub trait SetItemStateExt {
+    fn set_item_state_service(&self) -> SetItemState;
+}
+
+impl SetItemStateExt for SharedState {
+    fn set_item_state_service(&self) -> SetItemState {
+        SetItemState::new(self.db.clone())
+    }
+}
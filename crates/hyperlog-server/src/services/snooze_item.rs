@@ -0,0 +1,78 @@
+use hyperlog_core::validate;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct SnoozeItem {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub path: Vec<String>,
+    /// `YYYY-MM-DD` to defer the item until; `None` clears it.
+    pub start: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+impl SnoozeItem {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        validate::item(&req.path, None, req.start.as_deref(), None, &[])?;
+
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {}", req.root))?;
+
+        let res = sqlx::query(
+            r#"
+UPDATE
+    nodes
+SET
+    item_content = CASE
+        WHEN $1::TEXT IS NULL THEN item_content - 'start'
+        ELSE jsonb_set(item_content, '{start}', to_jsonb($1::TEXT))
+    END
+WHERE
+    root_id = $2
+    AND path = $3
+    AND item_type = 'ITEM'
+            "#,
+        )
+        .bind(&req.start)
+        .bind(root_id)
+        .bind(req.path.join("."))
+        .execute(&self.db)
+        .await?;
+
+        if res.rows_affected() != 1 {
+            anyhow::bail!("item not found: {}", req.path.join("."));
+        }
+
+        Ok(Response {})
+    }
+}
+
+pub trait SnoozeItemExt {
+    fn snooze_item_service(&self) -> SnoozeItem;
+}
+
+impl SnoozeItemExt for SharedState {
+    fn snooze_item_service(&self) -> SnoozeItem {
+        SnoozeItem::new(self.db.clone())
+    }
+}
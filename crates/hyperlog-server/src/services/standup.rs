@@ -0,0 +1,145 @@
+//! The daily standup across the user's roots or in one of them, filed by
+//! `hyperlog_core::standup`. Unlike the local backend, nodes carry a creation
+//! time here, so items added since the last working day are listed as well.
+//! Archived items are only listed as done.
+
+use std::collections::BTreeMap;
+
+use hyperlog_core::{
+    deps::Dependencies,
+    due,
+    log::ItemState,
+    standup::{Standup as StandupReport, StandupEntry},
+};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct Standup {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    /// Only this root; all of the user's roots when `None`.
+    pub root: Option<String>,
+    /// `YYYY-MM-DD` in the caller's timezone; the server's UTC date when `None`.
+    pub today: Option<String>,
+    pub user_id: Option<uuid::Uuid>,
+}
+
+pub struct Response {
+    pub standup: StandupReport,
+}
+
+#[derive(sqlx::FromRow)]
+struct ItemRow {
+    root_name: String,
+    path: String,
+    item_content: Option<Json<serde_json::Value>>,
+    created_unix: i64,
+    archived: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct ItemContent {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    state: ItemState,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    depends_on: Vec<String>,
+    #[serde(default)]
+    completed_at: Option<String>,
+    #[serde(default)]
+    completions: Vec<String>,
+}
+
+impl Standup {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        let today = match &req.today {
+            Some(today) => due::parse_date(today)
+                .ok_or_else(|| anyhow::anyhow!("expected today as YYYY-MM-DD, got: {today}"))?,
+            None => due::today(),
+        };
+
+        let rows: Vec<ItemRow> = sqlx::query_as(
+            r#"
+            SELECT
+                r.root_name,
+                n.path,
+                n.item_content,
+                COALESCE(extract(epoch from n.created_at)::bigint, 0) AS created_unix,
+                n.status = 'archive' AS archived
+            FROM nodes n
+            JOIN roots r ON r.id = n.root_id
+            WHERE r.user_id IS NOT DISTINCT FROM $1
+              AND r.deleted_at IS NULL
+              AND ($2::TEXT IS NULL OR r.root_name = $2)
+              AND n.item_type = 'ITEM'
+            "#,
+        )
+        .bind(req.user_id)
+        .bind(&req.root)
+        .fetch_all(&self.db)
+        .await?;
+
+        let items = rows
+            .into_iter()
+            .filter_map(|mut row| {
+                let Json(content) = row.item_content.take()?;
+                let content = serde_json::from_value::<ItemContent>(content).ok()?;
+                Some((row, content))
+            })
+            .collect::<Vec<_>>();
+
+        // Dependencies only point within a root, and never at archived items.
+        let mut deps = BTreeMap::<&str, Dependencies>::new();
+        for (row, content) in &items {
+            let deps = deps.entry(&row.root_name).or_default();
+            if row.archived {
+                continue;
+            }
+            deps.insert(
+                row.path.clone(),
+                content.state.is_closed(),
+                content.depends_on.clone(),
+            );
+        }
+
+        let mut standup = StandupReport::new(today);
+        for (row, content) in &items {
+            standup.push(StandupEntry {
+                root: &row.root_name,
+                path: &row.path.split('.').map(str::to_string).collect::<Vec<_>>(),
+                title: &content.title,
+                state: &content.state,
+                due: content.due.as_deref(),
+                waiting_on: deps[row.root_name.as_str()].open_dependencies(&row.path),
+                created_unix: Some(row.created_unix).filter(|c| *c > 0),
+                completed_at: content.completed_at.as_deref(),
+                completions: &content.completions,
+                archived: row.archived,
+            });
+        }
+        standup.sort();
+
+        Ok(Response { standup })
+    }
+}
+
+pub trait StandupExt {
+    fn standup_service(&self) -> Standup;
+}
+
+impl StandupExt for SharedState {
+    fn standup_service(&self) -> Standup {
+        Standup::new(self.db.clone())
+    }
+}
@@ -1,4 +1,9 @@
-use hyperlog_core::log::{ItemState, Link};
+use hyperlog_core::{
+    checklist::{self, ChecklistEntry},
+    completed, due,
+    log::ItemState,
+    recur,
+};
 use sqlx::types::Json;
 
 use crate::state::SharedState;
@@ -11,20 +16,30 @@ pub struct ToggleItem {
 pub struct Request {
     pub root: String,
     pub path: Vec<String>,
+    /// A checklist entry, counted from 0, to toggle instead of the item.
+    pub checklist_entry: Option<usize>,
     pub user_id: Option<uuid::Uuid>,
 }
 pub struct Response {}
 
+/// The stored content of an item, as far as changing its state touches it.
+/// Shared with set_item_state.
 #[derive(serde::Serialize, serde::Deserialize)]
-struct ItemContent {
-    pub title: String,
-    pub description: String,
+pub(crate) struct ItemContent {
     pub state: ItemState,
-    // Preserved across a toggle (don't drop PM metadata when flipping done).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub due: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recur: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub completions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub links: Vec<Link>,
+    pub checklist: Vec<ChecklistEntry>,
+    // Preserved across a toggle (don't drop PM metadata when flipping done).
+    #[serde(flatten)]
+    pub rest: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(sqlx::FromRow)]
@@ -33,9 +48,9 @@ struct Root {
 }
 
 #[derive(sqlx::FromRow)]
-struct Node {
-    id: uuid::Uuid,
-    item_content: Option<Json<ItemContent>>,
+pub(crate) struct Node {
+    pub id: uuid::Uuid,
+    pub item_content: Option<Json<ItemContent>>,
 }
 
 impl ToggleItem {
@@ -45,12 +60,15 @@ impl ToggleItem {
 
     pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
         let Root { id: root_id, .. } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
         )
         .bind(req.root)
         .bind(req.user_id)
         .fetch_one(&self.db)
         .await?;
+        // The row stays locked until the update below commits, so a concurrent
+        // toggle or state change can't be lost in between.
+        let mut tx = self.db.begin().await?;
         let Node {
             id: node_id,
             mut item_content,
@@ -64,18 +82,33 @@ WHERE
     root_id = $1
     AND path = $2
     AND item_type = $3
+FOR UPDATE
             "#,
         )
         .bind(root_id)
         .bind(req.path.join("."))
         .bind("ITEM")
-        .fetch_one(&self.db)
+        .fetch_one(&mut *tx)
         .await?;
 
-        if let Some(ref mut content) = item_content {
-            content.state = match content.state {
-                ItemState::NotDone => ItemState::Done,
-                ItemState::Done => ItemState::NotDone,
+        if let Some(Json(ref mut content)) = item_content {
+            match (req.checklist_entry, &mut content.recur) {
+                (Some(entry), _) => {
+                    if !checklist::toggle(&mut content.checklist, entry) {
+                        anyhow::bail!("item has no checklist entry {}", entry + 1);
+                    }
+                }
+                // Completing a recurring item moves it to its next occurrence instead.
+                (None, Some(rule)) if !content.state.is_closed() => recur::complete(
+                    rule,
+                    &mut content.due,
+                    &mut content.completions,
+                    due::today(),
+                )?,
+                (None, _) => {
+                    content.state = content.state.toggled();
+                    completed::track(&content.state, &mut content.completed_at, completed::now);
+                }
             }
         }
 
@@ -91,12 +124,13 @@ WHERE
         )
         .bind(item_content)
         .bind(node_id)
-        .execute(&self.db)
+        .execute(&mut *tx)
         .await?;
 
         if res.rows_affected() != 1 {
             anyhow::bail!("failed to update item");
         }
+        tx.commit().await?;
 
         Ok(Response {})
     }
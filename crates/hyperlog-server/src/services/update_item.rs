@@ -1,8 +1,15 @@
-use hyperlog_core::log::{ItemState, Link};
+use hyperlog_core::{
+    checklist::ChecklistEntry,
+    completed,
+    log::{ItemState, Link, Priority},
+    tags, validate,
+};
 use sqlx::types::Json;
 
 use crate::state::SharedState;
 
+use super::add_dependency::retarget_dependencies;
+
 #[derive(Clone)]
 pub struct UpdateItem {
     db: sqlx::PgPool,
@@ -17,7 +24,12 @@ pub struct Request {
     pub description: String,
     pub state: ItemState,
     pub due: Option<String>,
+    pub start: Option<String>,
+    pub recur: Option<String>,
     pub links: Vec<Link>,
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    pub checklist: Vec<ChecklistEntry>,
 }
 pub struct Response {}
 
@@ -28,8 +40,18 @@ struct ItemContent {
     pub state: ItemState,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub due: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recur: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub links: Vec<Link>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistEntry>,
 }
 
 #[derive(sqlx::FromRow)]
@@ -43,8 +65,17 @@ impl UpdateItem {
     }
 
     pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        validate::item(
+            &req.path,
+            req.due.as_deref(),
+            req.start.as_deref(),
+            req.recur.as_deref(),
+            &req.links,
+        )?;
+        validate::checklist(&req.checklist)?;
+
         let Root { id: root_id, .. } = sqlx::query_as(
-            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2"#,
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
         )
         .bind(req.root)
         .bind(req.user_id)
@@ -76,12 +107,22 @@ WHERE
         let mut rest = rest.to_vec();
         rest.push(req.title.replace(".", "-"));
 
+        let done_at = matches!(req.state, ItemState::Done).then(completed::now);
+
+        let mut tx = self.db.begin().await?;
+
+        // Completions of a recurring item, when it was marked done and its
+        // dependencies are kept by the server, not part of what a client edits.
         let res = sqlx::query(
             r#"
 UPDATE 
     nodes
 SET 
-    item_content = $1,
+    item_content = $1::JSONB || jsonb_strip_nulls(jsonb_build_object(
+        'completions', item_content->'completions',
+        'depends_on', item_content->'depends_on',
+        'completed_at', CASE WHEN $4::TEXT IS NOT NULL THEN COALESCE(item_content->'completed_at', to_jsonb($4::TEXT)) END
+    )),
     path = $2
 WHERE 
     id = $3
@@ -92,17 +133,31 @@ WHERE
             description: req.description,
             state: req.state,
             due: req.due,
+            start: req.start,
+            recur: req.recur,
             links: req.links,
+            tags: tags::normalize_all(&req.tags),
+            priority: req.priority,
+            checklist: req.checklist,
         }))
         .bind(rest.join("."))
         .bind(node_id)
-        .execute(&self.db)
+        .bind(done_at)
+        .execute(&mut *tx)
         .await?;
 
         if res.rows_affected() != 1 {
             anyhow::bail!("failed to update item");
         }
 
+        // The key follows the title, so the item may have moved.
+        let (src, dest) = (req.path.join("."), rest.join("."));
+        if src != dest {
+            retarget_dependencies(&mut tx, root_id, &src, &dest).await?;
+        }
+
+        tx.commit().await?;
+
         Ok(Response {})
     }
 }
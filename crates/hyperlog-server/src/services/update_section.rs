@@ -0,0 +1,82 @@
+//! Replacing the metadata of a section, as the engine does for the local
+//! backend. The metadata is kept as the section's `item_content`.
+
+use hyperlog_core::{log::SectionMeta, validate};
+use sqlx::types::Json;
+
+use crate::state::SharedState;
+
+#[derive(Clone)]
+pub struct UpdateSection {
+    db: sqlx::PgPool,
+}
+
+pub struct Request {
+    pub root: String,
+    pub path: Vec<String>,
+    /// Replaces what the section had.
+    pub meta: SectionMeta,
+    pub user_id: Option<uuid::Uuid>,
+}
+pub struct Response {}
+
+#[derive(sqlx::FromRow)]
+struct Root {
+    id: uuid::Uuid,
+}
+
+impl UpdateSection {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn execute(&self, req: Request) -> anyhow::Result<Response> {
+        validate::section(&req.meta)?;
+
+        let Root { id: root_id } = sqlx::query_as(
+            r#"SELECT * FROM roots WHERE root_name = $1 AND user_id IS NOT DISTINCT FROM $2 AND deleted_at IS NULL"#,
+        )
+        .bind(&req.root)
+        .bind(req.user_id)
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("root not found: {}", req.root))?;
+
+        // Sections without metadata keep no content, like freshly created ones.
+        let content = (!req.meta.is_empty()).then_some(Json(req.meta));
+        let res = sqlx::query(
+            r#"
+UPDATE
+    nodes
+SET
+    item_content = $1
+WHERE
+    root_id = $2
+    AND path = $3
+    AND item_type = 'SECTION'
+    AND status = 'active'
+            "#,
+        )
+        .bind(content)
+        .bind(root_id)
+        .bind(req.path.join("."))
+        .execute(&self.db)
+        .await?;
+
+        if res.rows_affected() != 1 {
+            anyhow::bail!("section not found: {}", req.path.join("."));
+        }
+
+        Ok(Response {})
+    }
+}
+
+pub trait UpdateSectionExt {
+    fn update_section_service(&self) -> UpdateSection;
+}
+
+impl UpdateSectionExt for SharedState {
+    fn update_section_service(&self) -> UpdateSection {
+        UpdateSection::new(self.db.clone())
+    }
+}
@@ -3,6 +3,8 @@ use std::{ops::Deref, sync::Arc};
 use anyhow::Context;
 use sqlx::{Pool, Postgres};
 
+use crate::auth::KeySet;
+
 #[derive(Clone)]
 pub struct SharedState(pub Arc<State>);
 
@@ -16,10 +18,13 @@ impl Deref for SharedState {
 
 pub struct State {
     pub db: Pool<Postgres>,
+    pub jwt_keys: Arc<KeySet>,
 }
 
 impl State {
     pub async fn new() -> anyhow::Result<Self> {
+        let jwt_keys = Arc::new(KeySet::from_env()?);
+
         let db = sqlx::PgPool::connect(
             &std::env::var("DATABASE_URL").context("DATABASE_URL is not set")?,
         )
@@ -32,6 +37,6 @@ impl State {
 
         let _ = sqlx::query("SELECT 1;").fetch_one(&db).await?;
 
-        Ok(Self { db })
+        Ok(Self { db, jwt_keys })
     }
 }
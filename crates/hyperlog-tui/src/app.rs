@@ -18,6 +18,7 @@ use crate::{
 use self::{
     command_bar::{CommandBar, CommandBarState},
     dialog::{
+        agenda::{Agenda, AgendaState},
         create_item::{CreateItem, CreateItemState},
         edit_item::{EditItem, EditItemState},
     },
@@ -28,7 +29,8 @@ pub mod dialog;
 
 pub enum Dialog {
     CreateItem { state: CreateItemState },
-    EditItem { state: EditItemState },
+    EditItem { state: Box<EditItemState> },
+    Agenda { state: AgendaState },
 }
 
 impl Dialog {
@@ -36,6 +38,7 @@ impl Dialog {
         match self {
             Dialog::CreateItem { state } => state.get_command().map(|c| c.into_command()),
             Dialog::EditItem { state } => state.get_command().map(|c| c.into_command()),
+            Dialog::Agenda { .. } => None,
         }
     }
 }
@@ -95,7 +98,15 @@ impl<'a> App<'a> {
             | Msg::ItemUpdated(IOEvent::Success(()))
             | Msg::SectionCreated(IOEvent::Success(()))
             | Msg::ItemToggled(IOEvent::Success(()))
-            | Msg::Archive(IOEvent::Success(())) => {
+            | Msg::ItemStateSet(IOEvent::Success(()))
+            | Msg::ItemSnoozed(IOEvent::Success(()))
+            | Msg::Converted(IOEvent::Success(()))
+            | Msg::SectionUpdated(IOEvent::Success(()))
+            | Msg::DependenciesChanged(IOEvent::Success(()))
+            | Msg::Archive(IOEvent::Success(()))
+            | Msg::Renamed(IOEvent::Success(()))
+            | Msg::Copied(IOEvent::Success(()))
+            | Msg::TemplateApplied(IOEvent::Success(())) => {
                 batch.with(self.graph_explorer.new_update_graph());
             }
             Msg::MoveRight => self.graph_explorer.move_right()?,
@@ -110,6 +121,7 @@ impl<'a> App<'a> {
                 }
             }
             Msg::OpenEditItemDialog { item } => self.open_edit_item_dialog(item),
+            Msg::OpenAgenda(IOEvent::Success(agenda)) => self.open_agenda(agenda.clone()),
             Msg::EnterInsertMode => self.mode = Mode::Insert,
             Msg::EnterViewMode => self.mode = Mode::View,
             Msg::EnterCommandMode => {
@@ -123,6 +135,17 @@ impl<'a> App<'a> {
                     batch.with(cmd);
                 }
             },
+            Msg::Yank => self.graph_explorer.yank(),
+            Msg::CycleState => {
+                if let Some(cmd) = self.graph_explorer.cycle_state() {
+                    batch.with(cmd);
+                }
+            }
+            Msg::Put => {
+                if let Some(cmd) = self.graph_explorer.put(false, false) {
+                    batch.with(cmd);
+                }
+            }
             Msg::SubmitCommand { command } => {
                 tracing::info!("submitting command");
 
@@ -172,6 +195,7 @@ impl<'a> App<'a> {
             match dialog {
                 Dialog::CreateItem { state } => state.update(&msg)?,
                 Dialog::EditItem { state } => state.update(&msg)?,
+                Dialog::Agenda { state } => state.update(&msg)?,
             }
         }
 
@@ -206,13 +230,22 @@ impl<'a> App<'a> {
         }
     }
 
+    fn open_agenda(&mut self, agenda: hyperlog_core::agenda::Agenda) {
+        if self.dialog.is_none() {
+            self.focus = AppFocus::Dialog;
+            self.dialog = Some(Dialog::Agenda {
+                state: AgendaState::new(agenda),
+            });
+        }
+    }
+
     fn open_edit_item_dialog(&mut self, item: &GraphItem) {
         if self.dialog.is_none() {
             let root = self.root.clone();
             let path = self.graph_explorer.get_current_path();
 
             self.dialog = Some(Dialog::EditItem {
-                state: EditItemState::new(&self.state, root, path, item),
+                state: Box::new(EditItemState::new(&self.state, root, path, item)),
             });
             self.command = None;
             self.focus = AppFocus::Dialog;
@@ -227,12 +260,7 @@ impl<'a> App<'a> {
                 tracing::info!("editor returned without changes, skipping");
             }
             Ok(Some(item)) => {
-                if let GraphItem::Item {
-                    title,
-                    description,
-                    state,
-                } = item
-                {
+                if let GraphItem::Item { .. } = item {
                     return Some(
                         self.state.update_item_command().command(
                             &self.root,
@@ -242,9 +270,7 @@ impl<'a> App<'a> {
                                 .iter()
                                 .map(|s| s.as_str())
                                 .collect_vec(),
-                            &title,
-                            &description,
-                            state,
+                            item,
                         ),
                     );
                 }
@@ -288,6 +314,7 @@ pub fn render_app(frame: &mut Frame, state: &mut App) {
         match dialog {
             Dialog::CreateItem { .. } => heading_parts.push(Span::raw("create item")),
             Dialog::EditItem { .. } => heading_parts.push(Span::raw("edit item")),
+            Dialog::Agenda { .. } => heading_parts.push(Span::raw("agenda")),
         }
     }
 
@@ -351,7 +378,10 @@ pub fn render_app(frame: &mut Frame, state: &mut App) {
                 frame.render_stateful_widget(&mut CreateItem::default(), chunks[1], state)
             }
             Dialog::EditItem { state } => {
-                frame.render_stateful_widget(&mut EditItem::default(), chunks[1], state)
+                frame.render_stateful_widget(&mut EditItem::default(), chunks[1], state.as_mut())
+            }
+            Dialog::Agenda { state } => {
+                frame.render_stateful_widget(&mut Agenda::default(), chunks[1], state)
             }
         }
 
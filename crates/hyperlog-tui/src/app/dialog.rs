@@ -205,5 +205,6 @@ fn clamp_x(area: &Rect, x: u16) -> u16 {
     }
 }
 
+pub mod agenda;
 pub mod create_item;
 pub mod edit_item;
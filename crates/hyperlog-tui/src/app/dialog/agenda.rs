@@ -0,0 +1,61 @@
+use hyperlog_core::agenda::Agenda as AgendaBuckets;
+use ratatui::{prelude::*, widgets::*};
+
+use crate::models::Msg;
+
+pub struct AgendaState {
+    agenda: AgendaBuckets,
+    scroll: u16,
+}
+
+impl AgendaState {
+    pub fn new(agenda: AgendaBuckets) -> Self {
+        Self { agenda, scroll: 0 }
+    }
+
+    pub fn update(&mut self, msg: &Msg) -> anyhow::Result<()> {
+        match msg {
+            Msg::MoveDown => self.scroll = self.scroll.saturating_add(1),
+            Msg::MoveUp => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct Agenda {}
+
+impl StatefulWidget for &mut Agenda {
+    type State = AgendaState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mut lines = Vec::new();
+        for (heading, items) in state.agenda.buckets() {
+            let heading_style = if heading == "Overdue" && !items.is_empty() {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            lines.push(Line::from(Span::styled(
+                format!("{heading} ({})", items.len()),
+                heading_style.bold(),
+            )));
+            for item in items {
+                lines.push(Line::from(vec![
+                    Span::raw("  "),
+                    Span::raw(item.due.clone()).dark_gray(),
+                    Span::raw("  "),
+                    Span::raw(item.title.clone()),
+                    Span::raw(format!("  {}.{}", item.root, item.path.join("."))).dark_gray(),
+                ]));
+            }
+            lines.push(Line::default());
+        }
+
+        Paragraph::new(lines)
+            .scroll((state.scroll, 0))
+            .render(area, buf);
+    }
+}
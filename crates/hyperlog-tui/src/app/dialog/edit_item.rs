@@ -97,17 +97,21 @@ impl EditItemState {
 
         if !title.is_empty() {
             let path = self.path.clone();
+            let mut item = self.item.clone();
+            if let GraphItem::Item {
+                title: item_title,
+                description: item_description,
+                ..
+            } = &mut item
+            {
+                *item_title = title.trim().to_string();
+                *item_description = description.trim().to_string();
+            }
 
             Some(self.state.update_item_command().command(
                 &self.root,
                 &path.iter().map(|s| s.as_str()).collect_vec(),
-                title.trim(),
-                description.trim(),
-                match &self.item {
-                    GraphItem::User(_) => Default::default(),
-                    GraphItem::Section(_) => Default::default(),
-                    GraphItem::Item { state, .. } => state.clone(),
-                },
+                item,
             ))
 
             // Some(commander::Command::UpdateItem {
@@ -117,7 +121,7 @@ impl EditItemState {
             //     description: description.trim().into(),
             //     state: match &self.item {
             //         GraphItem::User(_) => Default::default(),
-            //         GraphItem::Section(_) => Default::default(),
+            //         GraphItem::Section { .. } => Default::default(),
             //         GraphItem::Item { state, .. } => state.clone(),
             //     },
             // })
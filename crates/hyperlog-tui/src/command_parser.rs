@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use itertools::Itertools;
 
 pub enum Commands {
@@ -5,14 +7,71 @@ pub enum Commands {
     Quit,
     WriteQuit,
     Archive,
-    CreateSection { name: String },
-    CreateItem { name: String },
-    CreateBelow { name: String },
+    Rename {
+        name: String,
+        update_links: bool,
+    },
+    Yank,
+    Put {
+        reset_state: bool,
+        drop_due: bool,
+    },
+    Template {
+        name: String,
+        key: String,
+        vars: BTreeMap<String, String>,
+        anchor: Option<String>,
+    },
+    /// Due items in the current root, or in every root with `all`.
+    Agenda {
+        all: bool,
+    },
+    /// Make the current item wait on the item at the root-relative slash-path.
+    DependOn {
+        path: Vec<String>,
+    },
+    RemoveDependency {
+        path: Vec<String>,
+    },
+    /// Defer the current item until `start`; no longer deferred when `None`.
+    Snooze {
+        start: Option<String>,
+    },
+    /// Toggle the current item's checklist entry, counted from 0.
+    Check {
+        entry: usize,
+    },
+    /// Turn the current item into a section or the current section into an
+    /// item.
+    Convert,
+    /// Change one field of the current section's metadata.
+    Section {
+        field: SectionField,
+    },
+    CreateSection {
+        name: String,
+    },
+    CreateItem {
+        name: String,
+    },
+    CreateBelow {
+        name: String,
+    },
     Edit,
     Open,
 
     ShowAll,
     HideDone,
+    /// Order siblings by key, or most urgent first with `priority`.
+    Sort {
+        priority: bool,
+    },
+    /// Only show items carrying the tag; every item again when `None`.
+    FilterTag {
+        tag: Option<String>,
+    },
+    /// Show or hide items whose start date is still ahead.
+    ToggleDeferred,
     Test,
 }
 
@@ -26,8 +85,42 @@ impl Commands {
     }
 }
 
+/// A section metadata field with its new value; text fields are cleared
+/// without one.
+pub enum SectionField {
+    Title(String),
+    Description(String),
+    Colour(String),
+    Icon(String),
+    Collapsed(bool),
+}
+
+impl SectionField {
+    pub fn apply(&self, meta: &mut hyperlog_core::log::SectionMeta) {
+        match self {
+            SectionField::Title(title) => meta.title = title.clone(),
+            SectionField::Description(description) => meta.description = description.clone(),
+            SectionField::Colour(colour) => meta.colour = colour.clone(),
+            SectionField::Icon(icon) => meta.icon = icon.clone(),
+            SectionField::Collapsed(collapsed) => meta.collapsed = *collapsed,
+        }
+    }
+}
+
 pub struct CommandParser {}
 
+/// Keys may contain spaces, so the words are rejoined before splitting on `/`.
+fn parse_path(rest: &[&str]) -> Option<Vec<String>> {
+    let path = rest
+        .join(" ")
+        .split('/')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect_vec();
+
+    (!path.is_empty()).then_some(path)
+}
+
 impl CommandParser {
     pub fn parse(raw_command: &str) -> Option<Commands> {
         let prepared = raw_command.trim();
@@ -40,6 +133,83 @@ impl CommandParser {
                 "q" | "quit" => Some(Commands::Quit),
                 "wq" | "write-quit" => Some(Commands::WriteQuit),
                 "a" | "archive" => Some(Commands::Archive),
+                "r" | "rename" => {
+                    let (update_links, name) = match rest.split_first() {
+                        Some((&"--keep-links", name)) => (false, name),
+                        _ => (true, rest),
+                    };
+                    (!name.is_empty()).then(|| Commands::Rename {
+                        name: name.join(" "),
+                        update_links,
+                    })
+                }
+                "y" | "yank" => Some(Commands::Yank),
+                "p" | "put" => Some(Commands::Put {
+                    reset_state: rest.contains(&"--reset"),
+                    drop_due: rest.contains(&"--no-due"),
+                }),
+                "t" | "template" => match rest {
+                    [name, key, args @ ..] => {
+                        let mut vars = BTreeMap::new();
+                        let mut anchor = None;
+                        let mut args = args.iter();
+                        while let Some(arg) = args.next() {
+                            if *arg == "--anchor" {
+                                anchor = Some(args.next()?.to_string());
+                            } else {
+                                let (name, value) = hyperlog_core::template::parse_var(arg).ok()?;
+                                vars.insert(name, value);
+                            }
+                        }
+                        Some(Commands::Template {
+                            name: name.to_string(),
+                            key: key.to_string(),
+                            vars,
+                            anchor,
+                        })
+                    }
+                    _ => None,
+                },
+                "agenda" => Some(Commands::Agenda {
+                    all: rest.contains(&"--all"),
+                }),
+                "dep" | "depend-on" => parse_path(rest).map(|path| Commands::DependOn { path }),
+                "undep" | "remove-dependency" => {
+                    parse_path(rest).map(|path| Commands::RemoveDependency { path })
+                }
+                "snooze" => match rest {
+                    [] => Some(Commands::Snooze { start: None }),
+                    [date] => hyperlog_core::due::parse_snooze(date, hyperlog_core::due::today())
+                        .map(|start| Commands::Snooze { start: Some(start) }),
+                    _ => None,
+                },
+                // Entries are numbered from 1 as shown in the editor.
+                "check" => match rest {
+                    [entry] => entry
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|entry| entry.checked_sub(1))
+                        .map(|entry| Commands::Check { entry }),
+                    _ => None,
+                },
+                "convert" => Some(Commands::Convert),
+                "section" => {
+                    let (field, value) = rest.split_first()?;
+                    let value = value.join(" ");
+                    let field = match *field {
+                        "title" => SectionField::Title(value),
+                        "description" => SectionField::Description(value),
+                        "colour" | "color" => SectionField::Colour(value),
+                        "icon" => SectionField::Icon(value),
+                        "collapsed" => match value.as_str() {
+                            "" | "on" => SectionField::Collapsed(true),
+                            "off" => SectionField::Collapsed(false),
+                            _ => return None,
+                        },
+                        _ => return None,
+                    };
+                    Some(Commands::Section { field })
+                }
                 "cs" | "create-section" => rest.first().map(|name| Commands::CreateSection {
                     name: name.to_string(),
                 }),
@@ -52,6 +222,15 @@ impl CommandParser {
                 "e" | "edit" => Some(Commands::Edit),
                 "show-all" => Some(Commands::ShowAll),
                 "hide-done" => Some(Commands::HideDone),
+                "sort" => match rest {
+                    [] | ["key"] => Some(Commands::Sort { priority: false }),
+                    ["priority"] => Some(Commands::Sort { priority: true }),
+                    _ => None,
+                },
+                "tag" => Some(Commands::FilterTag {
+                    tag: hyperlog_core::tags::normalize(&rest.join(" ")),
+                }),
+                "deferred" => Some(Commands::ToggleDeferred),
                 "test" => Some(Commands::Test),
                 "o" | "open" => Some(Commands::Open),
                 _ => None,
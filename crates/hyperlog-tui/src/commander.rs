@@ -1,4 +1,10 @@
-use hyperlog_core::log::ItemState;
+use std::collections::BTreeMap;
+
+use hyperlog_core::{
+    checklist::ChecklistEntry,
+    convert::Kind,
+    log::{ItemState, Priority, SectionMeta},
+};
 use serde::Serialize;
 use tonic::transport::Channel;
 
@@ -12,6 +18,16 @@ pub enum Command {
     CreateRoot {
         root: String,
     },
+    RenameRoot {
+        root: String,
+        new_name: String,
+    },
+    DeleteRoot {
+        root: String,
+    },
+    RestoreRoot {
+        root: String,
+    },
     CreateSection {
         root: String,
         path: Vec<String>,
@@ -29,15 +45,81 @@ pub enum Command {
         title: String,
         description: String,
         state: ItemState,
+        due: Option<String>,
+        start: Option<String>,
+        recur: Option<String>,
+        tags: Vec<String>,
+        priority: Option<Priority>,
+        checklist: Vec<ChecklistEntry>,
     },
+    /// Toggle the item, or the checklist entry counted from 0 instead.
     ToggleItem {
         root: String,
         path: Vec<String>,
+        checklist_entry: Option<usize>,
+    },
+    SetItemState {
+        root: String,
+        path: Vec<String>,
+        state: ItemState,
     },
+    /// Move `src` into the section at `dest`, optionally in another root.
     Move {
         root: String,
         src: Vec<String>,
+        dest_root: Option<String>,
+        dest: Vec<String>,
+    },
+    Rename {
+        root: String,
+        path: Vec<String>,
+        new_key: String,
+        update_links: bool,
+    },
+    Copy {
+        root: String,
+        src: Vec<String>,
+        dest_root: Option<String>,
         dest: Vec<String>,
+        reset_state: bool,
+        drop_due: bool,
+    },
+    /// Instantiate the template `template` as a new section at `dest`.
+    ApplyTemplate {
+        root: String,
+        template: String,
+        dest: Vec<String>,
+        vars: BTreeMap<String, String>,
+        anchor: Option<String>,
+    },
+    /// Make the item at `path` wait on the item at `depends_on`, same root.
+    AddDependency {
+        root: String,
+        path: Vec<String>,
+        depends_on: Vec<String>,
+    },
+    RemoveDependency {
+        root: String,
+        path: Vec<String>,
+        depends_on: Vec<String>,
+    },
+    /// Defer the item at `path` until `start`; `None` clears it.
+    SnoozeItem {
+        root: String,
+        path: Vec<String>,
+        start: Option<String>,
+    },
+    /// Turn the node at `path` into an item or a section.
+    Convert {
+        root: String,
+        path: Vec<String>,
+        kind: Kind,
+    },
+    /// Replace the metadata of the section at `path`.
+    UpdateSection {
+        root: String,
+        path: Vec<String>,
+        meta: SectionMeta,
     },
     Archive {
         root: String,
@@ -1,6 +1,6 @@
 use std::collections::BTreeMap;
 
-use hyperlog_core::log::GraphItem;
+use hyperlog_core::{due, log::GraphItem};
 
 use crate::{events::Events, shared_engine::SharedEngine, storage::Storage};
 
@@ -29,11 +29,32 @@ impl Commander {
             Command::CreateRoot { root } => {
                 self.engine.create_root(&root)?;
             }
+            Command::RenameRoot { root, new_name } => {
+                self.engine.rename_root(&root, &new_name)?;
+            }
+            Command::DeleteRoot { root } => {
+                let item = self.engine.delete_root(&root)?;
+                // Without a copy in deleted/ the root would be lost for good.
+                if let Err(e) = self.storage.store_deleted_root(&root, item.clone()) {
+                    self.engine.restore_root(&root, item)?;
+                    return Err(e);
+                }
+            }
+            Command::RestoreRoot { root } => {
+                if self.engine.get(&root, &[]).is_some() {
+                    anyhow::bail!("root {} already exists, rename it before restoring", root);
+                }
+                let item = self
+                    .storage
+                    .take_deleted_root(&root)?
+                    .ok_or(anyhow::anyhow!("no deleted root named {}", root))?;
+                self.engine.restore_root(&root, item)?;
+            }
             Command::CreateSection { root, path } => {
                 self.engine.create(
                     &root,
                     &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
-                    GraphItem::Section(BTreeMap::default()),
+                    GraphItem::section(BTreeMap::default()),
                 )?;
             }
             Command::CreateItem {
@@ -45,26 +66,55 @@ impl Commander {
             } => self.engine.create(
                 &root,
                 &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
-                GraphItem::Item {
-                    title,
-                    description,
-                    state,
-                },
+                GraphItem::item(title, description, state),
             )?,
-            Command::Move { root, src, dest } => self.engine.section_move(
+            Command::Move {
+                root,
+                src,
+                dest_root,
+                dest,
+            } => self.engine.section_move(
                 &root,
                 &src.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                dest_root.as_deref().unwrap_or(&root),
                 &dest.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
             )?,
-            Command::ToggleItem { root, path } => self
-                .engine
-                .toggle_item(&root, &path.iter().map(|p| p.as_str()).collect::<Vec<_>>())?,
+            Command::ToggleItem {
+                root,
+                path,
+                checklist_entry: None,
+            } => self.engine.toggle_item(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                due::today(),
+            )?,
+            Command::ToggleItem {
+                root,
+                path,
+                checklist_entry: Some(entry),
+            } => self.engine.toggle_checklist_entry(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                entry,
+            )?,
+            Command::SetItemState { root, path, state } => self.engine.set_item_state(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                state,
+                due::today(),
+            )?,
             Command::UpdateItem {
                 root,
                 path,
                 title,
                 description,
                 state,
+                due,
+                start,
+                recur,
+                tags,
+                priority,
+                checklist,
             } => self.engine.update_item(
                 &root,
                 &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
@@ -72,8 +122,89 @@ impl Commander {
                     title,
                     description,
                     state,
+                    due,
+                    start,
+                    recur,
+                    completions: Vec::new(),
+                    completed_at: None,
+                    depends_on: Vec::new(),
+                    tags,
+                    priority,
+                    checklist,
                 },
             )?,
+            Command::Rename {
+                root,
+                path,
+                new_key,
+                update_links,
+            } => self.engine.rename(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                &new_key,
+                update_links,
+            )?,
+            Command::Copy {
+                root,
+                src,
+                dest_root,
+                dest,
+                reset_state,
+                drop_due,
+            } => self.engine.copy(
+                &root,
+                &src.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                dest_root.as_deref().unwrap_or(&root),
+                &dest.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                reset_state,
+                drop_due,
+            )?,
+            Command::ApplyTemplate {
+                root,
+                template,
+                dest,
+                vars,
+                anchor,
+            } => self.engine.apply_template(
+                &root,
+                &template,
+                &dest.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                &vars,
+                anchor.as_deref(),
+            )?,
+            Command::AddDependency {
+                root,
+                path,
+                depends_on,
+            } => self.engine.add_dependency(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                &depends_on.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+            )?,
+            Command::RemoveDependency {
+                root,
+                path,
+                depends_on,
+            } => self.engine.remove_dependency(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                &depends_on.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+            )?,
+            Command::SnoozeItem { root, path, start } => self.engine.snooze_item(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                start,
+            )?,
+            Command::Convert { root, path, kind } => self.engine.convert(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                kind,
+            )?,
+            Command::UpdateSection { root, path, meta } => self.engine.update_section(
+                &root,
+                &path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+                meta,
+            )?,
             Command::Archive { root, path } => self
                 .engine
                 .archive(&root, &path.iter().map(|p| p.as_str()).collect::<Vec<_>>())?,
@@ -28,6 +28,33 @@ impl Commander {
                 let response = client.create_root(request).await?;
                 let res = response.into_inner();
             }
+            Command::RenameRoot { root, new_name } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(RenameRootRequest { root, new_name });
+                let response = client.rename_root(request).await?;
+                let res = response.into_inner();
+            }
+            Command::DeleteRoot { root } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(DeleteRootRequest { root });
+                let response = client.delete_root(request).await?;
+                let res = response.into_inner();
+            }
+            Command::RestoreRoot { root } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(RestoreRootRequest { root });
+                let response = client.restore_root(request).await?;
+                let res = response.into_inner();
+            }
             Command::CreateSection { root, path } => {
                 let channel = self.channel.clone();
 
@@ -54,28 +81,67 @@ impl Commander {
                     item: Some(ItemGraphItem {
                         title,
                         description,
-                        item_state: Some(match state {
-                            hyperlog_core::log::ItemState::NotDone => {
-                                item_graph_item::ItemState::NotDone(ItemStateNotDone {})
-                            }
-                            hyperlog_core::log::ItemState::Done => {
-                                item_graph_item::ItemState::Done(ItemStateDone {})
-                            }
-                        }),
+                        item_state: Some(to_proto_state(&state)),
+                        ..Default::default()
                     }),
                 });
                 let response = client.create_item(request).await?;
                 let res = response.into_inner();
             }
-            Command::Move { root, src, dest } => {
-                todo!()
+            Command::Move {
+                root,
+                src,
+                dest_root,
+                dest,
+            } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                // The server takes the full new path rather than the parent.
+                let mut dest = dest;
+                dest.extend(src.last().cloned());
+
+                let request = tonic::Request::new(MoveRequest {
+                    root,
+                    src,
+                    dest,
+                    dest_root: dest_root.unwrap_or_default(),
+                });
+                let response = client.r#move(request).await?;
+                let res = response.into_inner();
+            }
+            Command::SetItemState { root, path, state } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(SetItemStateRequest {
+                    root,
+                    path,
+                    item: Some(ItemGraphItem {
+                        item_state: Some(to_proto_state(&state)),
+                        ..Default::default()
+                    }),
+                });
+                let response = client.set_item_state(request).await?;
+                let res = response.into_inner();
             }
-            Command::ToggleItem { root, path } => {
+            Command::ToggleItem {
+                root,
+                path,
+                checklist_entry,
+            } => {
                 let channel = self.channel.clone();
 
                 let mut client = GraphClient::new(channel);
 
-                let request = tonic::Request::new(ToggleItemRequest { root, path });
+                let request = tonic::Request::new(ToggleItemRequest {
+                    root,
+                    path,
+                    // Numbered from 1 on the wire, 0 being the item itself.
+                    checklist_entry: checklist_entry.map(|e| e as i32 + 1).unwrap_or_default(),
+                });
                 let response = client.toggle_item(request).await?;
                 let res = response.into_inner();
             }
@@ -85,6 +151,12 @@ impl Commander {
                 title,
                 description,
                 state,
+                due,
+                start,
+                recur,
+                tags,
+                priority,
+                checklist,
             } => {
                 let channel = self.channel.clone();
 
@@ -96,19 +168,168 @@ impl Commander {
                     item: Some(ItemGraphItem {
                         title,
                         description,
-                        item_state: Some(match state {
-                            hyperlog_core::log::ItemState::NotDone => {
-                                item_graph_item::ItemState::NotDone(ItemStateNotDone {})
-                            }
-                            hyperlog_core::log::ItemState::Done => {
-                                item_graph_item::ItemState::Done(ItemStateDone {})
-                            }
-                        }),
+                        due: due.unwrap_or_default(),
+                        start: start.unwrap_or_default(),
+                        recur: recur.unwrap_or_default(),
+                        tags,
+                        priority: priority.map(|p| p.to_string()).unwrap_or_default(),
+                        checklist: checklist
+                            .into_iter()
+                            .map(|c| ChecklistEntry {
+                                title: c.title,
+                                done: c.done,
+                            })
+                            .collect(),
+                        item_state: Some(to_proto_state(&state)),
+                        ..Default::default()
                     }),
                 });
                 let response = client.update_item(request).await?;
                 let res = response.into_inner();
             }
+            Command::Rename {
+                root,
+                path,
+                new_key,
+                update_links,
+            } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(RenameRequest {
+                    root,
+                    path,
+                    new_key,
+                    update_links,
+                });
+                let response = client.rename(request).await?;
+                let res = response.into_inner();
+            }
+            Command::Copy {
+                root,
+                src,
+                dest_root,
+                dest,
+                reset_state,
+                drop_due,
+            } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(CopyRequest {
+                    root,
+                    src,
+                    dest_root: dest_root.unwrap_or_default(),
+                    dest,
+                    reset_state,
+                    drop_due,
+                });
+                let response = client.copy(request).await?;
+                let res = response.into_inner();
+            }
+            Command::ApplyTemplate {
+                root,
+                template,
+                dest,
+                vars,
+                anchor,
+            } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(ApplyTemplateRequest {
+                    root,
+                    template,
+                    dest,
+                    vars: vars.into_iter().collect(),
+                    anchor: anchor.unwrap_or_default(),
+                });
+                let response = client.apply_template(request).await?;
+                let res = response.into_inner();
+            }
+            Command::AddDependency {
+                root,
+                path,
+                depends_on,
+            } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(AddDependencyRequest {
+                    root,
+                    path,
+                    depends_on,
+                });
+                let response = client.add_dependency(request).await?;
+                let res = response.into_inner();
+            }
+            Command::RemoveDependency {
+                root,
+                path,
+                depends_on,
+            } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(RemoveDependencyRequest {
+                    root,
+                    path,
+                    depends_on,
+                });
+                let response = client.remove_dependency(request).await?;
+                let res = response.into_inner();
+            }
+            Command::SnoozeItem { root, path, start } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(SnoozeItemRequest {
+                    root,
+                    path,
+                    start: start.unwrap_or_default(),
+                });
+                let response = client.snooze_item(request).await?;
+                let res = response.into_inner();
+            }
+            Command::Convert { root, path, kind } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(ConvertRequest {
+                    root,
+                    path,
+                    kind: kind.to_string(),
+                });
+                let response = client.convert(request).await?;
+                let res = response.into_inner();
+            }
+            Command::UpdateSection { root, path, meta } => {
+                let channel = self.channel.clone();
+
+                let mut client = GraphClient::new(channel);
+
+                let request = tonic::Request::new(UpdateSectionRequest {
+                    root,
+                    path,
+                    section: Some(SectionGraphItem {
+                        title: meta.title,
+                        description: meta.description,
+                        colour: meta.colour,
+                        icon: meta.icon,
+                        collapsed: meta.collapsed,
+                        ..Default::default()
+                    }),
+                });
+                let response = client.update_section(request).await?;
+                let res = response.into_inner();
+            }
             Command::Archive { root, path } => {
                 let channel = self.channel.clone();
 
@@ -123,3 +344,23 @@ impl Commander {
         Ok(())
     }
 }
+
+fn to_proto_state(state: &hyperlog_core::log::ItemState) -> item_graph_item::ItemState {
+    match state {
+        hyperlog_core::log::ItemState::NotDone => {
+            item_graph_item::ItemState::NotDone(ItemStateNotDone {})
+        }
+        hyperlog_core::log::ItemState::InProgress => {
+            item_graph_item::ItemState::InProgress(ItemStateInProgress {})
+        }
+        hyperlog_core::log::ItemState::Blocked { reason } => {
+            item_graph_item::ItemState::Blocked(ItemStateBlocked {
+                reason: reason.clone(),
+            })
+        }
+        hyperlog_core::log::ItemState::Done => item_graph_item::ItemState::Done(ItemStateDone {}),
+        hyperlog_core::log::ItemState::Cancelled => {
+            item_graph_item::ItemState::Cancelled(ItemStateCancelled {})
+        }
+    }
+}
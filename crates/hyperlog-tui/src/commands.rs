@@ -2,14 +2,24 @@ use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 pub mod batch;
 
+pub mod add_dependency;
+pub mod apply_template;
 pub mod archive;
+pub mod convert;
+pub mod copy;
 pub mod create_item;
 pub mod create_section;
+pub mod open_agenda;
 pub mod open_item;
 pub mod open_update_item_dialog;
+pub mod remove_dependency;
+pub mod rename;
+pub mod set_item_state;
+pub mod snooze_item;
 pub mod toggle_item;
 pub mod update_graph;
 pub mod update_item;
+pub mod update_section;
 
 use crate::models::Msg;
 
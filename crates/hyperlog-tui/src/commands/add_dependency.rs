@@ -0,0 +1,57 @@
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct AddDependencyCommand {
+    commander: Commander,
+}
+
+impl AddDependencyCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(self, root: &str, path: &[&str], depends_on: &[&str]) -> super::Command {
+        let root = root.to_owned();
+        let path = path.iter().map(|s| s.to_string()).collect_vec();
+        let depends_on = depends_on.iter().map(|s| s.to_string()).collect_vec();
+
+        super::Command::new(|dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::DependenciesChanged(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::AddDependency {
+                        root,
+                        path,
+                        depends_on,
+                    })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::DependenciesChanged(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::DependenciesChanged(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait AddDependencyCommandExt {
+    fn add_dependency_command(&self) -> AddDependencyCommand;
+}
+
+impl AddDependencyCommandExt for SharedState {
+    fn add_dependency_command(&self) -> AddDependencyCommand {
+        AddDependencyCommand::new(self.commander.clone())
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct ApplyTemplateCommand {
+    commander: Commander,
+}
+
+impl ApplyTemplateCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(
+        self,
+        root: &str,
+        template: &str,
+        dest: &[&str],
+        vars: BTreeMap<String, String>,
+        anchor: Option<String>,
+    ) -> super::Command {
+        let root = root.to_owned();
+        let template = template.to_owned();
+        let dest = dest.iter().map(|s| s.to_string()).collect_vec();
+
+        super::Command::new(move |dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::TemplateApplied(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::ApplyTemplate {
+                        root,
+                        template,
+                        dest,
+                        vars,
+                        anchor,
+                    })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::TemplateApplied(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::TemplateApplied(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait ApplyTemplateCommandExt {
+    fn apply_template_command(&self) -> ApplyTemplateCommand;
+}
+
+impl ApplyTemplateCommandExt for SharedState {
+    fn apply_template_command(&self) -> ApplyTemplateCommand {
+        ApplyTemplateCommand::new(self.commander.clone())
+    }
+}
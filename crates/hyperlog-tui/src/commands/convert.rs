@@ -0,0 +1,53 @@
+use hyperlog_core::convert::Kind;
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct ConvertCommand {
+    commander: Commander,
+}
+
+impl ConvertCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(self, root: &str, path: &[&str], kind: Kind) -> super::Command {
+        let root = root.to_owned();
+        let path = path.iter().map(|s| s.to_string()).collect_vec();
+
+        super::Command::new(move |dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::Converted(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::Convert { root, path, kind })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::Converted(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::Converted(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait ConvertCommandExt {
+    fn convert_command(&self) -> ConvertCommand;
+}
+
+impl ConvertCommandExt for SharedState {
+    fn convert_command(&self) -> ConvertCommand {
+        ConvertCommand::new(self.commander.clone())
+    }
+}
@@ -0,0 +1,69 @@
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct CopyCommand {
+    commander: Commander,
+}
+
+impl CopyCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(
+        self,
+        root: &str,
+        src: &[&str],
+        dest_root: &str,
+        dest: &[&str],
+        reset_state: bool,
+        drop_due: bool,
+    ) -> super::Command {
+        let root = root.to_owned();
+        let src = src.iter().map(|s| s.to_string()).collect_vec();
+        let dest_root = dest_root.to_owned();
+        let dest = dest.iter().map(|s| s.to_string()).collect_vec();
+
+        super::Command::new(move |dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::Copied(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::Copy {
+                        root,
+                        src,
+                        dest_root: Some(dest_root),
+                        dest,
+                        reset_state,
+                        drop_due,
+                    })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::Copied(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::Copied(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait CopyCommandExt {
+    fn copy_command(&self) -> CopyCommand;
+}
+
+impl CopyCommandExt for SharedState {
+    fn copy_command(&self) -> CopyCommand {
+        CopyCommand::new(self.commander.clone())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    models::{IOEvent, Msg},
+    querier::Querier,
+    state::SharedState,
+};
+
+pub struct OpenAgendaCommand {
+    querier: Querier,
+}
+
+impl OpenAgendaCommand {
+    pub fn new(querier: Querier) -> Self {
+        Self { querier }
+    }
+
+    /// Load the agenda for `root`, or for every root when `None`.
+    pub fn command(self, root: Option<&str>) -> super::Command {
+        let root = root.map(|r| r.to_owned());
+
+        super::Command::new(|dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::OpenAgenda(IOEvent::Initialized));
+
+                match self.querier.agenda_async(root.as_deref()).await {
+                    Ok(agenda) => dispatch.send(Msg::OpenAgenda(IOEvent::Success(agenda))),
+                    Err(e) => dispatch.send(Msg::OpenAgenda(IOEvent::Failure(e.to_string()))),
+                }
+            });
+
+            None
+        })
+    }
+}
+
+pub trait OpenAgendaCommandExt {
+    fn open_agenda_command(&self) -> OpenAgendaCommand;
+}
+
+impl OpenAgendaCommandExt for SharedState {
+    fn open_agenda_command(&self) -> OpenAgendaCommand {
+        OpenAgendaCommand::new(self.querier.clone())
+    }
+}
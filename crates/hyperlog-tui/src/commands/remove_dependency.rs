@@ -0,0 +1,57 @@
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct RemoveDependencyCommand {
+    commander: Commander,
+}
+
+impl RemoveDependencyCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(self, root: &str, path: &[&str], depends_on: &[&str]) -> super::Command {
+        let root = root.to_owned();
+        let path = path.iter().map(|s| s.to_string()).collect_vec();
+        let depends_on = depends_on.iter().map(|s| s.to_string()).collect_vec();
+
+        super::Command::new(|dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::DependenciesChanged(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::RemoveDependency {
+                        root,
+                        path,
+                        depends_on,
+                    })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::DependenciesChanged(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::DependenciesChanged(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait RemoveDependencyCommandExt {
+    fn remove_dependency_command(&self) -> RemoveDependencyCommand;
+}
+
+impl RemoveDependencyCommandExt for SharedState {
+    fn remove_dependency_command(&self) -> RemoveDependencyCommand {
+        RemoveDependencyCommand::new(self.commander.clone())
+    }
+}
@@ -0,0 +1,64 @@
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct RenameCommand {
+    commander: Commander,
+}
+
+impl RenameCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(
+        self,
+        root: &str,
+        path: &[&str],
+        new_key: &str,
+        update_links: bool,
+    ) -> super::Command {
+        let root = root.to_owned();
+        let path = path.iter().map(|s| s.to_string()).collect_vec();
+        let new_key = new_key.to_owned();
+
+        super::Command::new(move |dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::Renamed(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::Rename {
+                        root,
+                        path,
+                        new_key,
+                        update_links,
+                    })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::Renamed(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::Renamed(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait RenameCommandExt {
+    fn rename_command(&self) -> RenameCommand;
+}
+
+impl RenameCommandExt for SharedState {
+    fn rename_command(&self) -> RenameCommand {
+        RenameCommand::new(self.commander.clone())
+    }
+}
@@ -0,0 +1,53 @@
+use hyperlog_core::log::ItemState;
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct SetItemStateCommand {
+    commander: Commander,
+}
+
+impl SetItemStateCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(self, root: &str, path: &[&str], state: ItemState) -> super::Command {
+        let root = root.to_owned();
+        let path = path.iter().map(|s| s.to_string()).collect_vec();
+
+        super::Command::new(|dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::ItemStateSet(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::SetItemState { root, path, state })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::ItemStateSet(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::ItemStateSet(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait SetItemStateCommandExt {
+    fn set_item_state_command(&self) -> SetItemStateCommand;
+}
+
+impl SetItemStateCommandExt for SharedState {
+    fn set_item_state_command(&self) -> SetItemStateCommand {
+        SetItemStateCommand::new(self.commander.clone())
+    }
+}
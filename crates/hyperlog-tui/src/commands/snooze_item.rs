@@ -0,0 +1,52 @@
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct SnoozeItemCommand {
+    commander: Commander,
+}
+
+impl SnoozeItemCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(self, root: &str, path: &[&str], start: Option<String>) -> super::Command {
+        let root = root.to_owned();
+        let path = path.iter().map(|s| s.to_string()).collect_vec();
+
+        super::Command::new(|dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::ItemSnoozed(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::SnoozeItem { root, path, start })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::ItemSnoozed(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::ItemSnoozed(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait SnoozeItemCommandExt {
+    fn snooze_item_command(&self) -> SnoozeItemCommand;
+}
+
+impl SnoozeItemCommandExt for SharedState {
+    fn snooze_item_command(&self) -> SnoozeItemCommand {
+        SnoozeItemCommand::new(self.commander.clone())
+    }
+}
@@ -15,17 +15,27 @@ impl ToggleItemCommand {
         Self { commander }
     }
 
-    pub fn command(self, root: &str, path: &[&str]) -> super::Command {
+    /// Toggle the item, or its checklist entry counted from 0.
+    pub fn command(
+        self,
+        root: &str,
+        path: &[&str],
+        checklist_entry: Option<usize>,
+    ) -> super::Command {
         let root = root.to_owned();
         let path = path.iter().map(|s| s.to_string()).collect_vec();
 
-        super::Command::new(|dispatch| {
+        super::Command::new(move |dispatch| {
             tokio::spawn(async move {
                 dispatch.send(crate::models::Msg::ItemToggled(IOEvent::Initialized));
 
                 match self
                     .commander
-                    .execute(commander::Command::ToggleItem { root, path })
+                    .execute(commander::Command::ToggleItem {
+                        root,
+                        path,
+                        checklist_entry,
+                    })
                     .await
                 {
                     Ok(()) => {
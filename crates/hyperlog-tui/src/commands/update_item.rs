@@ -1,4 +1,4 @@
-use hyperlog_core::log::ItemState;
+use hyperlog_core::log::GraphItem;
 use itertools::Itertools;
 
 use crate::{
@@ -16,24 +16,34 @@ impl UpdateItemCommand {
         Self { commander }
     }
 
-    pub fn command(
-        self,
-        root: &str,
-        path: &[&str],
-        title: &str,
-        description: &str,
-        state: ItemState,
-    ) -> super::Command {
+    /// Update the item at `path` with the fields of `item`.
+    pub fn command(self, root: &str, path: &[&str], item: GraphItem) -> super::Command {
         let root = root.to_owned();
         let path = path.iter().map(|s| s.to_string()).collect_vec();
-        let title = title.to_string();
-        let description = description.to_string();
-        let state = state.clone();
 
-        super::Command::new(|dispatch| {
+        super::Command::new(move |dispatch| {
             tokio::spawn(async move {
                 dispatch.send(crate::models::Msg::ItemUpdated(IOEvent::Initialized));
 
+                let GraphItem::Item {
+                    title,
+                    description,
+                    state,
+                    due,
+                    start,
+                    recur,
+                    tags,
+                    priority,
+                    checklist,
+                    ..
+                } = item
+                else {
+                    dispatch.send(crate::models::Msg::ItemUpdated(IOEvent::Failure(
+                        "only items can be updated".into(),
+                    )));
+                    return;
+                };
+
                 match self
                     .commander
                     .execute(commander::Command::UpdateItem {
@@ -42,6 +52,12 @@ impl UpdateItemCommand {
                         title,
                         description,
                         state,
+                        due,
+                        start,
+                        recur,
+                        tags,
+                        priority,
+                        checklist,
                     })
                     .await
                 {
@@ -0,0 +1,53 @@
+use hyperlog_core::log::SectionMeta;
+use itertools::Itertools;
+
+use crate::{
+    commander::{self, Commander},
+    models::{IOEvent, Msg},
+    state::SharedState,
+};
+
+pub struct UpdateSectionCommand {
+    commander: Commander,
+}
+
+impl UpdateSectionCommand {
+    pub fn new(commander: Commander) -> Self {
+        Self { commander }
+    }
+
+    pub fn command(self, root: &str, path: &[&str], meta: SectionMeta) -> super::Command {
+        let root = root.to_owned();
+        let path = path.iter().map(|s| s.to_string()).collect_vec();
+
+        super::Command::new(move |dispatch| {
+            tokio::spawn(async move {
+                dispatch.send(Msg::SectionUpdated(IOEvent::Initialized));
+
+                match self
+                    .commander
+                    .execute(commander::Command::UpdateSection { root, path, meta })
+                    .await
+                {
+                    Ok(()) => {
+                        dispatch.send(Msg::SectionUpdated(IOEvent::Success(())));
+                    }
+                    Err(e) => {
+                        dispatch.send(Msg::SectionUpdated(IOEvent::Failure(e.to_string())));
+                    }
+                }
+            });
+            None
+        })
+    }
+}
+
+pub trait UpdateSectionCommandExt {
+    fn update_section_command(&self) -> UpdateSectionCommand;
+}
+
+impl UpdateSectionCommandExt for SharedState {
+    fn update_section_command(&self) -> UpdateSectionCommand {
+        UpdateSectionCommand::new(self.commander.clone())
+    }
+}
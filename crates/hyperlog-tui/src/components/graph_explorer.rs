@@ -1,15 +1,20 @@
 use anyhow::Result;
-use hyperlog_core::log::GraphItem;
+use hyperlog_core::{convert::Kind, log::GraphItem};
 use itertools::Itertools;
 use ratatui::{prelude::*, widgets::*};
 
 use crate::{
     command_parser::Commands,
     commands::{
-        archive::ArchiveCommandExt, batch::BatchCommand, create_item::CreateItemCommandExt,
-        create_section::CreateSectionCommandExt, open_item::OpenItemCommandExt,
-        open_update_item_dialog::OpenUpdateItemDialogCommandExt, toggle_item::ToggleItemCommandExt,
-        update_graph::UpdateGraphCommandExt, Command, IntoCommand,
+        add_dependency::AddDependencyCommandExt, apply_template::ApplyTemplateCommandExt,
+        archive::ArchiveCommandExt, batch::BatchCommand, convert::ConvertCommandExt,
+        copy::CopyCommandExt, create_item::CreateItemCommandExt,
+        create_section::CreateSectionCommandExt, open_agenda::OpenAgendaCommandExt,
+        open_item::OpenItemCommandExt, open_update_item_dialog::OpenUpdateItemDialogCommandExt,
+        remove_dependency::RemoveDependencyCommandExt, rename::RenameCommandExt,
+        set_item_state::SetItemStateCommandExt, snooze_item::SnoozeItemCommandExt,
+        toggle_item::ToggleItemCommandExt, update_graph::UpdateGraphCommandExt,
+        update_section::UpdateSectionCommandExt, Command, IntoCommand,
     },
     components::movement_graph::GraphItemType,
     models::{IOEvent, Msg},
@@ -33,9 +38,23 @@ impl Default for FilterBy {
     }
 }
 
+#[derive(Default, Clone, Debug)]
+pub enum SortBy {
+    #[default]
+    Key,
+    /// Most urgent first, unprioritized items after, then sections; by key
+    /// within each.
+    Priority,
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct DisplayOptions {
     pub filter_by: FilterBy,
+    pub sort_by: SortBy,
+    /// Only items carrying this tag, see [`hyperlog_core::tags::matches`].
+    pub tag: Option<String>,
+    /// Also items whose start date is still ahead.
+    pub show_deferred: bool,
 }
 
 pub struct GraphExplorer<'a> {
@@ -53,6 +72,9 @@ pub struct GraphExplorerState<'a> {
     display_options: DisplayOptions,
 
     graph: Option<GraphItem>,
+
+    /// Root and path of the node `put` copies.
+    yanked: Option<(String, Vec<String>)>,
 }
 
 impl<'a> GraphExplorerState<'a> {
@@ -90,6 +112,7 @@ impl<'a> GraphExplorer<'a> {
                 current_position: Vec::new(),
                 graph: None,
                 display_options: DisplayOptions::default(),
+                yanked: None,
             },
         }
     }
@@ -251,6 +274,164 @@ impl<'a> GraphExplorer<'a> {
                     );
                 }
             }
+            Commands::Rename { name, update_links } => {
+                let path = self.get_current_path();
+                if !path.is_empty() {
+                    batch.with(
+                        self.state
+                            .rename_command()
+                            .command(
+                                &self.inner.root,
+                                &path.iter().map(|i| i.as_str()).collect_vec(),
+                                name,
+                                *update_links,
+                            )
+                            .into_command(),
+                    );
+                }
+            }
+            Commands::Yank => self.yank(),
+            Commands::Put {
+                reset_state,
+                drop_due,
+            } => {
+                if let Some(cmd) = self.put(*reset_state, *drop_due) {
+                    batch.with(cmd);
+                }
+            }
+            Commands::Template {
+                name,
+                key,
+                vars,
+                anchor,
+            } => {
+                // Like put: into the current section, or next to the current item.
+                let mut dest = self.get_current_path();
+                if let Some(GraphItemType::Item { .. }) =
+                    self.get_current_item().map(|i| i.item_type)
+                {
+                    dest.pop();
+                }
+                dest.push(key.clone());
+
+                batch.with(self.state.apply_template_command().command(
+                    &self.inner.root,
+                    name,
+                    &dest.iter().map(|p| p.as_str()).collect_vec(),
+                    vars.clone(),
+                    anchor.clone(),
+                ));
+            }
+            Commands::Agenda { all } => {
+                let root = (!*all).then_some(self.inner.root.as_str());
+                batch.with(self.state.open_agenda_command().command(root));
+            }
+            Commands::DependOn { path: depends_on } => {
+                if let Some(GraphItemType::Item { .. }) =
+                    self.get_current_item().map(|i| i.item_type)
+                {
+                    batch.with(
+                        self.state.add_dependency_command().command(
+                            &self.inner.root,
+                            &self
+                                .get_current_path()
+                                .iter()
+                                .map(|p| p.as_str())
+                                .collect_vec(),
+                            &depends_on.iter().map(|p| p.as_str()).collect_vec(),
+                        ),
+                    );
+                }
+            }
+            Commands::RemoveDependency { path: depends_on } => {
+                if let Some(GraphItemType::Item { .. }) =
+                    self.get_current_item().map(|i| i.item_type)
+                {
+                    batch.with(
+                        self.state.remove_dependency_command().command(
+                            &self.inner.root,
+                            &self
+                                .get_current_path()
+                                .iter()
+                                .map(|p| p.as_str())
+                                .collect_vec(),
+                            &depends_on.iter().map(|p| p.as_str()).collect_vec(),
+                        ),
+                    );
+                }
+            }
+            Commands::Snooze { start } => {
+                if let Some(GraphItemType::Item { .. }) =
+                    self.get_current_item().map(|i| i.item_type)
+                {
+                    batch.with(
+                        self.state.snooze_item_command().command(
+                            &self.inner.root,
+                            &self
+                                .get_current_path()
+                                .iter()
+                                .map(|p| p.as_str())
+                                .collect_vec(),
+                            start.clone(),
+                        ),
+                    );
+                }
+            }
+            Commands::Check { entry } => {
+                if let Some(GraphItemType::Item { .. }) =
+                    self.get_current_item().map(|i| i.item_type)
+                {
+                    batch.with(
+                        self.state.toggle_item_command().command(
+                            &self.inner.root,
+                            &self
+                                .get_current_path()
+                                .iter()
+                                .map(|p| p.as_str())
+                                .collect_vec(),
+                            Some(*entry),
+                        ),
+                    );
+                }
+            }
+            Commands::Convert => {
+                let kind = match self.get_current_item().map(|i| i.item_type) {
+                    Some(GraphItemType::Item { .. }) => Some(Kind::Section),
+                    Some(GraphItemType::Section { .. }) => Some(Kind::Item),
+                    None => None,
+                };
+                if let Some(kind) = kind {
+                    batch.with(
+                        self.state.convert_command().command(
+                            &self.inner.root,
+                            &self
+                                .get_current_path()
+                                .iter()
+                                .map(|p| p.as_str())
+                                .collect_vec(),
+                            kind,
+                        ),
+                    );
+                }
+            }
+            Commands::Section { field } => {
+                if let Some(GraphItemType::Section { mut meta, .. }) =
+                    self.get_current_item().map(|i| i.item_type)
+                {
+                    field.apply(&mut meta);
+                    batch.with(
+                        self.state.update_section_command().command(
+                            &self.inner.root,
+                            &self
+                                .get_current_path()
+                                .iter()
+                                .map(|p| p.as_str())
+                                .collect_vec(),
+                            meta,
+                        ),
+                    );
+                }
+            }
             Commands::CreateSection { name } => {
                 if !name.is_empty() {
                     let mut path = self.get_current_path();
@@ -308,7 +489,7 @@ impl<'a> GraphExplorer<'a> {
                         item.name
                     );
                     match item.item_type {
-                        GraphItemType::Section => {
+                        GraphItemType::Section { .. } => {
                             todo!("cannot edit section at the moment")
                         }
                         GraphItemType::Item { .. } => {
@@ -327,6 +508,23 @@ impl<'a> GraphExplorer<'a> {
             Commands::HideDone => {
                 self.inner.display_options.filter_by = FilterBy::NotDone;
             }
+            Commands::Sort { priority } => {
+                self.inner.display_options.sort_by = if *priority {
+                    SortBy::Priority
+                } else {
+                    SortBy::Key
+                };
+                self.inner.current_position.clear();
+            }
+            Commands::FilterTag { tag } => {
+                self.inner.display_options.tag = tag.clone();
+                self.inner.current_position.clear();
+            }
+            Commands::ToggleDeferred => {
+                self.inner.display_options.show_deferred =
+                    !self.inner.display_options.show_deferred;
+                self.inner.current_position.clear();
+            }
             Commands::Test => {
                 return Ok(Some(Command::new(|dispatch| {
                     tokio::spawn(async move {
@@ -356,6 +554,69 @@ impl<'a> GraphExplorer<'a> {
         Ok(Some(batch.into_command()))
     }
 
+    pub(crate) fn yank(&mut self) {
+        let path = self.get_current_path();
+        if !path.is_empty() {
+            tracing::debug!("yanked: {}", path.join("."));
+            self.inner.yanked = Some((self.inner.root.clone(), path));
+        }
+    }
+
+    /// Copy the yanked node into the current section, or next to the current
+    /// item. The key gets a `-copy` suffix when it is already taken there.
+    pub(crate) fn put(&self, reset_state: bool, drop_due: bool) -> Option<Command> {
+        let (src_root, src) = self.inner.yanked.as_ref()?;
+        let key = src.last()?;
+
+        let mut parent = self.get_current_path();
+        if let Some(GraphItemType::Item { .. }) = self.get_current_item().map(|i| i.item_type) {
+            parent.pop();
+        }
+
+        let siblings = self
+            .inner
+            .graph
+            .as_ref()
+            .and_then(|g| g.get(&parent.iter().map(|p| p.as_str()).collect_vec()));
+        let taken = |key: &str| match siblings {
+            Some(GraphItem::User(s)) | Some(GraphItem::Section { items: s, .. }) => {
+                s.contains_key(key)
+            }
+            _ => false,
+        };
+        let dest_key = std::iter::once(key.clone())
+            .chain(std::iter::once(format!("{key}-copy")))
+            .chain((2..).map(|n| format!("{key}-copy-{n}")))
+            .find(|k| !taken(k))?;
+
+        let mut dest = parent;
+        dest.push(dest_key);
+
+        Some(self.state.copy_command().command(
+            src_root,
+            &src.iter().map(|p| p.as_str()).collect_vec(),
+            &self.inner.root,
+            &dest.iter().map(|p| p.as_str()).collect_vec(),
+            reset_state,
+            drop_due,
+        ))
+    }
+
+    /// Move the current item on to its next state, see
+    /// [`hyperlog_core::log::ItemState::next`].
+    pub(crate) fn cycle_state(&self) -> Option<Command> {
+        let path = self.get_current_path();
+        let path = path.iter().map(|p| p.as_str()).collect_vec();
+        match self.inner.graph.as_ref()?.get(&path)? {
+            GraphItem::Item { state, .. } => Some(self.state.set_item_state_command().command(
+                &self.inner.root,
+                &path,
+                state.next(),
+            )),
+            _ => None,
+        }
+    }
+
     pub(crate) fn interact(&mut self) -> anyhow::Result<Command> {
         let mut batch = BatchCommand::default();
 
@@ -376,6 +637,7 @@ impl<'a> GraphExplorer<'a> {
                     .iter()
                     .map(|i| i.as_str())
                     .collect_vec(),
+                None,
             );
 
             batch.with(cmd.into_command());
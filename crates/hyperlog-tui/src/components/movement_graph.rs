@@ -1,12 +1,35 @@
-use hyperlog_core::log::{GraphItem, ItemState};
+use hyperlog_core::{
+    checklist,
+    deps::Dependencies,
+    due,
+    log::{GraphItem, ItemState, Priority, SectionMeta},
+    stats::Counts,
+    tags,
+};
 use itertools::Itertools;
 
-use super::graph_explorer::{DisplayOptions, FilterBy};
+use super::graph_explorer::{DisplayOptions, FilterBy, SortBy};
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum GraphItemType {
-    Section,
-    Item { done: bool },
+    Section {
+        /// Items anywhere below it, hidden ones included.
+        counts: Counts,
+        /// Its description, colour, icon and collapsed state; empty for roots.
+        meta: SectionMeta,
+    },
+    Item {
+        state: ItemState,
+        /// Waiting on an item it depends on.
+        blocked: bool,
+        /// Set and inline tags.
+        tags: Vec<String>,
+        priority: Option<Priority>,
+        /// Its start date is still ahead.
+        deferred: bool,
+        /// Checklist entries done, and how many there are.
+        checklist: (usize, usize),
+    },
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -111,36 +134,105 @@ impl MovementGraph {
     }
 
     pub fn new(graph_item: GraphItem, display_options: &DisplayOptions) -> MovementGraph {
+        let deps = Dependencies::from_graph(&graph_item);
+
+        Self::build(graph_item, display_options, &deps, due::today(), "")
+    }
+
+    fn build(
+        graph_item: GraphItem,
+        display_options: &DisplayOptions,
+        deps: &Dependencies,
+        today: i64,
+        prefix: &str,
+    ) -> MovementGraph {
         let mut graph = MovementGraph::default();
 
         match graph_item {
-            GraphItem::User(sections) | GraphItem::Section(sections) => {
+            GraphItem::User(sections)
+            | GraphItem::Section {
+                items: sections, ..
+            } => {
                 let graph_items = sections
                     .iter()
-                    .sorted_by(|(a, _), (b, _)| Ord::cmp(a, b))
+                    .sorted_by(|(a_key, a), (b_key, b)| {
+                        let rank = |item: &GraphItem| match (&display_options.sort_by, item) {
+                            (SortBy::Key, _) => 0,
+                            (SortBy::Priority, GraphItem::Item { priority, .. }) => {
+                                priority.map_or(4, |p| p as u8)
+                            }
+                            (SortBy::Priority, _) => 5,
+                        };
+                        rank(a).cmp(&rank(b)).then_with(|| Ord::cmp(a_key, b_key))
+                    })
                     .filter(|(_, item)| {
-                        if let GraphItem::Item { state, .. } = item {
+                        if let GraphItem::Item {
+                            state,
+                            description,
+                            tags,
+                            start,
+                            ..
+                        } = item
+                        {
                             if matches!(display_options.filter_by, FilterBy::NotDone)
-                                && matches!(state, ItemState::Done)
+                                && state.is_closed()
                             {
                                 return false;
                             }
+                            if !display_options.show_deferred
+                                && due::is_deferred(start.as_deref(), today)
+                            {
+                                return false;
+                            }
+                            if let Some(tag) = &display_options.tag {
+                                if !tags::matches(tags, description, tag) {
+                                    return false;
+                                }
+                            }
                         }
 
                         true
                     })
                     .enumerate()
-                    .map(|(i, (key, value))| MovementGraphItem {
-                        index: i,
-                        name: key.clone(),
-                        values: Self::new(value.clone(), display_options),
-                        item_type: match value {
-                            GraphItem::User(_) => GraphItemType::Section,
-                            GraphItem::Section(_) => GraphItemType::Section,
-                            GraphItem::Item { state, .. } => GraphItemType::Item {
-                                done: matches!(state, ItemState::Done),
+                    .map(|(i, (key, value))| {
+                        let path = if prefix.is_empty() {
+                            key.clone()
+                        } else {
+                            format!("{prefix}.{key}")
+                        };
+
+                        MovementGraphItem {
+                            index: i,
+                            name: key.clone(),
+                            values: Self::build(value.clone(), display_options, deps, today, &path),
+                            item_type: match value {
+                                GraphItem::User(_) | GraphItem::Section { .. } => {
+                                    GraphItemType::Section {
+                                        counts: subtree_counts(value, today),
+                                        meta: match value {
+                                            GraphItem::Section { meta, .. } => meta.clone(),
+                                            _ => SectionMeta::default(),
+                                        },
+                                    }
+                                }
+                                GraphItem::Item {
+                                    state,
+                                    description,
+                                    tags,
+                                    priority,
+                                    start,
+                                    checklist,
+                                    ..
+                                } => GraphItemType::Item {
+                                    state: state.clone(),
+                                    blocked: deps.is_blocked(&path),
+                                    tags: tags::item_tags(tags, description),
+                                    priority: *priority,
+                                    deferred: due::is_deferred(start.as_deref(), today),
+                                    checklist: checklist::progress(checklist),
+                                },
                             },
-                        },
+                        }
                     })
                     .collect::<Vec<_>>();
 
@@ -153,6 +245,27 @@ impl MovementGraph {
     }
 }
 
+/// Counts of every item below `item`, whatever the display options hide.
+fn subtree_counts(item: &GraphItem, today: i64) -> Counts {
+    fn walk(item: &GraphItem, today: i64, counts: &mut Counts) {
+        match item {
+            GraphItem::User(children)
+            | GraphItem::Section {
+                items: children, ..
+            } => {
+                for child in children.values() {
+                    walk(child, today, counts);
+                }
+            }
+            GraphItem::Item { state, due, .. } => counts.add(state, due.as_deref(), today),
+        }
+    }
+
+    let mut counts = Counts::default();
+    walk(item, today, &mut counts);
+    counts
+}
+
 impl From<GraphItem> for MovementGraph {
     fn from(value: GraphItem) -> Self {
         MovementGraph::new(value, &DisplayOptions::default())
@@ -163,10 +276,16 @@ impl From<GraphItem> for MovementGraph {
 mod test {
     use std::collections::BTreeMap;
 
-    use hyperlog_core::log::{GraphItem, ItemState};
+    use hyperlog_core::{
+        log::{GraphItem, ItemState, Priority, SectionMeta},
+        stats::Counts,
+    };
     use similar_asserts::assert_eq;
 
-    use crate::components::movement_graph::{GraphItemType, MovementGraphItem};
+    use crate::components::{
+        graph_explorer::{DisplayOptions, FilterBy, SortBy},
+        movement_graph::{GraphItemType, MovementGraphItem},
+    };
 
     use super::MovementGraph;
 
@@ -200,26 +319,26 @@ mod test {
     fn test_can_transform_to_movement_graph() {
         let graph = GraphItem::User(BTreeMap::from([(
             "0".to_string(),
-            GraphItem::Section(BTreeMap::from([
-                ("00".to_string(), GraphItem::Section(BTreeMap::new())),
+            GraphItem::section(BTreeMap::from([
+                ("00".to_string(), GraphItem::section(BTreeMap::new())),
                 (
                     "01".to_string(),
-                    GraphItem::Section(BTreeMap::from([
+                    GraphItem::section(BTreeMap::from([
                         (
                             "010".to_string(),
-                            GraphItem::Item {
-                                title: "some-title".into(),
-                                description: "some-desc".into(),
-                                state: ItemState::NotDone,
-                            },
+                            GraphItem::item(
+                                "some-title".into(),
+                                "some-desc".into(),
+                                ItemState::NotDone,
+                            ),
                         ),
                         (
                             "011".to_string(),
-                            GraphItem::Item {
-                                title: "some-title".into(),
-                                description: "some-desc".into(),
-                                state: ItemState::NotDone,
-                            },
+                            GraphItem::item(
+                                "some-title".into(),
+                                "some-desc".into(),
+                                ItemState::NotDone,
+                            ),
                         ),
                     ])),
                 ),
@@ -233,32 +352,63 @@ mod test {
                 items: vec![MovementGraphItem {
                     index: 0,
                     name: "0".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts {
+                            total: 2,
+                            done: 0,
+                            overdue: 0,
+                        },
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![
                             MovementGraphItem {
                                 index: 0,
                                 name: "00".into(),
                                 values: MovementGraph::default(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                             },
                             MovementGraphItem {
                                 index: 1,
                                 name: "01".into(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts {
+                                        total: 2,
+                                        done: 0,
+                                        overdue: 0,
+                                    },
+                                    meta: SectionMeta::default(),
+                                },
                                 values: MovementGraph {
                                     items: vec![
                                         MovementGraphItem {
                                             index: 0,
                                             name: "010".into(),
                                             values: MovementGraph::default(),
-                                            item_type: GraphItemType::Item { done: false },
+                                            item_type: GraphItemType::Item {
+                                                state: ItemState::NotDone,
+                                                blocked: false,
+                                                tags: Vec::new(),
+                                                priority: None,
+                                                deferred: false,
+                                                checklist: (0, 0),
+                                            },
                                         },
                                         MovementGraphItem {
                                             index: 1,
                                             name: "011".into(),
                                             values: MovementGraph::default(),
-                                            item_type: GraphItemType::Item { done: false },
+                                            item_type: GraphItemType::Item {
+                                                state: ItemState::NotDone,
+                                                blocked: false,
+                                                tags: Vec::new(),
+                                                priority: None,
+                                                deferred: false,
+                                                checklist: (0, 0),
+                                            },
                                         },
                                     ]
                                 }
@@ -271,6 +421,101 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_can_sort_by_priority() {
+        let item = |new_priority| {
+            let mut item = GraphItem::item("some-title".into(), String::new(), ItemState::NotDone);
+            if let GraphItem::Item { priority, .. } = &mut item {
+                *priority = new_priority;
+            }
+            item
+        };
+        let graph = GraphItem::User(BTreeMap::from([
+            ("a".to_string(), GraphItem::section(BTreeMap::new())),
+            ("b".to_string(), item(None)),
+            ("c".to_string(), item(Some(Priority::P2))),
+            ("d".to_string(), item(Some(Priority::P0))),
+        ]));
+
+        let names = |sort_by| {
+            let options = DisplayOptions {
+                sort_by,
+                ..Default::default()
+            };
+            MovementGraph::new(graph.clone(), &options)
+                .items
+                .into_iter()
+                .map(|i| i.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["a", "b", "c", "d"], names(SortBy::Key));
+        assert_eq!(vec!["d", "c", "b", "a"], names(SortBy::Priority));
+    }
+
+    #[test]
+    fn test_hides_deferred_items_unless_shown() {
+        let item = |new_start: Option<&str>| {
+            let mut item = GraphItem::item("some-title".into(), String::new(), ItemState::NotDone);
+            if let GraphItem::Item { start, .. } = &mut item {
+                *start = new_start.map(String::from);
+            }
+            item
+        };
+        let graph = GraphItem::User(BTreeMap::from([
+            ("now".to_string(), item(None)),
+            ("started".to_string(), item(Some("2000-01-01"))),
+            ("later".to_string(), item(Some("9999-01-01"))),
+        ]));
+
+        let names = |show_deferred| {
+            let options = DisplayOptions {
+                show_deferred,
+                ..Default::default()
+            };
+            MovementGraph::new(graph.clone(), &options)
+                .items
+                .into_iter()
+                .map(|i| i.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["now", "started"], names(false));
+        assert_eq!(vec!["later", "now", "started"], names(true));
+    }
+
+    #[test]
+    fn test_section_counts_include_hidden_items() {
+        let item = |state| GraphItem::item("some-title".into(), String::new(), state);
+        let graph = GraphItem::User(BTreeMap::from([(
+            "section".to_string(),
+            GraphItem::section(BTreeMap::from([
+                ("done".to_string(), item(ItemState::Done)),
+                ("open".to_string(), item(ItemState::NotDone)),
+                ("cancelled".to_string(), item(ItemState::Cancelled)),
+            ])),
+        )]));
+
+        let options = DisplayOptions {
+            filter_by: FilterBy::NotDone,
+            ..Default::default()
+        };
+        let section = &MovementGraph::new(graph, &options).items[0];
+
+        assert_eq!(1, section.values.items.len());
+        assert_eq!(
+            GraphItemType::Section {
+                counts: Counts {
+                    total: 2,
+                    done: 1,
+                    overdue: 0,
+                },
+                meta: SectionMeta::default(),
+            },
+            section.item_type
+        );
+    }
+
     #[test]
     fn test_get_graph_item() -> anyhow::Result<()> {
         let graph = MovementGraph {
@@ -278,20 +523,29 @@ mod test {
                 MovementGraphItem {
                     index: 0,
                     name: "0".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![
                             MovementGraphItem {
                                 index: 0,
                                 name: "0".into(),
                                 values: MovementGraph::default(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                             },
                             MovementGraphItem {
                                 index: 1,
                                 name: "0".into(),
                                 values: MovementGraph::default(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                             },
                         ],
                     },
@@ -299,26 +553,38 @@ mod test {
                 MovementGraphItem {
                     index: 1,
                     name: "0".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![
                             MovementGraphItem {
                                 index: 0,
                                 name: "0".into(),
                                 values: MovementGraph::default(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                             },
                             MovementGraphItem {
                                 index: 1,
                                 name: "0".into(),
                                 values: MovementGraph::default(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                             },
                             MovementGraphItem {
                                 index: 2,
                                 name: "0".into(),
                                 values: MovementGraph::default(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                             },
                         ],
                     },
@@ -326,20 +592,29 @@ mod test {
                 MovementGraphItem {
                     index: 2,
                     name: "0".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![
                             MovementGraphItem {
                                 index: 0,
                                 name: "0".into(),
                                 values: MovementGraph::default(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                             },
                             MovementGraphItem {
                                 index: 1,
                                 name: "0".into(),
                                 values: MovementGraph::default(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                             },
                         ],
                     },
@@ -396,12 +671,18 @@ mod test {
                 MovementGraphItem {
                     index: 0,
                     name: "0".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![MovementGraphItem {
                             index: 0,
                             name: "0".into(),
-                            item_type: GraphItemType::Section,
+                            item_type: GraphItemType::Section {
+                                counts: Counts::default(),
+                                meta: SectionMeta::default(),
+                            },
                             values: MovementGraph::default(),
                         }],
                     },
@@ -409,19 +690,28 @@ mod test {
                 MovementGraphItem {
                     index: 1,
                     name: "1".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![
                             MovementGraphItem {
                                 index: 0,
                                 name: "0".into(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                                 values: MovementGraph::default(),
                             },
                             MovementGraphItem {
                                 index: 1,
                                 name: "1".into(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                                 values: MovementGraph::default(),
                             },
                         ],
@@ -430,25 +720,37 @@ mod test {
                 MovementGraphItem {
                     index: 2,
                     name: "2".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![
                             MovementGraphItem {
                                 index: 0,
                                 name: "0".into(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                                 values: MovementGraph::default(),
                             },
                             MovementGraphItem {
                                 index: 1,
                                 name: "1".into(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                                 values: MovementGraph::default(),
                             },
                             MovementGraphItem {
                                 index: 2,
                                 name: "2".into(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                                 values: MovementGraph::default(),
                             },
                         ],
@@ -474,17 +776,26 @@ mod test {
                 MovementGraphItem {
                     index: 0,
                     name: "other".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![MovementGraphItem {
                             index: 0,
                             name: "other".into(),
-                            item_type: GraphItemType::Section,
+                            item_type: GraphItemType::Section {
+                                counts: Counts::default(),
+                                meta: SectionMeta::default(),
+                            },
                             values: MovementGraph {
                                 items: vec![MovementGraphItem {
                                     index: 0,
                                     name: "other".into(),
-                                    item_type: GraphItemType::Section,
+                                    item_type: GraphItemType::Section {
+                                        counts: Counts::default(),
+                                        meta: SectionMeta::default(),
+                                    },
                                     values: MovementGraph { items: vec![] },
                                 }],
                             },
@@ -494,25 +805,37 @@ mod test {
                 MovementGraphItem {
                     index: 1,
                     name: "some".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph { items: vec![] },
                 },
                 MovementGraphItem {
                     index: 2,
                     name: "something".into(),
-                    item_type: GraphItemType::Section,
+                    item_type: GraphItemType::Section {
+                        counts: Counts::default(),
+                        meta: SectionMeta::default(),
+                    },
                     values: MovementGraph {
                         items: vec![
                             MovementGraphItem {
                                 index: 0,
                                 name: "else".into(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                                 values: MovementGraph { items: vec![] },
                             },
                             MovementGraphItem {
                                 index: 1,
                                 name: "third".into(),
-                                item_type: GraphItemType::Section,
+                                item_type: GraphItemType::Section {
+                                    counts: Counts::default(),
+                                    meta: SectionMeta::default(),
+                                },
                                 values: MovementGraph { items: vec![] },
                             },
                         ],
@@ -1,7 +1,19 @@
+use hyperlog_core::log::ItemState;
 use ratatui::prelude::*;
 
 use crate::components::movement_graph::{GraphItemType, MovementGraph};
 
+fn checkbox(state: &ItemState, blocked: bool) -> &'static str {
+    match state {
+        _ if blocked && !state.is_closed() => "- [!]",
+        ItemState::NotDone => "- [ ]",
+        ItemState::InProgress => "- [~]",
+        ItemState::Blocked { .. } => "- [!]",
+        ItemState::Done => "- [x]",
+        ItemState::Cancelled => "- [-]",
+    }
+}
+
 #[allow(dead_code)]
 pub trait ClassicRenderGraph {
     fn render_graph(&self, items: &[usize]) -> Vec<ratatui::prelude::Line>;
@@ -17,15 +29,9 @@ impl ClassicRenderGraph for MovementGraph {
         let mut lines = Vec::new();
 
         for item in &self.items {
-            let prefix = match item.item_type {
-                GraphItemType::Section => "- ",
-                GraphItemType::Item { done } => {
-                    if done {
-                        "- [x]"
-                    } else {
-                        "- [ ]"
-                    }
-                }
+            let prefix = match &item.item_type {
+                GraphItemType::Section { .. } => "- ",
+                GraphItemType::Item { state, blocked, .. } => checkbox(state, *blocked),
             };
 
             match items.split_first().map(|(first, rest)| {
@@ -82,15 +88,9 @@ impl ClassicRenderGraph for MovementGraph {
         let mut lines = Vec::new();
 
         for item in &self.items {
-            let prefix = match item.item_type {
-                GraphItemType::Section => "-",
-                GraphItemType::Item { done } => {
-                    if done {
-                        "- [x]"
-                    } else {
-                        "- [ ]"
-                    }
-                }
+            let prefix = match &item.item_type {
+                GraphItemType::Section { .. } => "-",
+                GraphItemType::Item { state, blocked, .. } => checkbox(state, *blocked),
             };
             match items.split_first().map(|(first, rest)| {
                 if item.index == *first {
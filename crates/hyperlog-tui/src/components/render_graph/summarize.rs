@@ -1,9 +1,14 @@
 use crate::components::movement_graph::{GraphItemType, MovementGraph, MovementGraphItem};
+use hyperlog_core::{
+    log::{ItemState, Priority},
+    tags,
+};
 use itertools::Itertools;
 use ratatui::prelude::*;
 
 const GREEN: Color = Color::Rgb(127, 255, 0);
 const ORANGE: Color = Color::Rgb(255, 165, 0);
+const RED: Color = Color::Rgb(255, 69, 0);
 
 pub trait Summarize {
     fn heading(&self) -> Vec<Span>;
@@ -15,27 +20,102 @@ impl Summarize for MovementGraphItem {
     fn heading(&self) -> Vec<Span> {
         let name = Span::from(self.name.clone());
 
-        match self.item_type {
-            GraphItemType::Section => {
+        match &self.item_type {
+            GraphItemType::Section { counts, meta } => {
                 let items = self.values.items.len();
 
-                vec![
+                // A title stands in for the key, tinted with the accent colour.
+                let mut name = match meta.title.as_str() {
+                    "" => name,
+                    title => Span::from(title.to_string()),
+                };
+                if let Some((r, g, b)) = meta.rgb() {
+                    name = name.fg(Color::Rgb(r, g, b));
+                }
+
+                let mut heading = Vec::new();
+                if !meta.icon.is_empty() {
+                    heading.push(Span::from(format!("{} ", meta.icon)));
+                }
+                heading.extend([
                     name,
                     Span::from(" ~ ").fg(GREEN),
                     Span::from(format!("(items: {})", items)).fg(Color::DarkGray),
-                ]
+                ]);
+                if let Some(percent) = counts.percent_done() {
+                    let colour = match percent {
+                        100 => GREEN,
+                        _ if counts.overdue > 0 => RED,
+                        _ => Color::DarkGray,
+                    };
+                    heading.push(Span::from(format!(" {percent}% done")).fg(colour));
+                }
+                heading
             }
-            GraphItemType::Item { done } => {
-                if done {
-                    vec![
+            GraphItemType::Item {
+                state,
+                blocked,
+                tags,
+                priority,
+                deferred,
+                checklist: (done, total),
+            } => {
+                // Open items waiting on a dependency show as blocked too.
+                let waiting = *blocked && !state.is_closed();
+                let mark = match state {
+                    _ if waiting => Some(Span::from("!").fg(RED)),
+                    ItemState::NotDone => None,
+                    ItemState::InProgress => Some(Span::from("~").fg(ORANGE)),
+                    ItemState::Blocked { .. } => Some(Span::from("!").fg(RED)),
+                    ItemState::Done => Some(Span::from("x").fg(GREEN)),
+                    ItemState::Cancelled => Some(Span::from("-").fg(Color::DarkGray)),
+                };
+                let mut heading = match mark {
+                    Some(mark) => vec![
                         Span::from("[").fg(Color::DarkGray),
-                        Span::from("x").fg(GREEN),
+                        mark,
                         Span::from("] ").fg(Color::DarkGray),
-                        name,
-                    ]
-                } else {
-                    vec![Span::from("[ ] ").fg(Color::DarkGray), name]
+                        match state {
+                            ItemState::Cancelled => name.crossed_out().fg(Color::DarkGray),
+                            _ => name,
+                        },
+                    ],
+                    None => vec![Span::from("[ ] ").fg(Color::DarkGray), name],
+                };
+                if let Some(priority) = priority {
+                    let colour = match priority {
+                        Priority::P0 => RED,
+                        Priority::P1 => ORANGE,
+                        Priority::P2 | Priority::P3 => Color::DarkGray,
+                    };
+                    heading.insert(
+                        heading.len() - 1,
+                        Span::from(format!("{priority} ")).fg(colour),
+                    );
+                }
+                if *total > 0 {
+                    let colour = match done == total {
+                        true => GREEN,
+                        false => Color::DarkGray,
+                    };
+                    heading.push(Span::from(format!(" {done}/{total}")).fg(colour));
+                }
+                if let ItemState::Blocked { reason } = state {
+                    if !reason.is_empty() {
+                        heading.push(Span::from(format!(" ({reason})")).fg(Color::DarkGray));
+                    }
+                }
+                if waiting {
+                    heading.push(Span::from(" (waiting on dependencies)").fg(Color::DarkGray));
                 }
+                if *deferred {
+                    heading.push(Span::from(" (deferred)").fg(Color::DarkGray));
+                }
+                for tag in tags {
+                    heading
+                        .push(Span::from(format!(" {}", tags::display(tag))).fg(Color::DarkGray));
+                }
+                heading
             }
         }
     }
@@ -44,6 +124,13 @@ impl Summarize for MovementGraphItem {
         let heading = self.heading();
         let mut output = vec![heading];
 
+        // Collapsed sections only open up when selected.
+        if let GraphItemType::Section { meta, .. } = &self.item_type {
+            if meta.collapsed {
+                return output;
+            }
+        }
+
         let items = &self.values.items;
 
         let items = if items.len() > 2 {
@@ -82,6 +169,15 @@ impl Summarize for MovementGraphItem {
 
         let mut output = vec![heading];
 
+        if let GraphItemType::Section { meta, .. } = &self.item_type {
+            if let Some(line) = meta.description.lines().next() {
+                output.push(vec![
+                    Span::from(" ".repeat(4)),
+                    Span::from(line.to_string()).fg(Color::DarkGray),
+                ]);
+            }
+        }
+
         for item in items {
             for mut brief in item.brief() {
                 brief.insert(0, Span::from(" ".repeat(4)));
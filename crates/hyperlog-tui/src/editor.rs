@@ -9,7 +9,10 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode},
     ExecutableCommand,
 };
-use hyperlog_core::log::{GraphItem, ItemState};
+use hyperlog_core::{
+    checklist::ChecklistEntry,
+    log::{GraphItem, ItemState, Priority},
+};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
@@ -29,6 +32,21 @@ struct EditorFile {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct Metadata {
     state: ItemState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due: Option<String>,
+    /// Hidden until this `YYYY-MM-DD`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recur: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// `P0` to `P3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<Priority>,
+    /// One `[ ] step` or `[x] step` per entry, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    checklist: Vec<String>,
 }
 
 impl EditorFile {
@@ -53,11 +71,26 @@ impl TryFrom<&GraphItem> for EditorFile {
             title,
             description,
             state,
+            due,
+            start,
+            recur,
+            tags,
+            priority,
+            checklist,
+            ..
         } = value.clone()
         {
             Ok(Self {
                 title,
-                metadata: Metadata { state },
+                metadata: Metadata {
+                    state,
+                    due,
+                    start,
+                    recur,
+                    tags,
+                    priority,
+                    checklist: checklist.iter().map(ToString::to_string).collect(),
+                },
                 body: description,
             })
         } else {
@@ -79,6 +112,11 @@ impl TryFrom<&str> for EditorFile {
 
         tracing::trace!("parsing frontmatter content: {}", frontmatter_content);
         let metadata: Metadata = toml::from_str(frontmatter_content)?;
+        for entry in &metadata.checklist {
+            entry
+                .parse::<ChecklistEntry>()
+                .map_err(|e| anyhow!("invalid checklist entry {:?}: {}", entry, e))?;
+        }
 
         let line_parts = value.split("\n");
 
@@ -108,6 +146,22 @@ impl From<EditorFile> for GraphItem {
             title: value.title,
             description: value.body,
             state: value.metadata.state,
+            due: value.metadata.due,
+            start: value.metadata.start,
+            recur: value.metadata.recur,
+            // Kept by the engine when the edit is applied.
+            completions: Vec::new(),
+            completed_at: None,
+            depends_on: Vec::new(),
+            tags: value.metadata.tags,
+            priority: value.metadata.priority,
+            // Checked when the file was parsed.
+            checklist: value
+                .metadata
+                .checklist
+                .iter()
+                .filter_map(|entry| entry.parse().ok())
+                .collect(),
         }
     }
 }
@@ -1,7 +1,20 @@
 use std::{collections::BTreeMap, fmt::Display};
 
 use anyhow::{anyhow, Context};
-use hyperlog_core::log::{Graph, GraphItem, ItemState};
+use hyperlog_core::{
+    agenda::{Agenda, AgendaItem, MAX_ITEMS as MAX_AGENDA_ITEMS},
+    checklist::{self, ChecklistEntry},
+    completed::{self, Completed},
+    convert::{self, Kind},
+    deps::{self, Dependencies},
+    log::{Graph, GraphItem, ItemState, SectionMeta},
+    recur,
+    search::{Search, SearchHit, MAX_HITS},
+    standup::{Standup, StandupEntry},
+    stats::{self, Stats, StatsEntry},
+    tags, template, validate,
+    wiki_links::LinkResolver,
+};
 
 #[derive(Default)]
 pub struct Engine {
@@ -27,6 +40,36 @@ impl Engine {
         Ok(())
     }
 
+    pub fn rename_root(&mut self, root: &str, new_name: &str) -> anyhow::Result<()> {
+        if self.graph.contains_key(new_name) {
+            anyhow::bail!("root {} already exists", new_name);
+        }
+
+        let item = self
+            .graph
+            .remove(root)
+            .ok_or(anyhow!("root {} was not found", root))?;
+        self.graph.insert(new_name.to_string(), item);
+
+        Ok(())
+    }
+
+    /// Removes the root from the graph and hands back its tree, so the caller
+    /// can keep it around until it is purged.
+    pub fn delete_root(&mut self, root: &str) -> anyhow::Result<GraphItem> {
+        self.graph
+            .remove(root)
+            .ok_or(anyhow!("root {} was not found", root))
+    }
+
+    pub fn restore_root(&mut self, root: &str, item: GraphItem) -> anyhow::Result<()> {
+        self.graph
+            .try_insert(root.to_string(), item)
+            .map_err(|_| anyhow!("root {} already exists, rename it before restoring", root))?;
+
+        Ok(())
+    }
+
     pub fn create(&mut self, root: &str, path: &[&str], item: GraphItem) -> anyhow::Result<()> {
         let graph = &mut self.graph;
 
@@ -48,7 +91,7 @@ impl Engine {
                     None => anyhow::bail!("path: {} section was not found", section),
                 },
                 GraphItem::Item { .. } => anyhow::bail!("path: {} was already found", section),
-                GraphItem::Section(s) => match s.get_mut(section.to_owned()) {
+                GraphItem::Section { items: s, .. } => match s.get_mut(section.to_owned()) {
                     Some(graph_item) => {
                         current_item = graph_item;
                     }
@@ -61,7 +104,7 @@ impl Engine {
             GraphItem::User(u) => {
                 u.insert(last.to_string(), item);
             }
-            GraphItem::Section(s) => {
+            GraphItem::Section { items: s, .. } => {
                 s.insert(last.to_string(), item);
             }
             GraphItem::Item { .. } => anyhow::bail!("cannot insert an item into an item"),
@@ -88,53 +131,304 @@ impl Engine {
         root.take(path)
     }
 
+    /// Move the node at `src_path` into the section (or root) at `dest_path`
+    /// in `dest_root`, keeping its key. `dest_root` may be `root` itself.
     pub fn section_move(
         &mut self,
         root: &str,
         src_path: &[&str],
+        dest_root: &str,
         dest_path: &[&str],
     ) -> anyhow::Result<()> {
-        let src = self
+        let src_item = src_path
+            .last()
+            .ok_or(anyhow!("src path must have at least one item"))?;
+        if self.get(root, src_path).is_none() {
+            anyhow::bail!("failed to find source path");
+        }
+        if root == dest_root && dest_path.starts_with(src_path) {
+            anyhow::bail!("cannot move a node into its own subtree");
+        }
+
+        // Validate the destination before taking the source out of the graph.
+        match self.get(dest_root, dest_path) {
+            Some(GraphItem::User(s)) | Some(GraphItem::Section { items: s, .. }) => {
+                if s.contains_key(*src_item) {
+                    anyhow::bail!("key was already found, aborting: {}", src_item);
+                }
+            }
+            Some(GraphItem::Item { .. }) => {
+                anyhow::bail!("failed to insert src at item, item doesn't support arbitrary items")
+            }
+            None => anyhow::bail!("failed to find destination"),
+        }
+
+        let mut src = self
             .take(root, src_path)
             .ok_or(anyhow!("failed to find source path"))?;
+        let (src_key, dest_key) = (
+            src_path.join("."),
+            dest_path
+                .iter()
+                .chain(std::iter::once(src_item))
+                .copied()
+                .collect::<Vec<_>>()
+                .join("."),
+        );
 
-        let dest = self
-            .get_mut(root, dest_path)
-            .ok_or(anyhow!("failed to find destination"))?;
+        // Across roots only the edges within the moved subtree still make
+        // sense, the ones crossing it are dropped on both sides.
+        if root != dest_root {
+            rewrite_dependencies(&mut src, &|depends_on| {
+                deps::carry(depends_on, &src_key, &dest_key)
+            });
+            if let Some(root_item) = self.graph.get_mut(root) {
+                rewrite_dependencies(root_item, &|depends_on| deps::detach(depends_on, &src_key));
+            }
+        }
 
-        let src_item = src_path
-            .last()
-            .ok_or(anyhow!("src path must have at least one item"))?;
+        match self.get_mut(dest_root, dest_path) {
+            Some(GraphItem::User(s)) | Some(GraphItem::Section { items: s, .. }) => {
+                s.insert(src_item.to_string(), src);
+            }
+            _ => anyhow::bail!("failed to find destination"),
+        }
 
-        match dest {
-            GraphItem::User(u) => {
-                u.try_insert(src_item.to_string(), src)
-                    .map_err(|_e| anyhow!("key was already found, aborting: {}", src_item))?;
+        if root == dest_root {
+            if let Some(root_item) = self.graph.get_mut(root) {
+                retarget_dependencies(root_item, &src_key, &dest_key);
             }
-            GraphItem::Section(s) => {
-                s.try_insert(src_item.to_string(), src)
-                    .map_err(|_e| anyhow!("key was already found, aborting: {}", src_item))?;
+        }
+
+        Ok(())
+    }
+
+    /// Change the key of the node at `path` within its parent. With
+    /// `update_links`, `[[wiki-links]]` in the root that point at the node or
+    /// below it are rewritten to follow.
+    pub fn rename(
+        &mut self,
+        root: &str,
+        path: &[&str],
+        new_key: &str,
+        update_links: bool,
+    ) -> anyhow::Result<()> {
+        if new_key.is_empty() {
+            anyhow::bail!("new key must be non-empty");
+        }
+        if new_key.contains('.') {
+            anyhow::bail!("new key cannot contain '.': {}", new_key);
+        }
+        let (old_key, parent_path) = path
+            .split_last()
+            .ok_or(anyhow!("path must have at least one item"))?;
+        if *old_key == new_key {
+            return Ok(());
+        }
+
+        match self.get(root, parent_path) {
+            Some(GraphItem::User(s)) | Some(GraphItem::Section { items: s, .. }) => {
+                if !s.contains_key(*old_key) {
+                    anyhow::bail!("path: {}.{} was not found", root, path.join("."));
+                }
+                if s.contains_key(new_key) {
+                    anyhow::bail!("{} already exists in its parent", new_key);
+                }
             }
-            GraphItem::Item { .. } => {
-                anyhow::bail!("failed to insert src at item, item doesn't support arbitrary items")
+            _ => anyhow::bail!("path: {}.{} was not found", root, path.join(".")),
+        }
+
+        if update_links {
+            let src = path.join(".");
+            let dest = parent_path
+                .iter()
+                .copied()
+                .chain(std::iter::once(new_key))
+                .collect::<Vec<_>>()
+                .join(".");
+
+            let root_item = self
+                .graph
+                .get_mut(root)
+                .ok_or(anyhow!("root was missing a user, aborting"))?;
+            let mut nodes = Vec::new();
+            link_targets(root_item, "", &mut nodes);
+            retarget_links(root_item, &LinkResolver::new(nodes), &src, &dest);
+        }
+
+        if let Some(GraphItem::User(s)) | Some(GraphItem::Section { items: s, .. }) =
+            self.get_mut(root, parent_path)
+        {
+            if let Some(node) = s.remove(*old_key) {
+                s.insert(new_key.to_string(), node);
             }
         }
 
+        // Dependencies are structural, they follow the node regardless of
+        // `update_links`.
+        if let Some(root_item) = self.graph.get_mut(root) {
+            let dest = parent_path
+                .iter()
+                .copied()
+                .chain(std::iter::once(new_key))
+                .collect::<Vec<_>>()
+                .join(".");
+            retarget_dependencies(root_item, &path.join("."), &dest);
+        }
+
+        Ok(())
+    }
+
+    /// Clone the node at `src_path` and everything below it to `dest_path` in
+    /// `dest_root`, which may be `root` itself.
+    pub fn copy(
+        &mut self,
+        root: &str,
+        src_path: &[&str],
+        dest_root: &str,
+        dest_path: &[&str],
+        reset_state: bool,
+        drop_due: bool,
+    ) -> anyhow::Result<()> {
+        if dest_path.is_empty() {
+            anyhow::bail!("dest path must have at least one item");
+        }
+        let mut item = self
+            .get(root, src_path)
+            .filter(|_| !src_path.is_empty())
+            .cloned()
+            .ok_or(anyhow!("failed to find source path"))?;
+        if self.get(dest_root, dest_path).is_some() {
+            anyhow::bail!("destination already exists: {}", dest_path.join("."));
+        }
+        match dest_path
+            .split_last()
+            .and_then(|(_, parent)| self.get(dest_root, parent))
+        {
+            Some(GraphItem::User(_)) | Some(GraphItem::Section { .. }) => {}
+            _ => anyhow::bail!("destination parent section not found"),
+        }
+
+        if reset_state || drop_due {
+            reset_items(&mut item, reset_state, drop_due);
+        }
+        // The copy depends on its own copied items, and on nothing outside it
+        // once it is in another root.
+        let (src_key, dest_key) = (src_path.join("."), dest_path.join("."));
+        if root == dest_root {
+            retarget_dependencies(&mut item, &src_key, &dest_key);
+        } else {
+            rewrite_dependencies(&mut item, &|depends_on| {
+                deps::carry(depends_on, &src_key, &dest_key)
+            });
+        }
+
+        self.create(dest_root, dest_path, item)
+    }
+
+    /// Instantiate the template called `name` as a new section at
+    /// `dest_path`, substituting `{{variables}}` in the destination, keys,
+    /// titles, descriptions and dues. Relative dues (`+3d`) count from
+    /// `anchor` (`YYYY-MM-DD`), today when `None`.
+    pub fn apply_template(
+        &mut self,
+        root: &str,
+        name: &str,
+        dest_path: &[&str],
+        vars: &BTreeMap<String, String>,
+        anchor: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let anchor = template::anchor(anchor)?;
+        let template_path = template::template_path(name);
+        let item = match self.get(
+            root,
+            &template_path.iter().map(|p| p.as_str()).collect::<Vec<_>>(),
+        ) {
+            Some(item @ GraphItem::Section { .. }) => render_template(item, vars, anchor)?,
+            _ => anyhow::bail!("template not found: {}", name),
+        };
+        let dest_path = dest_path
+            .iter()
+            .map(|key| template::substitute_key(key, vars))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let dest_path = dest_path.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+
+        if dest_path.is_empty() {
+            anyhow::bail!("dest path must have at least one item");
+        }
+        if self.get(root, &dest_path).is_some() {
+            anyhow::bail!("destination already exists: {}", dest_path.join("."));
+        }
+        match dest_path
+            .split_last()
+            .and_then(|(_, parent)| self.get(root, parent))
+        {
+            Some(GraphItem::User(_)) | Some(GraphItem::Section { .. }) => {}
+            _ => anyhow::bail!("destination parent section not found"),
+        }
+
+        self.create(root, &dest_path, item)
+    }
+
+    /// Turn the node at `path` into `kind` in place, see [`convert`].
+    pub fn convert(&mut self, root: &str, path: &[&str], kind: Kind) -> anyhow::Result<()> {
+        let Some(key) = path.last() else {
+            anyhow::bail!("a root can't be converted");
+        };
+        let node = self
+            .get_mut(root, path)
+            .ok_or(anyhow!("node was not found: {}", path.join(".")))?;
+        *node = convert::convert(key, node, kind)?;
+
         Ok(())
     }
 
+    /// Replace the metadata of the section at `path`.
+    pub fn update_section(
+        &mut self,
+        root: &str,
+        path: &[&str],
+        meta: SectionMeta,
+    ) -> anyhow::Result<()> {
+        validate::section(&meta)?;
+
+        match self.get_mut(root, path) {
+            Some(GraphItem::Section { meta: current, .. }) => {
+                *current = meta;
+                Ok(())
+            }
+            Some(_) => anyhow::bail!("{} is not a section", path.join(".")),
+            None => anyhow::bail!("section was not found: {}", path.join(".")),
+        }
+    }
+
     pub fn delete(&mut self, root: &str, path: &[&str]) -> anyhow::Result<()> {
         self.take(root, path)
             .map(|_| ())
             .ok_or(anyhow!("item was not found"))
     }
 
-    pub fn toggle_item(&mut self, root: &str, path: &[&str]) -> anyhow::Result<()> {
+    /// Flip an item between done and not done. Completing a recurring item
+    /// instead records the completion on `today` and moves it to its next
+    /// occurrence, see [`recur::complete`].
+    pub fn toggle_item(&mut self, root: &str, path: &[&str], today: i64) -> anyhow::Result<()> {
         if let Some(item) = self.get_mut(root, path) {
             match item {
-                GraphItem::Item { state, .. } => match state {
-                    ItemState::NotDone => *state = ItemState::Done,
-                    ItemState::Done => *state = ItemState::NotDone,
+                GraphItem::Item {
+                    state,
+                    due,
+                    recur,
+                    completions,
+                    completed_at,
+                    ..
+                } => match recur {
+                    Some(rule) if !state.is_closed() => {
+                        recur::complete(rule, due, completions, today)?
+                    }
+                    _ => {
+                        *state = state.toggled();
+                        completed::track(state, completed_at, completed::now);
+                    }
                 },
                 _ => {
                     anyhow::bail!("{}.{:?} is not an item", root, path)
@@ -145,6 +439,126 @@ impl Engine {
         Ok(())
     }
 
+    /// Flip the checklist entry at `index`, counted from 0.
+    pub fn toggle_checklist_entry(
+        &mut self,
+        root: &str,
+        path: &[&str],
+        index: usize,
+    ) -> anyhow::Result<()> {
+        match self.get_mut(root, path) {
+            Some(GraphItem::Item { checklist, .. }) => {
+                if !checklist::toggle(checklist, index) {
+                    anyhow::bail!("item has no checklist entry {}", index + 1)
+                }
+            }
+            Some(_) => anyhow::bail!("{}.{:?} is not an item", root, path),
+            None => anyhow::bail!("no item at {}.{:?}", root, path),
+        }
+
+        Ok(())
+    }
+
+    /// Set the item's state. Setting a recurring item done completes the
+    /// occurrence on `today` instead, like [`Engine::toggle_item`].
+    pub fn set_item_state(
+        &mut self,
+        root: &str,
+        path: &[&str],
+        new_state: ItemState,
+        today: i64,
+    ) -> anyhow::Result<()> {
+        match self.get_mut(root, path) {
+            Some(GraphItem::Item {
+                state,
+                due,
+                recur,
+                completions,
+                completed_at,
+                ..
+            }) => match recur {
+                // Done on a recurring item completes the occurrence, as toggling does.
+                Some(rule) if new_state == ItemState::Done && !state.is_closed() => {
+                    recur::complete(rule, due, completions, today)
+                }
+                _ => {
+                    *state = new_state;
+                    completed::track(state, completed_at, completed::now);
+                    Ok(())
+                }
+            },
+            Some(_) => anyhow::bail!("{}.{:?} is not an item", root, path),
+            None => anyhow::bail!("item was not found"),
+        }
+    }
+
+    /// Defer the item until `start`, or stop deferring it when `None`.
+    pub fn snooze_item(
+        &mut self,
+        root: &str,
+        path: &[&str],
+        new_start: Option<String>,
+    ) -> anyhow::Result<()> {
+        match self.get_mut(root, path) {
+            Some(GraphItem::Item { start, .. }) => {
+                *start = new_start;
+                Ok(())
+            }
+            Some(_) => anyhow::bail!("{}.{:?} is not an item", root, path),
+            None => anyhow::bail!("item was not found"),
+        }
+    }
+
+    /// Make the item at `path` wait on the item at `dependency`, both in
+    /// `root`. Edges that would close a cycle are rejected.
+    pub fn add_dependency(
+        &mut self,
+        root: &str,
+        path: &[&str],
+        dependency: &[&str],
+    ) -> anyhow::Result<()> {
+        let root_item = self
+            .graph
+            .get(root)
+            .ok_or(anyhow!("root was not found: {}", root))?;
+        let deps = Dependencies::from_graph(root_item);
+        let (item, dependency) = (path.join("."), dependency.join("."));
+        if !deps.contains(&item) {
+            anyhow::bail!("item was not found: {}", item);
+        }
+        if !deps.contains(&dependency) {
+            anyhow::bail!("dependency was not found: {}", dependency);
+        }
+        if deps.would_cycle(&item, &dependency) {
+            anyhow::bail!("{} depending on {} would create a cycle", item, dependency);
+        }
+
+        if let Some(GraphItem::Item { depends_on, .. }) = self.get_mut(root, path) {
+            if !depends_on.contains(&dependency) {
+                depends_on.push(dependency);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_dependency(
+        &mut self,
+        root: &str,
+        path: &[&str],
+        dependency: &[&str],
+    ) -> anyhow::Result<()> {
+        match self.get_mut(root, path) {
+            Some(GraphItem::Item { depends_on, .. }) => {
+                let dependency = dependency.join(".");
+                depends_on.retain(|d| *d != dependency);
+                Ok(())
+            }
+            Some(_) => anyhow::bail!("{}.{:?} is not an item", root, path),
+            None => anyhow::bail!("item was not found"),
+        }
+    }
+
     pub fn update_item(
         &mut self,
         root: &str,
@@ -154,7 +568,7 @@ impl Engine {
         if let Some((name, dest_last)) = path.split_last() {
             if let Some(parent) = self.get_mut(root, dest_last) {
                 match parent {
-                    GraphItem::User(s) | GraphItem::Section(s) => {
+                    GraphItem::User(s) | GraphItem::Section { items: s, .. } => {
                         if let Some(mut existing) = s.remove(*name) {
                             match (&mut existing, item) {
                                 (
@@ -162,19 +576,60 @@ impl Engine {
                                         title: ex_title,
                                         description: ex_desc,
                                         state: ex_state,
+                                        completed_at: ex_completed_at,
+                                        due: ex_due,
+                                        start: ex_start,
+                                        recur: ex_recur,
+                                        tags: ex_tags,
+                                        priority: ex_priority,
+                                        checklist: ex_checklist,
+                                        ..
                                     },
                                     GraphItem::Item {
                                         title,
                                         description,
                                         state,
+                                        due,
+                                        start,
+                                        recur,
+                                        tags,
+                                        priority,
+                                        checklist,
+                                        ..
                                     },
                                 ) => {
+                                    // Completions are history and dependencies have their own
+                                    // commands, neither is something an edit replaces.
                                     ex_title.clone_from(title);
                                     ex_desc.clone_from(description);
                                     ex_state.clone_from(state);
+                                    completed::track(ex_state, ex_completed_at, completed::now);
+                                    ex_due.clone_from(due);
+                                    ex_start.clone_from(start);
+                                    ex_recur.clone_from(recur);
+                                    *ex_tags = tags::normalize_all(tags);
+                                    *ex_priority = *priority;
+                                    ex_checklist.clone_from(checklist);
 
                                     let title = title.replace(".", "-");
-                                    s.insert(title, existing.clone());
+                                    s.insert(title.clone(), existing.clone());
+
+                                    // The key follows the title, so the item may have moved.
+                                    if title != *name {
+                                        let dest = dest_last
+                                            .iter()
+                                            .copied()
+                                            .chain(std::iter::once(title.as_str()))
+                                            .collect::<Vec<_>>()
+                                            .join(".");
+                                        if let Some(root_item) = self.graph.get_mut(root) {
+                                            retarget_dependencies(
+                                                root_item,
+                                                &path.join("."),
+                                                &dest,
+                                            );
+                                        }
+                                    }
                                 }
                                 _ => {
                                     anyhow::bail!(
@@ -205,6 +660,196 @@ impl Engine {
         }
     }
 
+    /// Not-done items with a due date in `root`, or in every root when
+    /// `None`, bucketed relative to `today` (days since 1970-01-01). At most
+    /// [`MAX_AGENDA_ITEMS`] of them, the soonest due first.
+    pub fn agenda(&self, root: Option<&str>, today: i64) -> Agenda {
+        let mut agenda = Agenda::default();
+        for (name, item) in self.graph.iter() {
+            if root.is_some_and(|root| root != name) {
+                continue;
+            }
+            agenda_items(name, item, &mut Vec::new(), today, &mut agenda);
+        }
+        agenda.sort();
+        agenda.truncate(MAX_AGENDA_ITEMS);
+
+        agenda
+    }
+
+    /// Items completed between `since` and `until` (days since 1970-01-01) in
+    /// `root`, or in every root when `None`, most recent first.
+    pub fn completed(&self, root: Option<&str>, since: i64, until: i64) -> Completed {
+        let mut completed = Completed::new(since, until);
+        for (name, item) in self.graph.iter() {
+            if root.is_some_and(|root| root != name) {
+                continue;
+            }
+            walk_items(item, &mut Vec::new(), &mut |path, item| {
+                if let GraphItem::Item {
+                    title,
+                    completions,
+                    completed_at,
+                    ..
+                } = item
+                {
+                    completed.push(name, path, title, completed_at.as_deref(), completions);
+                }
+            });
+        }
+        completed.sort();
+
+        completed
+    }
+
+    /// The standup for `today` (days since 1970-01-01) over `root`, or over
+    /// every root when `None`. Items here have no creation time, so only those
+    /// in progress are listed as still open.
+    pub fn standup(&self, root: Option<&str>, today: i64) -> Standup {
+        let mut standup = Standup::new(today);
+        for (name, item) in self.graph.iter() {
+            if root.is_some_and(|root| root != name) {
+                continue;
+            }
+            let deps = Dependencies::from_graph(item);
+            walk_items(item, &mut Vec::new(), &mut |path, item| {
+                if let GraphItem::Item {
+                    title,
+                    state,
+                    due,
+                    completions,
+                    completed_at,
+                    ..
+                } = item
+                {
+                    standup.push(StandupEntry {
+                        root: name,
+                        path,
+                        title,
+                        state,
+                        due: due.as_deref(),
+                        waiting_on: deps.open_dependencies(&path.join(".")),
+                        created_unix: None,
+                        completed_at: completed_at.as_deref(),
+                        completions,
+                        archived: false,
+                    });
+                }
+            });
+        }
+        standup.sort();
+
+        standup
+    }
+
+    /// Progress of the root or section at `path` and of every section below
+    /// it, with a series for the days `since` through `today` (days since
+    /// 1970-01-01). Items here have no creation time, so none count as created.
+    pub fn stats(
+        &self,
+        root: &str,
+        path: &[&str],
+        since: i64,
+        today: i64,
+    ) -> anyhow::Result<Stats> {
+        stats::check_range(since, today)?;
+
+        fn walk(item: &GraphItem, path: &mut Vec<String>, stats: &mut Stats) {
+            match item {
+                GraphItem::User(children)
+                | GraphItem::Section {
+                    items: children, ..
+                } => {
+                    for (key, child) in children {
+                        path.push(key.clone());
+                        if let GraphItem::Section { .. } = child {
+                            stats.add_section(path.clone());
+                        }
+                        walk(child, path, stats);
+                        path.pop();
+                    }
+                }
+                GraphItem::Item {
+                    state,
+                    due,
+                    completed_at,
+                    ..
+                } => stats.push(StatsEntry {
+                    path,
+                    state,
+                    due: due.as_deref(),
+                    created_unix: None,
+                    completed_at: completed_at.as_deref(),
+                }),
+            }
+        }
+
+        let item = self
+            .get(root, path)
+            .filter(|item| !matches!(item, GraphItem::Item { .. }))
+            .ok_or(anyhow!("no root or section at {}.{}", root, path.join(".")))?;
+        let mut path = path.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        let mut stats = Stats::new(path.clone(), since, today);
+        walk(item, &mut path, &mut stats);
+        stats.finish();
+
+        Ok(stats)
+    }
+
+    /// Tags in use in `root`, or in every root when `None`, with how many items
+    /// carry each.
+    pub fn tags(&self, root: Option<&str>) -> Vec<(String, i64)> {
+        let mut items = Vec::new();
+        for (name, item) in self.graph.iter() {
+            if root.is_some_and(|root| root != name) {
+                continue;
+            }
+            walk_items(item, &mut Vec::new(), &mut |_, item| {
+                if let GraphItem::Item {
+                    description, tags, ..
+                } = item
+                {
+                    items.push(tags::item_tags(tags, description));
+                }
+            });
+        }
+
+        tags::count(items)
+    }
+
+    /// Items matching `search` in `root`, or in every root when `None`, in root
+    /// and path order. At most [`MAX_HITS`] of them.
+    pub fn search(&self, root: Option<&str>, search: &Search) -> Vec<SearchHit> {
+        let mut hits = Vec::new();
+        for (name, item) in self.graph.iter() {
+            if root.is_some_and(|root| root != name) {
+                continue;
+            }
+            walk_items(item, &mut Vec::new(), &mut |path, item| {
+                if let GraphItem::Item {
+                    title,
+                    description,
+                    state,
+                    tags,
+                    ..
+                } = item
+                {
+                    if hits.len() < MAX_HITS && search.matches(title, description, tags) {
+                        hits.push(SearchHit {
+                            root: name.clone(),
+                            path: path.to_vec(),
+                            title: title.clone(),
+                            done: *state == ItemState::Done,
+                            tags: tags::item_tags(tags, description),
+                        });
+                    }
+                }
+            });
+        }
+
+        hits
+    }
+
     pub fn archive(&mut self, root: &str, path: &[&str]) -> anyhow::Result<()> {
         self.delete(root, path)?;
 
@@ -212,6 +857,214 @@ impl Engine {
     }
 }
 
+/// Call `f` with every item below `item` and its path, in path order.
+fn walk_items(item: &GraphItem, path: &mut Vec<String>, f: &mut impl FnMut(&[String], &GraphItem)) {
+    match item {
+        GraphItem::User(children)
+        | GraphItem::Section {
+            items: children, ..
+        } => {
+            for (key, child) in children {
+                path.push(key.clone());
+                walk_items(child, path, f);
+                path.pop();
+            }
+        }
+        GraphItem::Item { .. } => f(path, item),
+    }
+}
+
+/// File the open items below `item` that have a due into `agenda`.
+fn agenda_items(
+    root: &str,
+    item: &GraphItem,
+    path: &mut Vec<String>,
+    today: i64,
+    agenda: &mut Agenda,
+) {
+    match item {
+        GraphItem::User(children)
+        | GraphItem::Section {
+            items: children, ..
+        } => {
+            for (key, child) in children {
+                path.push(key.clone());
+                agenda_items(root, child, path, today, agenda);
+                path.pop();
+            }
+        }
+        GraphItem::Item {
+            title,
+            state,
+            due: Some(due),
+            ..
+        } if !state.is_closed() => agenda.push(
+            AgendaItem {
+                root: root.to_string(),
+                path: path.clone(),
+                title: title.clone(),
+                due: due.clone(),
+            },
+            today,
+        ),
+        GraphItem::Item { .. } => {}
+    }
+}
+
+/// (dotted path, display name) of every node below `item`, in path order.
+fn link_targets(item: &GraphItem, prefix: &str, out: &mut Vec<(String, String)>) {
+    if let GraphItem::User(children)
+    | GraphItem::Section {
+        items: children, ..
+    } = item
+    {
+        for (key, child) in children {
+            let path = match prefix {
+                "" => key.clone(),
+                prefix => format!("{prefix}.{key}"),
+            };
+            let name = match child {
+                GraphItem::Item { title, .. } if !title.trim().is_empty() => {
+                    title.trim().to_string()
+                }
+                _ => key.clone(),
+            };
+            out.push((path.clone(), name));
+            link_targets(child, &path, out);
+        }
+    }
+}
+
+fn retarget_links(item: &mut GraphItem, resolver: &LinkResolver, src: &str, dest: &str) {
+    match item {
+        GraphItem::User(children)
+        | GraphItem::Section {
+            items: children, ..
+        } => {
+            for child in children.values_mut() {
+                retarget_links(child, resolver, src, dest);
+            }
+        }
+        GraphItem::Item { description, .. } => {
+            if description.contains("[[") {
+                *description = resolver.retarget(description, src, dest);
+            }
+        }
+    }
+}
+
+fn rewrite_dependencies(item: &mut GraphItem, rewrite: &impl Fn(&mut Vec<String>) -> bool) {
+    match item {
+        GraphItem::User(children)
+        | GraphItem::Section {
+            items: children, ..
+        } => {
+            for child in children.values_mut() {
+                rewrite_dependencies(child, rewrite);
+            }
+        }
+        GraphItem::Item { depends_on, .. } => {
+            rewrite(depends_on);
+        }
+    }
+}
+
+fn retarget_dependencies(item: &mut GraphItem, src: &str, dest: &str) {
+    rewrite_dependencies(item, &|depends_on| deps::retarget(depends_on, src, dest));
+}
+
+fn reset_items(item: &mut GraphItem, reset_state: bool, drop_due: bool) {
+    match item {
+        GraphItem::User(children)
+        | GraphItem::Section {
+            items: children, ..
+        } => {
+            for child in children.values_mut() {
+                reset_items(child, reset_state, drop_due);
+            }
+        }
+        GraphItem::Item {
+            state,
+            due,
+            completions,
+            completed_at,
+            checklist,
+            ..
+        } => {
+            if reset_state {
+                *state = ItemState::NotDone;
+                completions.clear();
+                *completed_at = None;
+                checklist::reset(checklist);
+            }
+            if drop_due {
+                *due = None;
+            }
+        }
+    }
+}
+
+fn render_template(
+    item: &GraphItem,
+    vars: &BTreeMap<String, String>,
+    anchor: i64,
+) -> anyhow::Result<GraphItem> {
+    let render_children = |children: &BTreeMap<String, GraphItem>| {
+        let mut rendered = BTreeMap::new();
+        for (key, child) in children {
+            let key = template::substitute_key(key, vars)?;
+            if rendered
+                .insert(key.clone(), render_template(child, vars, anchor)?)
+                .is_some()
+            {
+                anyhow::bail!("template renders two nodes with the same key: {}", key);
+            }
+        }
+        Ok(rendered)
+    };
+
+    Ok(match item {
+        GraphItem::User(children) => GraphItem::User(render_children(children)?),
+        GraphItem::Section { items, meta } => GraphItem::Section {
+            meta: template::render_meta(meta, vars)?,
+            items: render_children(items)?,
+        },
+        GraphItem::Item {
+            title,
+            description,
+            due,
+            start,
+            recur,
+            tags,
+            priority,
+            checklist,
+            ..
+        } => GraphItem::Item {
+            title: template::substitute(title, vars)?,
+            description: template::substitute(description, vars)?,
+            state: ItemState::NotDone,
+            due: due
+                .as_deref()
+                .map(|due| template::render_due(due, vars, anchor))
+                .transpose()?,
+            start: start
+                .as_deref()
+                .map(|start| template::render_due(start, vars, anchor))
+                .transpose()?,
+            recur: recur.clone(),
+            completions: Vec::new(),
+            completed_at: None,
+            depends_on: Vec::new(),
+            tags: tags.clone(),
+            priority: *priority,
+            checklist: checklist
+                .iter()
+                .map(|entry| ChecklistEntry::new(entry.title.clone()))
+                .collect(),
+        },
+    })
+}
+
 impl Display for Engine {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let output = serde_json::to_string_pretty(&self.graph).unwrap();
@@ -223,7 +1076,14 @@ impl Display for Engine {
 mod test {
     use std::collections::BTreeMap;
 
-    use hyperlog_core::log::{GraphItem, ItemState};
+    use hyperlog_core::{
+        checklist::{self, ChecklistEntry},
+        convert::Kind,
+        deps::Dependencies,
+        due,
+        log::{GraphItem, ItemState, SectionMeta},
+        search::Search,
+    };
     use similar_asserts::assert_eq;
 
     use super::Engine;
@@ -253,7 +1113,7 @@ mod test {
             .create(
                 "kjuulh",
                 &["some-section"],
-                GraphItem::Section(BTreeMap::default()),
+                GraphItem::section(BTreeMap::default()),
             )
             .unwrap();
 
@@ -279,7 +1139,7 @@ mod test {
             .create(
                 "kjuulh",
                 &["some-section"],
-                GraphItem::Section(BTreeMap::default()),
+                GraphItem::section(BTreeMap::default()),
             )
             .unwrap();
 
@@ -287,7 +1147,7 @@ mod test {
             .create(
                 "kjuulh",
                 &["some-section", "some-sub-section"],
-                GraphItem::Section(BTreeMap::default()),
+                GraphItem::section(BTreeMap::default()),
             )
             .unwrap();
 
@@ -316,11 +1176,11 @@ mod test {
             .create(
                 "kjuulh",
                 &["some-item"],
-                GraphItem::Item {
-                    title: "some-title".to_string(),
-                    description: "some-description".to_string(),
-                    state: ItemState::NotDone,
-                },
+                GraphItem::item(
+                    "some-title".to_string(),
+                    "some-description".to_string(),
+                    ItemState::NotDone,
+                ),
             )
             .unwrap();
 
@@ -349,18 +1209,18 @@ mod test {
             .create(
                 "kjuulh",
                 &["some-section"],
-                GraphItem::Section(BTreeMap::default()),
+                GraphItem::section(BTreeMap::default()),
             )
             .unwrap();
         engine
             .create(
                 "kjuulh",
                 &["some-section", "some-item"],
-                GraphItem::Item {
-                    title: "some-title".to_string(),
-                    description: "some-description".to_string(),
-                    state: ItemState::NotDone,
-                },
+                GraphItem::item(
+                    "some-title".to_string(),
+                    "some-description".to_string(),
+                    ItemState::NotDone,
+                ),
             )
             .unwrap();
 
@@ -392,21 +1252,21 @@ mod test {
             .create(
                 "kjuulh",
                 &["some-section"],
-                GraphItem::Section(BTreeMap::default()),
+                GraphItem::section(BTreeMap::default()),
             )
             .unwrap();
         engine
             .create(
                 "kjuulh",
                 &["some-section", "some-sub-section"],
-                GraphItem::Section(BTreeMap::default()),
+                GraphItem::section(BTreeMap::default()),
             )
             .unwrap();
         engine
             .create(
                 "kjuulh",
                 &["some-section", "some-sub-section", "sub-sub-section"],
-                GraphItem::Section(BTreeMap::default()),
+                GraphItem::section(BTreeMap::default()),
             )
             .unwrap();
         engine
@@ -418,11 +1278,11 @@ mod test {
                     "sub-sub-section",
                     "some-item",
                 ],
-                GraphItem::Item {
-                    title: "some-title".to_string(),
-                    description: "some-description".to_string(),
-                    state: ItemState::NotDone,
-                },
+                GraphItem::item(
+                    "some-title".to_string(),
+                    "some-description".to_string(),
+                    ItemState::NotDone,
+                ),
             )
             .unwrap();
 
@@ -601,6 +1461,7 @@ mod test {
                     "sub-sub-section",
                     "some-item",
                 ],
+                "kjuulh",
                 &["some-section"],
             )
             .unwrap();
@@ -637,6 +1498,7 @@ mod test {
             .section_move(
                 "kjuulh",
                 &["some-section", "some-sub-section", "sub-sub-section"],
+                "kjuulh",
                 &["some-section"],
             )
             .unwrap();
@@ -719,33 +1581,760 @@ mod test {
         );
     }
 
-    fn get_complex_graph() -> Engine {
-        let mut engine = Engine::default();
+    #[test]
+    fn test_can_rename_root() {
+        let mut engine = get_complex_graph();
+        engine.create_root("other").unwrap();
 
-        engine.create_root("kjuulh").unwrap();
-        engine
-            .create(
-                "kjuulh",
-                &["some-section"],
-                GraphItem::Section(BTreeMap::default()),
-            )
-            .unwrap();
-        engine
+        assert!(engine.rename_root("kjuulh", "other").is_err());
+        assert!(engine.rename_root("missing", "new").is_err());
+
+        engine.rename_root("kjuulh", "renamed").unwrap();
+
+        assert!(engine.get("kjuulh", &[]).is_none());
+        assert!(engine
+            .get(
+                "renamed",
+                &[
+                    "some-section",
+                    "some-sub-section",
+                    "sub-sub-section",
+                    "some-item"
+                ]
+            )
+            .is_some());
+    }
+
+    #[test]
+    fn test_can_delete_and_restore_root() {
+        let mut engine = get_complex_graph();
+
+        let deleted = engine.delete_root("kjuulh").unwrap();
+
+        assert_eq!(None, engine.get_roots());
+        assert!(engine.delete_root("kjuulh").is_err());
+
+        engine.restore_root("kjuulh", deleted).unwrap();
+
+        assert_eq!(get_complex_graph().to_string(), engine.to_string());
+    }
+
+    #[test]
+    fn test_can_rename_section() {
+        let mut engine = get_complex_graph();
+        engine
             .create(
+                "kjuulh",
+                &["other-section"],
+                GraphItem::section(BTreeMap::default()),
+            )
+            .unwrap();
+
+        assert!(engine
+            .rename("kjuulh", &["some-section"], "other-section", false)
+            .is_err());
+        assert!(engine
+            .rename("kjuulh", &["some-section"], "with.dot", false)
+            .is_err());
+        assert!(engine
+            .rename("kjuulh", &["missing"], "renamed", false)
+            .is_err());
+
+        engine
+            .rename(
                 "kjuulh",
                 &["some-section", "some-sub-section"],
-                GraphItem::Section(BTreeMap::default()),
+                "renamed",
+                false,
             )
             .unwrap();
+
+        assert!(engine
+            .get("kjuulh", &["some-section", "some-sub-section"])
+            .is_none());
+        assert!(engine
+            .get(
+                "kjuulh",
+                &["some-section", "renamed", "sub-sub-section", "some-item"]
+            )
+            .is_some());
+    }
+
+    #[test]
+    fn test_rename_updates_links() {
+        let mut engine = get_complex_graph();
         engine
             .create(
+                "kjuulh",
+                &["notes"],
+                GraphItem::item("notes".to_string(), "[[some-sub-section]] [[some-section/some-sub-section/sub-sub-section|deep]] [[some-title]]".to_string(), ItemState::NotDone),
+            )
+            .unwrap();
+
+        engine
+            .rename(
+                "kjuulh",
+                &["some-section", "some-sub-section"],
+                "renamed",
+                true,
+            )
+            .unwrap();
+
+        assert_eq!(
+            Some(&GraphItem::item(
+                "notes".to_string(),
+                "[[renamed]] [[some-section/renamed/sub-sub-section|deep]] [[some-title]]"
+                    .to_string(),
+                ItemState::NotDone
+            )),
+            engine.get("kjuulh", &["notes"])
+        );
+    }
+
+    #[test]
+    fn test_can_copy_section_across_roots() {
+        let mut engine = get_complex_graph();
+        engine.create_root("other").unwrap();
+        engine
+            .toggle_item(
+                "kjuulh",
+                &[
+                    "some-section",
+                    "some-sub-section",
+                    "sub-sub-section",
+                    "some-item",
+                ],
+                0,
+            )
+            .unwrap();
+
+        engine
+            .copy(
+                "kjuulh",
+                &["some-section", "some-sub-section"],
+                "other",
+                &["copied"],
+                true,
+                false,
+            )
+            .unwrap();
+
+        assert!(engine
+            .copy(
+                "kjuulh",
+                &["some-section", "some-sub-section"],
+                "other",
+                &["copied"],
+                true,
+                false,
+            )
+            .is_err());
+        assert!(engine
+            .copy(
+                "kjuulh",
+                &["some-section"],
+                "other",
+                &["missing", "x"],
+                true,
+                false
+            )
+            .is_err());
+        assert!(matches!(
+            engine.get(
+                "kjuulh",
+                &[
+                    "some-section",
+                    "some-sub-section",
+                    "sub-sub-section",
+                    "some-item"
+                ]
+            ),
+            Some(GraphItem::Item {
+                state: ItemState::Done,
+                completed_at: Some(_),
+                ..
+            })
+        ));
+        assert_eq!(
+            Some(&GraphItem::item(
+                "some-title".to_string(),
+                "some-description".to_string(),
+                ItemState::NotDone
+            )),
+            engine.get("other", &["copied", "sub-sub-section", "some-item"])
+        );
+    }
+
+    #[test]
+    fn test_can_move_section_across_roots() {
+        let mut engine = get_complex_graph();
+        engine.create_root("other").unwrap();
+        engine
+            .create(
+                "other",
+                &["some-sub-section"],
+                GraphItem::section(BTreeMap::default()),
+            )
+            .unwrap();
+
+        assert!(engine
+            .section_move(
+                "kjuulh",
+                &["some-section", "some-sub-section"],
+                "other",
+                &[],
+            )
+            .is_err());
+        assert!(engine
+            .section_move(
+                "kjuulh",
+                &["some-section"],
+                "kjuulh",
+                &["some-section", "some-sub-section"],
+            )
+            .is_err());
+        assert!(engine
+            .get("kjuulh", &["some-section", "some-sub-section"])
+            .is_some());
+
+        engine
+            .section_move(
                 "kjuulh",
                 &["some-section", "some-sub-section", "sub-sub-section"],
-                GraphItem::Section(BTreeMap::default()),
+                "other",
+                &["some-sub-section"],
+            )
+            .unwrap();
+
+        assert_eq!(
+            r#"{
+  "kjuulh": {
+    "type": "user",
+    "some-section": {
+      "type": "section",
+      "some-sub-section": {
+        "type": "section"
+      }
+    }
+  },
+  "other": {
+    "type": "user",
+    "some-sub-section": {
+      "type": "section",
+      "sub-sub-section": {
+        "type": "section",
+        "some-item": {
+          "type": "item",
+          "title": "some-title",
+          "description": "some-description",
+          "state": "not-done"
+        }
+      }
+    }
+  }
+}"#,
+            engine.to_string()
+        );
+    }
+
+    #[test]
+    fn test_can_apply_template() {
+        let mut engine = get_complex_graph();
+        engine
+            .create(
+                "kjuulh",
+                &["templates"],
+                GraphItem::section(BTreeMap::default()),
             )
             .unwrap();
         engine
             .create(
+                "kjuulh",
+                &["templates", "release"],
+                GraphItem::section(BTreeMap::default()),
+            )
+            .unwrap();
+        let mut item = GraphItem::item(
+            "Tag {{version}}".into(),
+            "ask {{ owner }}".into(),
+            ItemState::Done,
+        );
+        if let GraphItem::Item { due, .. } = &mut item {
+            *due = Some("+3d".into());
+        }
+        engine
+            .create("kjuulh", &["templates", "release", "tag-{{version}}"], item)
+            .unwrap();
+
+        let vars = [("version", "v1"), ("owner", "kjuulh")]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .into();
+        engine
+            .apply_template(
+                "kjuulh",
+                "release",
+                &["some-section", "release-{{version}}"],
+                &vars,
+                Some("2026-10-18"),
+            )
+            .unwrap();
+        assert!(engine
+            .apply_template(
+                "kjuulh",
+                "release",
+                &["some-section", "other"],
+                &BTreeMap::new(),
+                None
+            )
+            .is_err());
+        assert!(engine
+            .apply_template("kjuulh", "missing", &["some-section", "other"], &vars, None)
+            .is_err());
+
+        let mut expected =
+            GraphItem::item("Tag v1".into(), "ask kjuulh".into(), ItemState::NotDone);
+        if let GraphItem::Item { due, .. } = &mut expected {
+            *due = Some("2026-10-21".into());
+        }
+        assert_eq!(
+            Some(&expected),
+            engine.get("kjuulh", &["some-section", "release-v1", "tag-v1"])
+        );
+        assert!(engine.get("kjuulh", &["some-section", "other"]).is_none());
+    }
+
+    #[test]
+    fn test_agenda_skips_done_and_undated_items() {
+        let mut engine = get_complex_graph();
+        engine.create_root("other").unwrap();
+        for (root, key, state, due) in [
+            ("kjuulh", "overdue", ItemState::NotDone, Some("2026-10-01")),
+            ("kjuulh", "done", ItemState::Done, Some("2026-10-01")),
+            ("other", "today", ItemState::NotDone, Some("2026-10-18")),
+            ("other", "undated", ItemState::NotDone, None),
+        ] {
+            let mut item = GraphItem::item(key.into(), String::new(), state);
+            if let GraphItem::Item { due: item_due, .. } = &mut item {
+                *item_due = due.map(Into::into);
+            }
+            engine.create(root, &[key], item).unwrap();
+        }
+        let today = hyperlog_core::due::parse_date("2026-10-18").unwrap();
+
+        let agenda = engine.agenda(None, today);
+        assert_eq!(
+            vec![vec!["overdue".to_string()]],
+            agenda
+                .overdue
+                .iter()
+                .map(|i| i.path.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, agenda.today.len());
+        assert!(agenda.this_week.is_empty() && agenda.later.is_empty());
+
+        assert!(engine.agenda(Some("kjuulh"), today).today.is_empty());
+    }
+
+    #[test]
+    fn test_tags_include_inline_ones_and_filter_search() {
+        let mut engine = get_complex_graph();
+        for (key, description, tags) in [
+            ("crash", "fails on #Bug report", vec![]),
+            ("typo", "", vec!["bug".to_string(), "@home".into()]),
+            ("groceries", "milk", vec!["@home".into()]),
+        ] {
+            let mut item = GraphItem::item(key.into(), description.into(), ItemState::NotDone);
+            if let GraphItem::Item {
+                tags: item_tags, ..
+            } = &mut item
+            {
+                *item_tags = tags;
+            }
+            engine.create("kjuulh", &[key], item).unwrap();
+        }
+
+        assert_eq!(
+            vec![("@home".to_string(), 2), ("bug".into(), 2)],
+            engine.tags(Some("kjuulh"))
+        );
+
+        let search = |text: &str, tag: Option<&str>| {
+            engine
+                .search(
+                    None,
+                    &Search {
+                        text: text.into(),
+                        tag: tag.map(Into::into),
+                    },
+                )
+                .into_iter()
+                .map(|hit| hit.path.join("."))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["crash", "typo"], search("", Some("#bug")));
+        assert_eq!(vec!["groceries"], search("MILK", Some("@home")));
+    }
+
+    #[test]
+    fn test_toggling_recurring_item_moves_to_next_occurrence() {
+        let mut engine = get_complex_graph();
+        let mut item = GraphItem::item("weekly-review".into(), String::new(), ItemState::NotDone);
+        if let GraphItem::Item { due, recur, .. } = &mut item {
+            *due = Some("2026-10-16".into());
+            *recur = Some("weekly:fri".into());
+        }
+        engine
+            .create("kjuulh", &["some-section", "weekly-review"], item.clone())
+            .unwrap();
+        let today = hyperlog_core::due::parse_date("2026-10-18").unwrap();
+
+        engine
+            .toggle_item("kjuulh", &["some-section", "weekly-review"], today)
+            .unwrap();
+
+        if let GraphItem::Item {
+            due, completions, ..
+        } = &mut item
+        {
+            *due = Some("2026-10-23".into());
+            *completions = vec!["2026-10-18".into()];
+        }
+        assert_eq!(
+            Some(&item),
+            engine.get("kjuulh", &["some-section", "weekly-review"])
+        );
+    }
+
+    #[test]
+    fn test_setting_recurring_item_done_moves_to_next_occurrence() {
+        let mut engine = get_complex_graph();
+        let path = ["some-section", "weekly-review"];
+        let mut item =
+            GraphItem::item("weekly-review".into(), String::new(), ItemState::InProgress);
+        if let GraphItem::Item { due, recur, .. } = &mut item {
+            *due = Some("2026-10-16".into());
+            *recur = Some("weekly:fri".into());
+        }
+        engine.create("kjuulh", &path, item).unwrap();
+        let today = hyperlog_core::due::parse_date("2026-10-18").unwrap();
+
+        engine
+            .set_item_state("kjuulh", &path, ItemState::Done, today)
+            .unwrap();
+
+        assert!(matches!(
+            engine.get("kjuulh", &path),
+            Some(GraphItem::Item {
+                state: ItemState::InProgress,
+                due: Some(due),
+                completions,
+                completed_at: None,
+                ..
+            }) if due == "2026-10-23" && completions == &["2026-10-18"]
+        ));
+    }
+
+    #[test]
+    fn test_toggling_blocked_item_closes_it() {
+        let mut engine = get_complex_graph();
+        let path = [
+            "some-section",
+            "some-sub-section",
+            "sub-sub-section",
+            "item",
+        ];
+        engine
+            .create(
+                "kjuulh",
+                &path,
+                GraphItem::item("item".into(), String::new(), ItemState::NotDone),
+            )
+            .unwrap();
+
+        engine
+            .set_item_state(
+                "kjuulh",
+                &path,
+                ItemState::Blocked {
+                    reason: "waiting on review".into(),
+                },
+                0,
+            )
+            .unwrap();
+        engine.toggle_item("kjuulh", &path, 0).unwrap();
+        assert!(matches!(
+            engine.get("kjuulh", &path),
+            Some(GraphItem::Item {
+                state: ItemState::Done,
+                ..
+            })
+        ));
+
+        engine
+            .set_item_state("kjuulh", &path, ItemState::Cancelled, 0)
+            .unwrap();
+        engine.toggle_item("kjuulh", &path, 0).unwrap();
+        assert!(matches!(
+            engine.get("kjuulh", &path),
+            Some(GraphItem::Item {
+                state: ItemState::NotDone,
+                ..
+            })
+        ));
+
+        assert!(engine
+            .set_item_state("kjuulh", &["some-section"], ItemState::Done, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_snoozing_sets_and_clears_the_start_date() {
+        let mut engine = get_complex_graph();
+        let path = [
+            "some-section",
+            "some-sub-section",
+            "sub-sub-section",
+            "item",
+        ];
+        engine
+            .create(
+                "kjuulh",
+                &path,
+                GraphItem::item("item".into(), String::new(), ItemState::NotDone),
+            )
+            .unwrap();
+
+        engine
+            .snooze_item("kjuulh", &path, Some("2026-11-02".into()))
+            .unwrap();
+        let start = |engine: &Engine| match engine.get("kjuulh", &path) {
+            Some(GraphItem::Item { start, .. }) => start.clone(),
+            _ => panic!("item was not found"),
+        };
+        assert_eq!(Some("2026-11-02".to_string()), start(&engine));
+
+        engine.snooze_item("kjuulh", &path, None).unwrap();
+        assert_eq!(None, start(&engine));
+
+        assert!(engine
+            .snooze_item("kjuulh", &["some-section"], None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_standup_files_items_by_state() {
+        let mut engine = get_complex_graph();
+        for (key, state) in [
+            ("working", ItemState::InProgress),
+            ("waiting", ItemState::NotDone),
+        ] {
+            engine
+                .create(
+                    "kjuulh",
+                    &["some-section", key],
+                    GraphItem::item("item".into(), String::new(), state),
+                )
+                .unwrap();
+        }
+        engine
+            .add_dependency(
+                "kjuulh",
+                &["some-section", "waiting"],
+                &["some-section", "working"],
+            )
+            .unwrap();
+
+        let standup = engine.standup(Some("kjuulh"), due::today());
+        let paths = |items: &[hyperlog_core::standup::StandupItem]| {
+            items.iter().map(|i| i.path.join(".")).collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["some-section.working"], paths(&standup.open));
+        assert_eq!(vec!["some-section.waiting"], paths(&standup.blocked));
+        assert_eq!(
+            Some("waiting on some-section.working".to_string()),
+            standup.blocked[0].detail
+        );
+        assert!(standup.done.is_empty());
+    }
+
+    #[test]
+    fn test_stats_count_the_subtree() {
+        let mut engine = get_complex_graph();
+        let today = due::today();
+        let path = [
+            "some-section",
+            "some-sub-section",
+            "sub-sub-section",
+            "some-item",
+        ];
+        engine.toggle_item("kjuulh", &path, today).unwrap();
+
+        let stats = engine
+            .stats("kjuulh", &["some-section"], today - 1, today)
+            .unwrap();
+        assert_eq!(vec!["some-section"], stats.sections[0].path);
+        assert_eq!(Some(100), stats.sections[0].counts.percent_done());
+        assert_eq!(1, stats.days[1].completed);
+        assert_eq!(
+            vec![1, 0],
+            stats.days.iter().map(|d| d.remaining).collect::<Vec<_>>()
+        );
+
+        assert!(engine.stats("kjuulh", &path, today, today).is_err());
+    }
+
+    #[test]
+    fn test_toggling_records_and_clears_completion() {
+        let mut engine = get_complex_graph();
+        let path = ["some-section", "item"];
+        engine
+            .create(
+                "kjuulh",
+                &path,
+                GraphItem::item("item".into(), String::new(), ItemState::NotDone),
+            )
+            .unwrap();
+        let today = due::today();
+
+        engine.toggle_item("kjuulh", &path, today).unwrap();
+        let completed = engine.completed(Some("kjuulh"), today, today);
+        assert_eq!(1, completed.items.len());
+        assert_eq!("item", completed.items[0].title);
+        assert_eq!(vec!["some-section", "item"], completed.items[0].path);
+
+        engine.toggle_item("kjuulh", &path, today).unwrap();
+        match engine.get("kjuulh", &path) {
+            Some(GraphItem::Item { completed_at, .. }) => assert_eq!(&None, completed_at),
+            _ => panic!("item was not found"),
+        }
+        assert!(engine.completed(None, today, today).items.is_empty());
+    }
+
+    #[test]
+    fn test_toggling_a_checklist_entry_leaves_the_item_open() {
+        let mut engine = get_complex_graph();
+        let path = ["some-section", "item"];
+        let mut item = GraphItem::item("item".into(), String::new(), ItemState::NotDone);
+        if let GraphItem::Item { checklist, .. } = &mut item {
+            *checklist = vec![ChecklistEntry::new("one"), ChecklistEntry::new("two")];
+        }
+        engine.create("kjuulh", &path, item).unwrap();
+
+        engine.toggle_checklist_entry("kjuulh", &path, 1).unwrap();
+        assert!(engine.toggle_checklist_entry("kjuulh", &path, 2).is_err());
+        match engine.get("kjuulh", &path) {
+            Some(GraphItem::Item {
+                state, checklist, ..
+            }) => {
+                assert_eq!(&ItemState::NotDone, state);
+                assert_eq!(Some("1/2".to_string()), checklist::summary(checklist));
+            }
+            _ => panic!("item was not found"),
+        }
+
+        engine
+            .copy("kjuulh", &path, "kjuulh", &["copy"], true, false)
+            .unwrap();
+        match engine.get("kjuulh", &["copy"]) {
+            Some(GraphItem::Item { checklist, .. }) => {
+                assert_eq!((0, 2), checklist::progress(checklist))
+            }
+            _ => panic!("copy was not found"),
+        }
+    }
+
+    #[test]
+    fn test_convert_between_item_and_section() {
+        let mut engine = get_complex_graph();
+        let path = ["some-section", "launch"];
+        engine
+            .create(
+                "kjuulh",
+                &path,
+                GraphItem::item(
+                    "Launch v1.0".into(),
+                    "Everything for the launch".into(),
+                    ItemState::InProgress,
+                ),
+            )
+            .unwrap();
+
+        engine.convert("kjuulh", &path, Kind::Section).unwrap();
+        match engine.get("kjuulh", &path) {
+            Some(GraphItem::Section { meta, items }) => {
+                assert_eq!("Launch v1.0", meta.title);
+                assert_eq!("Everything for the launch", meta.description);
+                assert!(items.is_empty());
+            }
+            _ => panic!("section was not found"),
+        }
+
+        engine
+            .create(
+                "kjuulh",
+                &["some-section", "launch", "docs"],
+                GraphItem::section(BTreeMap::default()),
+            )
+            .unwrap();
+        assert!(engine.convert("kjuulh", &path, Kind::Item).is_err());
+        engine
+            .delete("kjuulh", &["some-section", "launch", "docs"])
+            .unwrap();
+
+        engine.convert("kjuulh", &path, Kind::Item).unwrap();
+        match engine.get("kjuulh", &path) {
+            Some(GraphItem::Item {
+                title,
+                description,
+                state,
+                ..
+            }) => {
+                assert_eq!("Launch v1.0", title);
+                assert_eq!("Everything for the launch", description);
+                assert_eq!(&ItemState::NotDone, state);
+            }
+            _ => panic!("item was not found"),
+        }
+        assert!(engine.convert("kjuulh", &[], Kind::Section).is_err());
+    }
+
+    #[test]
+    fn test_update_section_replaces_its_metadata() {
+        let mut engine = get_complex_graph();
+        let path = ["some-section"];
+        let meta = SectionMeta {
+            title: "Some section".into(),
+            description: "Things to get to".into(),
+            colour: "#ff8800".into(),
+            icon: "*".into(),
+            collapsed: true,
+        };
+
+        engine
+            .update_section("kjuulh", &path, meta.clone())
+            .unwrap();
+        match engine.get("kjuulh", &path) {
+            Some(GraphItem::Section {
+                meta: actual,
+                items,
+            }) => {
+                assert_eq!(&meta, actual);
+                assert!(!items.is_empty());
+            }
+            _ => panic!("section was not found"),
+        }
+
+        let bad_colour = SectionMeta {
+            colour: "orange".into(),
+            ..Default::default()
+        };
+        assert!(engine.update_section("kjuulh", &path, bad_colour).is_err());
+        assert!(engine
+            .update_section("kjuulh", &["missing"], SectionMeta::default())
+            .is_err());
+        assert!(engine
+            .update_section(
                 "kjuulh",
                 &[
                     "some-section",
@@ -753,11 +2342,164 @@ mod test {
                     "sub-sub-section",
                     "some-item",
                 ],
-                GraphItem::Item {
-                    title: "some-title".to_string(),
-                    description: "some-description".to_string(),
-                    state: ItemState::NotDone,
-                },
+                SectionMeta::default(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_dependencies_reject_cycles_and_follow_renames() {
+        let mut engine = get_complex_graph();
+        for key in ["design", "build"] {
+            engine
+                .create(
+                    "kjuulh",
+                    &["some-section", key],
+                    GraphItem::item(key.into(), String::new(), ItemState::NotDone),
+                )
+                .unwrap();
+        }
+
+        engine
+            .add_dependency(
+                "kjuulh",
+                &["some-section", "build"],
+                &["some-section", "design"],
+            )
+            .unwrap();
+        assert!(engine
+            .add_dependency(
+                "kjuulh",
+                &["some-section", "design"],
+                &["some-section", "build"],
+            )
+            .is_err());
+        assert!(engine
+            .add_dependency("kjuulh", &["some-section", "build"], &["missing"])
+            .is_err());
+
+        let deps = Dependencies::from_graph(engine.get("kjuulh", &[]).unwrap());
+        assert!(deps.is_blocked("some-section.build"));
+
+        engine
+            .rename("kjuulh", &["some-section"], "project", false)
+            .unwrap();
+        let Some(GraphItem::Item { depends_on, .. }) = engine.get("kjuulh", &["project", "build"])
+        else {
+            panic!("build should still be an item");
+        };
+        assert_eq!(&vec!["project.design".to_string()], depends_on);
+
+        engine
+            .remove_dependency("kjuulh", &["project", "build"], &["project", "design"])
+            .unwrap();
+        let deps = Dependencies::from_graph(engine.get("kjuulh", &[]).unwrap());
+        assert!(!deps.is_blocked("project.build"));
+    }
+
+    #[test]
+    fn test_moving_across_roots_drops_dependencies_across_the_subtree() {
+        let mut engine = get_complex_graph();
+        engine.create_root("other").unwrap();
+        engine
+            .create(
+                "other",
+                &["archive"],
+                GraphItem::section(BTreeMap::default()),
+            )
+            .unwrap();
+        for path in [
+            &["kickoff"][..],
+            &["launch"],
+            &["some-section", "design"],
+            &["some-section", "build"],
+        ] {
+            engine
+                .create(
+                    "kjuulh",
+                    path,
+                    GraphItem::item(path.join("."), String::new(), ItemState::NotDone),
+                )
+                .unwrap();
+        }
+        for (item, dependency) in [
+            (&["some-section", "design"][..], &["kickoff"][..]),
+            (&["some-section", "build"], &["some-section", "design"]),
+            (&["launch"], &["some-section", "build"]),
+        ] {
+            engine.add_dependency("kjuulh", item, dependency).unwrap();
+        }
+        let depends_on = |engine: &Engine, root: &str, path: &[&str]| match engine.get(root, path) {
+            Some(GraphItem::Item { depends_on, .. }) => depends_on.clone(),
+            _ => panic!("{root}.{path:?} should be an item"),
+        };
+
+        engine
+            .copy(
+                "kjuulh",
+                &["some-section"],
+                "other",
+                &["copy"],
+                false,
+                false,
+            )
+            .unwrap();
+        assert!(depends_on(&engine, "other", &["copy", "design"]).is_empty());
+        assert_eq!(
+            vec!["copy.design"],
+            depends_on(&engine, "other", &["copy", "build"])
+        );
+
+        engine
+            .section_move("kjuulh", &["some-section"], "other", &["archive"])
+            .unwrap();
+        assert!(depends_on(&engine, "other", &["archive", "some-section", "design"]).is_empty());
+        assert_eq!(
+            vec!["archive.some-section.design"],
+            depends_on(&engine, "other", &["archive", "some-section", "build"])
+        );
+        assert!(depends_on(&engine, "kjuulh", &["launch"]).is_empty());
+    }
+
+    fn get_complex_graph() -> Engine {
+        let mut engine = Engine::default();
+
+        engine.create_root("kjuulh").unwrap();
+        engine
+            .create(
+                "kjuulh",
+                &["some-section"],
+                GraphItem::section(BTreeMap::default()),
+            )
+            .unwrap();
+        engine
+            .create(
+                "kjuulh",
+                &["some-section", "some-sub-section"],
+                GraphItem::section(BTreeMap::default()),
+            )
+            .unwrap();
+        engine
+            .create(
+                "kjuulh",
+                &["some-section", "some-sub-section", "sub-sub-section"],
+                GraphItem::section(BTreeMap::default()),
+            )
+            .unwrap();
+        engine
+            .create(
+                "kjuulh",
+                &[
+                    "some-section",
+                    "some-sub-section",
+                    "sub-sub-section",
+                    "some-item",
+                ],
+                GraphItem::item(
+                    "some-title".to_string(),
+                    "some-description".to_string(),
+                    ItemState::NotDone,
+                ),
             )
             .unwrap();
 
@@ -1,4 +1,4 @@
-use hyperlog_core::log::GraphItem;
+use hyperlog_core::{agenda::Agenda, log::GraphItem};
 
 use crate::commands::{Command, IntoCommand};
 
@@ -14,6 +14,9 @@ pub enum Msg {
     OpenEditItemDialog { item: GraphItem },
     OpenEditor { item: GraphItem },
     Interact,
+    Yank,
+    Put,
+    CycleState,
 
     EnterInsertMode,
     EnterViewMode,
@@ -28,11 +31,21 @@ pub enum Msg {
     ItemUpdated(IOEvent<()>),
     SectionCreated(IOEvent<()>),
     ItemToggled(IOEvent<()>),
+    ItemStateSet(IOEvent<()>),
+    ItemSnoozed(IOEvent<()>),
+    Converted(IOEvent<()>),
+    SectionUpdated(IOEvent<()>),
+    DependenciesChanged(IOEvent<()>),
     Archive(IOEvent<()>),
+    Renamed(IOEvent<()>),
+    Copied(IOEvent<()>),
+    TemplateApplied(IOEvent<()>),
 
     OpenUpdateItemDialog(IOEvent<()>),
 
     OpenItem(IOEvent<()>),
+
+    OpenAgenda(IOEvent<Agenda>),
 }
 
 #[derive(Debug)]
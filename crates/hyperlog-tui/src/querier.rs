@@ -1,4 +1,11 @@
-use hyperlog_core::log::GraphItem;
+use hyperlog_core::{
+    agenda::Agenda,
+    completed::Completed,
+    log::GraphItem,
+    search::{Search, SearchHit},
+    standup::Standup,
+    stats::Stats,
+};
 use tonic::transport::Channel;
 
 use crate::shared_engine::SharedEngine;
@@ -52,6 +59,69 @@ impl Querier {
         }
     }
 
+    /// Not-done items with a due date in `root`, or in every root when `None`.
+    pub async fn agenda_async(&self, root: Option<&str>) -> anyhow::Result<Agenda> {
+        match &self.variant {
+            QuerierVariant::Local(querier) => Ok(querier.agenda(root)),
+            QuerierVariant::Remote(querier) => querier.agenda(root).await,
+        }
+    }
+
+    /// Items completed between `since` and `until`, inclusive days since
+    /// 1970-01-01, in `root` or in every root when `None`.
+    pub async fn completed_async(
+        &self,
+        root: Option<&str>,
+        since: i64,
+        until: i64,
+    ) -> anyhow::Result<Completed> {
+        match &self.variant {
+            QuerierVariant::Local(querier) => Ok(querier.completed(root, since, until)),
+            QuerierVariant::Remote(querier) => querier.completed(root, since, until).await,
+        }
+    }
+
+    /// Today's standup over `root`, or over every root when `None`.
+    pub async fn standup_async(&self, root: Option<&str>) -> anyhow::Result<Standup> {
+        match &self.variant {
+            QuerierVariant::Local(querier) => Ok(querier.standup(root)),
+            QuerierVariant::Remote(querier) => querier.standup(root).await,
+        }
+    }
+
+    /// Progress of the root or section at `path` and the sections below it,
+    /// day by day from `since` (days since 1970-01-01) through today.
+    pub async fn stats_async(
+        &self,
+        root: &str,
+        path: &[&str],
+        since: i64,
+    ) -> anyhow::Result<Stats> {
+        match &self.variant {
+            QuerierVariant::Local(querier) => querier.stats(root, path, since),
+            QuerierVariant::Remote(querier) => querier.stats(root, path, since).await,
+        }
+    }
+
+    /// Tags in use in `root`, or in every root when `None`, most used first.
+    pub async fn tags_async(&self, root: Option<&str>) -> anyhow::Result<Vec<(String, i64)>> {
+        match &self.variant {
+            QuerierVariant::Local(querier) => Ok(querier.tags(root)),
+            QuerierVariant::Remote(querier) => querier.tags(root).await,
+        }
+    }
+
+    pub async fn search_async(
+        &self,
+        root: Option<&str>,
+        search: &Search,
+    ) -> anyhow::Result<Vec<SearchHit>> {
+        match &self.variant {
+            QuerierVariant::Local(querier) => Ok(querier.search(root, search)),
+            QuerierVariant::Remote(querier) => querier.search(root, search).await,
+        }
+    }
+
     pub fn get_available_roots(&self) -> Option<Vec<String>> {
         match &self.variant {
             QuerierVariant::Local(querier) => querier.get_available_roots(),
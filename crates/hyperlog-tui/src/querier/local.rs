@@ -1,4 +1,12 @@
-use hyperlog_core::log::GraphItem;
+use hyperlog_core::{
+    agenda::Agenda,
+    completed::Completed,
+    due,
+    log::GraphItem,
+    search::{Search, SearchHit},
+    standup::Standup,
+    stats::Stats,
+};
 
 use crate::shared_engine::SharedEngine;
 
@@ -18,6 +26,30 @@ impl Querier {
         self.engine.get_roots()
     }
 
+    pub fn agenda(&self, root: Option<&str>) -> Agenda {
+        self.engine.agenda(root, due::today())
+    }
+
+    pub fn completed(&self, root: Option<&str>, since: i64, until: i64) -> Completed {
+        self.engine.completed(root, since, until)
+    }
+
+    pub fn standup(&self, root: Option<&str>) -> Standup {
+        self.engine.standup(root, due::today())
+    }
+
+    pub fn stats(&self, root: &str, path: &[&str], since: i64) -> anyhow::Result<Stats> {
+        self.engine.stats(root, path, since, due::today())
+    }
+
+    pub fn tags(&self, root: Option<&str>) -> Vec<(String, i64)> {
+        self.engine.tags(root)
+    }
+
+    pub fn search(&self, root: Option<&str>, search: &Search) -> Vec<SearchHit> {
+        self.engine.search(root, search)
+    }
+
     pub fn get(
         &self,
         root: &str,
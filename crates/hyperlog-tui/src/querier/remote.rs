@@ -1,8 +1,19 @@
 use std::collections::BTreeMap;
 
-use hyperlog_core::log::GraphItem;
+use anyhow::Context;
+use hyperlog_core::{
+    agenda::{Agenda, AgendaItem},
+    completed::{Completed, CompletedItem},
+    due,
+    log::{GraphItem, SectionMeta},
+    search::{Search, SearchHit},
+    standup::{Standup, StandupItem},
+    stats::{Counts, DayStats, SectionStats, Stats},
+};
 use hyperlog_protos::hyperlog::{
-    graph_client::GraphClient, graph_item::Contents, GetAvailableRootsRequest, GetRequest,
+    graph_client::GraphClient, graph_item::Contents, AgendaRequest, CompletedRequest,
+    GetAvailableRootsRequest, GetRequest, GetStatsRequest, ListTagsRequest, SearchRequest,
+    StandupRequest,
 };
 use itertools::Itertools;
 use tonic::transport::Channel;
@@ -36,6 +47,200 @@ impl Querier {
         }
     }
 
+    pub async fn agenda(&self, root: Option<&str>) -> anyhow::Result<Agenda> {
+        let channel = self.channel.clone();
+
+        let mut client = GraphClient::new(channel);
+
+        let request = tonic::Request::new(AgendaRequest {
+            root: root.unwrap_or_default().into(),
+            today: String::new(),
+        });
+        let response = client.agenda(request).await?;
+        let res = response.into_inner();
+
+        let items = |items: Vec<hyperlog_protos::hyperlog::AgendaItem>| {
+            items
+                .into_iter()
+                .map(|i| AgendaItem {
+                    root: i.root,
+                    path: i.path,
+                    title: i.title,
+                    due: i.due,
+                })
+                .collect()
+        };
+        Ok(Agenda {
+            overdue: items(res.overdue),
+            today: items(res.today),
+            this_week: items(res.this_week),
+            later: items(res.later),
+        })
+    }
+
+    pub async fn completed(
+        &self,
+        root: Option<&str>,
+        since: i64,
+        until: i64,
+    ) -> anyhow::Result<Completed> {
+        let channel = self.channel.clone();
+
+        let mut client = GraphClient::new(channel);
+
+        let request = tonic::Request::new(CompletedRequest {
+            root: root.unwrap_or_default().into(),
+            since: due::format_date(since),
+            until: due::format_date(until),
+        });
+        let response = client.completed(request).await?;
+
+        Ok(Completed {
+            since,
+            until,
+            items: response
+                .into_inner()
+                .items
+                .into_iter()
+                .map(|i| CompletedItem {
+                    root: i.root,
+                    path: i.path,
+                    title: i.title,
+                    completed_at: i.completed_at,
+                })
+                .collect(),
+        })
+    }
+
+    pub async fn standup(&self, root: Option<&str>) -> anyhow::Result<Standup> {
+        let channel = self.channel.clone();
+
+        let mut client = GraphClient::new(channel);
+
+        let request = tonic::Request::new(StandupRequest {
+            root: root.unwrap_or_default().into(),
+            today: due::format_date(due::today()),
+        });
+        let response = client.standup(request).await?;
+        let res = response.into_inner();
+
+        let items = |items: Vec<hyperlog_protos::hyperlog::StandupItem>| {
+            items
+                .into_iter()
+                .map(|i| StandupItem {
+                    root: i.root,
+                    path: i.path,
+                    title: i.title,
+                    detail: Some(i.detail).filter(|d| !d.is_empty()),
+                })
+                .collect()
+        };
+        Ok(Standup {
+            today: due::parse_date(&res.today).context("standup today is not a date")?,
+            since: due::parse_date(&res.since).context("standup since is not a date")?,
+            done: items(res.done),
+            open: items(res.open),
+            overdue: items(res.overdue),
+            blocked: items(res.blocked),
+        })
+    }
+
+    pub async fn stats(&self, root: &str, path: &[&str], since: i64) -> anyhow::Result<Stats> {
+        let channel = self.channel.clone();
+
+        let mut client = GraphClient::new(channel);
+
+        let today = due::today();
+        let request = tonic::Request::new(GetStatsRequest {
+            root: root.into(),
+            path: path.iter().map(|p| p.to_string()).collect(),
+            since: due::format_date(since),
+            today: due::format_date(today),
+        });
+        let response = client.get_stats(request).await?;
+        let res = response.into_inner();
+
+        Ok(Stats {
+            since,
+            today,
+            sections: res
+                .sections
+                .into_iter()
+                .map(|s| SectionStats {
+                    path: s.path,
+                    counts: Counts {
+                        total: s.total,
+                        done: s.done,
+                        overdue: s.overdue,
+                    },
+                })
+                .collect(),
+            days: res
+                .days
+                .into_iter()
+                .map(|d| {
+                    Ok(DayStats {
+                        date: due::parse_date(&d.date).context("stats date is not a date")?,
+                        created: d.created,
+                        completed: d.completed,
+                        remaining: d.remaining,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+
+    pub async fn tags(&self, root: Option<&str>) -> anyhow::Result<Vec<(String, i64)>> {
+        let channel = self.channel.clone();
+
+        let mut client = GraphClient::new(channel);
+
+        let request = tonic::Request::new(ListTagsRequest {
+            root: root.unwrap_or_default().into(),
+        });
+        let response = client.list_tags(request).await?;
+
+        Ok(response
+            .into_inner()
+            .tags
+            .into_iter()
+            .map(|t| (t.tag, t.count))
+            .collect())
+    }
+
+    pub async fn search(
+        &self,
+        root: Option<&str>,
+        search: &Search,
+    ) -> anyhow::Result<Vec<SearchHit>> {
+        let channel = self.channel.clone();
+
+        let mut client = GraphClient::new(channel);
+
+        let request = tonic::Request::new(SearchRequest {
+            root: root.unwrap_or_default().into(),
+            query: search.text.clone(),
+            tag: search.tag.clone().unwrap_or_default(),
+        });
+        let response = client.search(request).await?;
+
+        Ok(response
+            .into_inner()
+            .items
+            .into_iter()
+            .filter_map(|i| {
+                let (root, path) = i.path.split_first()?;
+                Some(SearchHit {
+                    root: root.clone(),
+                    path: path.to_vec(),
+                    title: i.title,
+                    done: i.done,
+                    tags: i.tags,
+                })
+            })
+            .collect())
+    }
+
     pub async fn get(
         &self,
         root: &str,
@@ -54,9 +259,12 @@ impl Querier {
 
         let mut client = GraphClient::new(channel);
 
+        // Deferred items are hidden by the graph explorer, which can toggle them.
         let request = tonic::Request::new(GetRequest {
             root: root.into(),
             paths,
+            include_deferred: true,
+            today: String::new(),
         });
 
         let response = client.get(request).await?;
@@ -95,24 +303,56 @@ fn transform_proto_to_local(input: &hyperlog_protos::hyperlog::GraphItem) -> Opt
                     }
                 }
 
-                Some(GraphItem::Section(items))
+                Some(GraphItem::Section {
+                    meta: SectionMeta {
+                        title: section.title.clone(),
+                        description: section.description.clone(),
+                        colour: section.colour.clone(),
+                        icon: section.icon.clone(),
+                        collapsed: section.collapsed,
+                    },
+                    items,
+                })
             }
             Contents::Item(item) => Some(GraphItem::Item {
                 title: item.title.clone(),
                 description: item.description.clone(),
-                state: match &item.item_state {
-                    Some(state) => match state {
-                        hyperlog_protos::hyperlog::item_graph_item::ItemState::NotDone(_) => {
-                            hyperlog_core::log::ItemState::NotDone
-                        }
-                        hyperlog_protos::hyperlog::item_graph_item::ItemState::Done(_) => {
-                            hyperlog_core::log::ItemState::Done
-                        }
-                    },
-                    None => hyperlog_core::log::ItemState::NotDone,
-                },
+                state: to_core_state(item.item_state.as_ref()),
+                due: Some(item.due.clone()).filter(|d| !d.is_empty()),
+                start: Some(item.start.clone()).filter(|s| !s.is_empty()),
+                recur: Some(item.recur.clone()).filter(|r| !r.is_empty()),
+                completions: item.completions.clone(),
+                completed_at: Some(item.completed_at.clone()).filter(|c| !c.is_empty()),
+                depends_on: item.depends_on.clone(),
+                tags: item.tags.clone(),
+                priority: item.priority.parse().ok(),
+                checklist: item
+                    .checklist
+                    .iter()
+                    .map(|c| hyperlog_core::checklist::ChecklistEntry {
+                        title: c.title.clone(),
+                        done: c.done,
+                    })
+                    .collect(),
             }),
         },
         None => None,
     }
 }
+
+fn to_core_state(
+    state: Option<&hyperlog_protos::hyperlog::item_graph_item::ItemState>,
+) -> hyperlog_core::log::ItemState {
+    use hyperlog_core::log::ItemState;
+    use hyperlog_protos::hyperlog::item_graph_item::ItemState as ProtoState;
+
+    match state {
+        Some(ProtoState::NotDone(_)) | None => ItemState::NotDone,
+        Some(ProtoState::InProgress(_)) => ItemState::InProgress,
+        Some(ProtoState::Blocked(blocked)) => ItemState::Blocked {
+            reason: blocked.reason.clone(),
+        },
+        Some(ProtoState::Done(_)) => ItemState::Done,
+        Some(ProtoState::Cancelled(_)) => ItemState::Cancelled,
+    }
+}
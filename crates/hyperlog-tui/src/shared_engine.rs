@@ -1,6 +1,17 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
 
-use hyperlog_core::log::GraphItem;
+use hyperlog_core::{
+    agenda::Agenda,
+    completed::Completed,
+    convert::Kind,
+    log::{GraphItem, ItemState, SectionMeta},
+    search::{Search, SearchHit},
+    standup::Standup,
+    stats::Stats,
+};
 
 use crate::engine::Engine;
 
@@ -26,6 +37,18 @@ impl SharedEngine {
         self.inner.write().unwrap().create_root(root)
     }
 
+    pub fn rename_root(&self, root: &str, new_name: &str) -> anyhow::Result<()> {
+        self.inner.write().unwrap().rename_root(root, new_name)
+    }
+
+    pub fn delete_root(&self, root: &str) -> anyhow::Result<GraphItem> {
+        self.inner.write().unwrap().delete_root(root)
+    }
+
+    pub fn restore_root(&self, root: &str, item: GraphItem) -> anyhow::Result<()> {
+        self.inner.write().unwrap().restore_root(root, item)
+    }
+
     pub fn create(&self, root: &str, path: &[&str], item: GraphItem) -> anyhow::Result<()> {
         self.inner.write().unwrap().create(root, path, item)
     }
@@ -38,20 +61,168 @@ impl SharedEngine {
         &self,
         root: &str,
         src_path: &[&str],
+        dest_root: &str,
+        dest_path: &[&str],
+    ) -> anyhow::Result<()> {
+        self.inner
+            .write()
+            .unwrap()
+            .section_move(root, src_path, dest_root, dest_path)
+    }
+
+    pub fn rename(
+        &self,
+        root: &str,
+        path: &[&str],
+        new_key: &str,
+        update_links: bool,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .write()
+            .unwrap()
+            .rename(root, path, new_key, update_links)
+    }
+
+    pub fn copy(
+        &self,
+        root: &str,
+        src_path: &[&str],
+        dest_root: &str,
+        dest_path: &[&str],
+        reset_state: bool,
+        drop_due: bool,
+    ) -> anyhow::Result<()> {
+        self.inner.write().unwrap().copy(
+            root,
+            src_path,
+            dest_root,
+            dest_path,
+            reset_state,
+            drop_due,
+        )
+    }
+
+    pub fn apply_template(
+        &self,
+        root: &str,
+        name: &str,
         dest_path: &[&str],
+        vars: &BTreeMap<String, String>,
+        anchor: Option<&str>,
     ) -> anyhow::Result<()> {
         self.inner
             .write()
             .unwrap()
-            .section_move(root, src_path, dest_path)
+            .apply_template(root, name, dest_path, vars, anchor)
+    }
+
+    pub fn agenda(&self, root: Option<&str>, today: i64) -> Agenda {
+        self.inner.read().unwrap().agenda(root, today)
+    }
+
+    pub fn completed(&self, root: Option<&str>, since: i64, until: i64) -> Completed {
+        self.inner.read().unwrap().completed(root, since, until)
+    }
+
+    pub fn standup(&self, root: Option<&str>, today: i64) -> Standup {
+        self.inner.read().unwrap().standup(root, today)
+    }
+
+    pub fn stats(
+        &self,
+        root: &str,
+        path: &[&str],
+        since: i64,
+        today: i64,
+    ) -> anyhow::Result<Stats> {
+        self.inner.read().unwrap().stats(root, path, since, today)
     }
 
-    pub fn toggle_item(&self, root: &str, path: &[&str]) -> anyhow::Result<()> {
-        self.inner.write().unwrap().toggle_item(root, path)?;
+    pub fn tags(&self, root: Option<&str>) -> Vec<(String, i64)> {
+        self.inner.read().unwrap().tags(root)
+    }
+
+    pub fn search(&self, root: Option<&str>, search: &Search) -> Vec<SearchHit> {
+        self.inner.read().unwrap().search(root, search)
+    }
+
+    pub fn toggle_item(&self, root: &str, path: &[&str], today: i64) -> anyhow::Result<()> {
+        self.inner.write().unwrap().toggle_item(root, path, today)?;
 
         Ok(())
     }
 
+    pub fn toggle_checklist_entry(
+        &self,
+        root: &str,
+        path: &[&str],
+        index: usize,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .write()
+            .unwrap()
+            .toggle_checklist_entry(root, path, index)
+    }
+
+    pub fn set_item_state(
+        &self,
+        root: &str,
+        path: &[&str],
+        state: ItemState,
+        today: i64,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .write()
+            .unwrap()
+            .set_item_state(root, path, state, today)
+    }
+
+    pub fn convert(&self, root: &str, path: &[&str], kind: Kind) -> anyhow::Result<()> {
+        self.inner.write().unwrap().convert(root, path, kind)
+    }
+
+    pub fn update_section(
+        &self,
+        root: &str,
+        path: &[&str],
+        meta: SectionMeta,
+    ) -> anyhow::Result<()> {
+        self.inner.write().unwrap().update_section(root, path, meta)
+    }
+
+    pub fn snooze_item(
+        &self,
+        root: &str,
+        path: &[&str],
+        start: Option<String>,
+    ) -> anyhow::Result<()> {
+        self.inner.write().unwrap().snooze_item(root, path, start)
+    }
+
+    pub fn add_dependency(
+        &self,
+        root: &str,
+        path: &[&str],
+        dependency: &[&str],
+    ) -> anyhow::Result<()> {
+        self.inner
+            .write()
+            .unwrap()
+            .add_dependency(root, path, dependency)
+    }
+
+    pub fn remove_dependency(
+        &self,
+        root: &str,
+        path: &[&str],
+        dependency: &[&str],
+    ) -> anyhow::Result<()> {
+        self.inner
+            .write()
+            .unwrap()
+            .remove_dependency(root, path, dependency)
+    }
+
     pub(crate) fn update_item(
         &self,
         root: &str,
@@ -1,11 +1,24 @@
 use std::{
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use hyperlog_core::log::GraphItem;
+use serde::{Deserialize, Serialize};
+
 use crate::{engine::Engine, shared_engine::SharedEngine};
 
+/// How long a deleted root is kept in `deleted/` before it is purged.
+const DELETED_ROOT_GRACE: Duration = Duration::from_secs(30 * 86400);
+
+#[derive(Serialize, Deserialize)]
+struct DeletedRoot {
+    root: String,
+    deleted_at: u64,
+    item: GraphItem,
+}
+
 pub struct LockFile(PathBuf);
 
 impl Drop for LockFile {
@@ -143,6 +156,75 @@ impl Storage {
         anyhow::bail!("lock file exists and is valid. Aborting");
     }
 
+    fn deleted(&self) -> anyhow::Result<PathBuf> {
+        self.cache().map(|c| c.join("deleted"))
+    }
+
+    /// Keep a deleted root on disk for the grace period, and purge the ones
+    /// that have outlived it.
+    pub fn store_deleted_root(&self, root: &str, item: GraphItem) -> anyhow::Result<()> {
+        let deleted_at = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let dir = self.deleted()?;
+        std::fs::create_dir_all(&dir)?;
+
+        std::fs::write(
+            dir.join(format!("{}.json", deleted_at.as_nanos())),
+            serde_json::to_string_pretty(&DeletedRoot {
+                root: root.to_string(),
+                deleted_at: deleted_at.as_secs(),
+                item,
+            })?,
+        )?;
+
+        self.purge_deleted_roots(deleted_at.saturating_sub(DELETED_ROOT_GRACE))
+    }
+
+    /// Take back the most recently deleted root named `root`, if it is still kept.
+    pub fn take_deleted_root(&self, root: &str) -> anyhow::Result<Option<GraphItem>> {
+        let latest = self
+            .deleted_roots()?
+            .into_iter()
+            .filter(|(_, deleted)| deleted.root == root)
+            .max_by_key(|(_, deleted)| deleted.deleted_at);
+
+        match latest {
+            Some((path, deleted)) => {
+                std::fs::remove_file(path)?;
+                Ok(Some(deleted.item))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn purge_deleted_roots(&self, before: Duration) -> anyhow::Result<()> {
+        for (path, deleted) in self.deleted_roots()? {
+            if deleted.deleted_at < before.as_secs() {
+                tracing::info!("purging deleted root: {}", deleted.root);
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn deleted_roots(&self) -> anyhow::Result<Vec<(PathBuf, DeletedRoot)>> {
+        let dir = self.deleted()?;
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut deleted = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let contents = std::fs::read_to_string(&path)?;
+                deleted.push((path, serde_json::from_str(&contents)?));
+            }
+        }
+
+        Ok(deleted)
+    }
+
     fn cache(&self) -> anyhow::Result<PathBuf> {
         Ok(self.base.join("hyperlog"))
     }
@@ -265,4 +347,26 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn deleted_roots_are_kept_until_purged() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let mut storage = Storage::default();
+        storage.with_base(tempdir.path());
+
+        storage.store_deleted_root("some-root", GraphItem::User(BTreeMap::default()))?;
+        assert_eq!(None, storage.take_deleted_root("other-root")?);
+        assert_eq!(
+            Some(GraphItem::User(BTreeMap::default())),
+            storage.take_deleted_root("some-root")?
+        );
+        assert_eq!(None, storage.take_deleted_root("some-root")?);
+
+        storage.store_deleted_root("some-root", GraphItem::User(BTreeMap::default()))?;
+        storage.purge_deleted_roots(Duration::from_secs(u64::MAX))?;
+        assert_eq!(None, storage.take_deleted_root("some-root")?);
+
+        Ok(())
+    }
 }
@@ -47,6 +47,66 @@ enum Commands {
         #[command(subcommand)]
         commands: QueryCommands,
     },
+    /// Templates are sections kept under a root's `templates` section
+    Template {
+        #[command(subcommand)]
+        commands: TemplateCommands,
+    },
+    /// Not-done items with a due date: overdue, today, this week and later
+    Agenda {
+        /// Only this root, instead of all of them
+        #[arg(long)]
+        root: Option<String>,
+    },
+    Report {
+        #[command(subcommand)]
+        commands: ReportCommands,
+    },
+    /// Items completed in a date range, most recent first
+    Done {
+        /// Only this root, instead of all of them
+        #[arg(long)]
+        root: Option<String>,
+
+        /// First day: YYYY-MM-DD, today, yesterday, or -Nd/-Nw
+        #[arg(long, default_value = "-7d")]
+        since: String,
+
+        /// Last day, defaults to today
+        #[arg(long)]
+        until: Option<String>,
+    },
+    /// Done and overdue per section, and a burndown of what remained open
+    Stats {
+        #[arg(long)]
+        root: String,
+
+        /// Dot-separated section, instead of the whole root
+        #[arg(long)]
+        path: Option<String>,
+
+        /// First day: YYYY-MM-DD, today, yesterday, or -Nd/-Nw
+        #[arg(long, default_value = "-14d")]
+        since: String,
+    },
+    /// Tags in use, inline #tags included, with how many items carry each
+    Tags {
+        /// Only this root, instead of all of them
+        #[arg(long)]
+        root: Option<String>,
+    },
+    /// Items whose title or description contains the query
+    Search {
+        /// Only this root, instead of all of them
+        #[arg(long)]
+        root: Option<String>,
+
+        /// Only items carrying this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        query: Vec<String>,
+    },
     Info {},
 
     CreateRoot {
@@ -54,6 +114,25 @@ enum Commands {
         name: String,
     },
 
+    RenameRoot {
+        #[arg(long)]
+        root: String,
+
+        #[arg(long)]
+        name: String,
+    },
+
+    /// Deleted roots are kept for a grace period and can be brought back with restore-root
+    DeleteRoot {
+        #[arg(long)]
+        root: String,
+    },
+
+    RestoreRoot {
+        #[arg(long)]
+        root: String,
+    },
+
     ClearLock {},
 }
 
@@ -73,6 +152,40 @@ enum ExecCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum TemplateCommands {
+    /// Instantiate a template as a new section at path
+    Apply {
+        #[arg(long = "root")]
+        root: String,
+
+        #[arg(long = "template")]
+        template: String,
+
+        /// Dot-separated path of the new section, may use {{variables}}
+        #[arg(long = "path")]
+        path: String,
+
+        /// Variable as name=value, may be repeated
+        #[arg(long = "var", value_parser = hyperlog_core::template::parse_var)]
+        vars: Vec<(String, String)>,
+
+        /// YYYY-MM-DD that relative dues such as +3d count from, defaults to today
+        #[arg(long = "anchor")]
+        anchor: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportCommands {
+    /// Done since the last working day, still open, overdue and blocked, as markdown
+    Standup {
+        /// Only this root, instead of all of them
+        #[arg(long)]
+        root: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum QueryCommands {
     Get {
@@ -161,6 +274,34 @@ pub async fn execute() -> anyhow::Result<()> {
                 }
             }
         }
+        Some(Commands::Template { commands }) => {
+            let state = State::new(backend).await?;
+            match commands {
+                TemplateCommands::Apply {
+                    root,
+                    template,
+                    path,
+                    vars,
+                    anchor,
+                } => {
+                    state
+                        .commander
+                        .execute(commander::Command::ApplyTemplate {
+                            root,
+                            template: template.clone(),
+                            dest: path
+                                .split('.')
+                                .map(|s| s.to_string())
+                                .filter(|s| !s.is_empty())
+                                .collect::<Vec<String>>(),
+                            vars: vars.into_iter().collect(),
+                            anchor,
+                        })
+                        .await?;
+                    println!("Template {} was applied at {}", template, path);
+                }
+            }
+        }
         Some(Commands::CreateRoot { name }) => {
             let state = State::new(backend).await?;
             state
@@ -169,6 +310,146 @@ pub async fn execute() -> anyhow::Result<()> {
                 .await?;
             println!("Root was successfully created, now run:\n\n$ hyperlog");
         }
+        Some(Commands::RenameRoot { root, name }) => {
+            let state = State::new(backend).await?;
+            state
+                .commander
+                .execute(commander::Command::RenameRoot {
+                    root: root.clone(),
+                    new_name: name.clone(),
+                })
+                .await?;
+            println!("Root {} was renamed to {}", root, name);
+        }
+        Some(Commands::DeleteRoot { root }) => {
+            let state = State::new(backend).await?;
+            state
+                .commander
+                .execute(commander::Command::DeleteRoot { root: root.clone() })
+                .await?;
+            println!(
+                "Root {} was deleted, undo with:\n\n$ hyperlog restore-root --root {}",
+                root, root
+            );
+        }
+        Some(Commands::RestoreRoot { root }) => {
+            let state = State::new(backend).await?;
+            state
+                .commander
+                .execute(commander::Command::RestoreRoot { root: root.clone() })
+                .await?;
+            println!("Root {} was restored", root);
+        }
+        Some(Commands::Agenda { root }) => {
+            let state = State::new(backend).await?;
+            let agenda = state.querier.agenda_async(root.as_deref()).await?;
+            for (heading, items) in agenda.buckets() {
+                println!("{} ({})", heading, items.len());
+                for item in items {
+                    println!(
+                        "  {}  {}  ({}: {})",
+                        item.due,
+                        item.title,
+                        item.root,
+                        item.path.join(".")
+                    );
+                }
+            }
+        }
+        Some(Commands::Report { commands }) => {
+            let state = State::new(backend).await?;
+            match commands {
+                ReportCommands::Standup { root } => {
+                    let standup = state.querier.standup_async(root.as_deref()).await?;
+                    print!("{}", standup.to_markdown());
+                }
+            }
+        }
+        Some(Commands::Done { root, since, until }) => {
+            let today = hyperlog_core::due::today();
+            let parse = |raw: &str| {
+                hyperlog_core::completed::parse_since(raw, today)
+                    .ok_or_else(|| anyhow::anyhow!("invalid date: {}", raw))
+            };
+            let since = parse(&since)?;
+            let until = until.as_deref().map(parse).transpose()?.unwrap_or(today);
+
+            let state = State::new(backend).await?;
+            let completed = state
+                .querier
+                .completed_async(root.as_deref(), since, until)
+                .await?;
+            println!(
+                "Done {} to {} ({})",
+                hyperlog_core::due::format_date(since),
+                hyperlog_core::due::format_date(until),
+                completed.items.len()
+            );
+            for item in completed.items {
+                println!(
+                    "  {}  {}  ({}: {})",
+                    item.completed_at,
+                    item.title,
+                    item.root,
+                    item.path.join(".")
+                );
+            }
+        }
+        Some(Commands::Stats { root, path, since }) => {
+            let since = hyperlog_core::completed::parse_since(&since, hyperlog_core::due::today())
+                .ok_or_else(|| anyhow::anyhow!("invalid date: {}", since))?;
+            let path = path.unwrap_or_default();
+            let path = path
+                .split('.')
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>();
+
+            let state = State::new(backend).await?;
+            let stats = state.querier.stats_async(&root, &path, since).await?;
+            let depth = path.len();
+            for section in &stats.sections {
+                let counts = section.counts;
+                println!(
+                    "{}{}  {}/{} done ({}%)  {} overdue",
+                    "  ".repeat(section.path.len() - depth),
+                    section.path.last().unwrap_or(&root),
+                    counts.done,
+                    counts.total,
+                    counts.percent_done().unwrap_or(0),
+                    counts.overdue
+                );
+            }
+            println!();
+            print!("{}", stats.burndown(40));
+        }
+        Some(Commands::Tags { root }) => {
+            let state = State::new(backend).await?;
+            for (tag, count) in state.querier.tags_async(root.as_deref()).await? {
+                println!("{:>5}  {}", count, hyperlog_core::tags::display(&tag));
+            }
+        }
+        Some(Commands::Search { root, tag, query }) => {
+            let state = State::new(backend).await?;
+            let search = hyperlog_core::search::Search {
+                text: query.join(" "),
+                tag,
+            };
+            for hit in state.querier.search_async(root.as_deref(), &search).await? {
+                let tags = hit
+                    .tags
+                    .iter()
+                    .map(|t| hyperlog_core::tags::display(t))
+                    .collect::<Vec<_>>();
+                println!(
+                    "[{}] {}  ({}: {})  {}",
+                    if hit.done { "x" } else { " " },
+                    hit.title,
+                    hit.root,
+                    hit.path.join("."),
+                    tags.join(" ")
+                );
+            }
+        }
         Some(Commands::Info {}) => {
             let state = State::new(backend).await?;
             if let Some(info) = state.info() {